@@ -82,7 +82,9 @@ export default function App() {
     let response = go(
       `${maxDepth}`,
       serialise_board(`${boardValue.us} ${boardValue.them} ${boardValue.share}`),
-      player)
+      player,
+      undefined,
+      undefined)
     if (response[0] === "error") {
       if (response[1] === "depth") {
         if (response[2] === "invalid") {
@@ -95,9 +97,10 @@ export default function App() {
       }
     } else if (response[0] === "info") {
       if (response[1] === "depth") {
-        let dpt = Number(response[2])
-        let pv = response.slice(4, 4 + dpt)
-        let evaluation = response[4 + dpt + 1]
+        // Fields are found by keyword, as the engine reports more of them over time.
+        let evalIndex = response.indexOf("eval")
+        let pv = response.slice(response.indexOf("pv") + 1, evalIndex)
+        let evaluation = response[evalIndex + 1]
         setAnalysis(`Eval: ${ evaluation }, PV: ${ pv.map(x => x.toUpperCase()).join(", ") }`)
       }
     }
@@ -0,0 +1,47 @@
+// Generates the evaluation tables at build time when `embedded-tables` is enabled,
+// reusing the exact same code that generates them at runtime,
+// and the C header for the `capi` feature.
+
+use std::{env, fs, path::Path};
+
+#[allow(dead_code)]
+#[path = "src/engine.rs"]
+mod engine;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/engine.rs");
+
+    #[cfg(feature = "capi")]
+    generate_header();
+
+    if env::var_os("CARGO_FEATURE_EMBEDDED_TABLES").is_none() {
+        return;
+    }
+
+    // Both tables are written consecutively as little-endian `i32`s.
+    let (large, small) = engine::init();
+    let bytes: Vec<u8> = large
+        .iter()
+        .chain(small.iter())
+        .flat_map(|eval| eval.to_le_bytes())
+        .collect();
+
+    let out_dir = env::var_os("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("tables.bin"), bytes).unwrap();
+}
+
+// Writes `include/uttt.h` for the C interface, which only depends on `src/capi.rs`.
+#[cfg(feature = "capi")]
+fn generate_header() {
+    println!("cargo:rerun-if-changed=src/capi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let config = cbindgen::Config::from_file(Path::new(&crate_dir).join("cbindgen.toml")).unwrap();
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(Path::new(&crate_dir).join("src/capi.rs"))
+        .generate()
+        .expect("failed to generate the C header")
+        .write_to_file(Path::new(&crate_dir).join("include/uttt.h"));
+}
@@ -0,0 +1,66 @@
+/* Generated by cbindgen from src/capi.rs when building with the `capi` feature. */
+
+#ifndef UTTT_H
+#define UTTT_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define UTTT_OK 0
+
+#define UTTT_ERROR_ARGUMENT -1
+
+#define UTTT_ERROR_BOARD -2
+
+#define UTTT_ERROR_DEPTH -3
+
+#define UTTT_ERROR_ABORTED -4
+
+#define UTTT_ERROR_BUFFER -5
+
+#define UTTT_NO_MOVE 81
+
+typedef struct UtttEngine UtttEngine;
+
+typedef bool (*UtttStopCallback)(void *user_data);
+
+typedef void (*UtttProgressCallback)(void *user_data, uint32_t depth, uint32_t mv, uint32_t number);
+
+typedef struct {
+  uint32_t best_move;
+  uint32_t ponder_move;
+  uint32_t depth;
+  uint32_t seldepth;
+  int32_t eval;
+  uint64_t nodes;
+} UtttSearchResult;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+UtttEngine *uttt_engine_new(uint32_t hash_mb);
+
+void uttt_engine_free(UtttEngine *engine);
+
+int uttt_engine_set_position(UtttEngine *engine, const char *board, bool x_to_move);
+
+void uttt_engine_new_game(UtttEngine *engine);
+
+int uttt_engine_search(UtttEngine *engine,
+                       uint32_t depth,
+                       UtttStopCallback stop,
+                       UtttProgressCallback progress,
+                       void *user_data,
+                       UtttSearchResult *result);
+
+int uttt_move_string(uint32_t mv, char *buffer, size_t length);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* UTTT_H */
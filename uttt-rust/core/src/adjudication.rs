@@ -0,0 +1,129 @@
+use crate::{
+    engine::{winning_cells, zone_cells, Board, Eval},
+    game::{Game, GameResult},
+};
+
+/**
+ * Rules for ending a game early, once one side is clearly lost
+ * or neither side is making progress. Scores are relative to the side
+ * that has just moved, as reported by its search before the move.
+ * Setting either number of moves to zero disables that kind of adjudication.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AdjudicationRules {
+    // A side resigns once its score has been at most `-resign_score`
+    // for `resign_moves` of its moves in a row.
+    pub resign_score: Eval,
+    pub resign_moves: usize,
+    // A game is drawn once every score has been within `draw_score` of zero
+    // for `draw_moves` recorded moves in a row, provided that at least
+    // `draw_min_ply` moves have been played and few threats remain (see `threat_count`).
+    pub draw_score: Eval,
+    pub draw_moves: usize,
+    pub draw_min_ply: usize,
+    pub draw_max_threats: u32,
+}
+
+impl Default for AdjudicationRules {
+    fn default() -> Self {
+        AdjudicationRules {
+            resign_score: 600,
+            resign_moves: 3,
+            draw_score: 10,
+            draw_moves: 8,
+            draw_min_ply: 30,
+            draw_max_threats: 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Adjudication {
+    // The given side (`false` for X) resigns.
+    Resign(bool),
+    Draw,
+}
+
+impl Adjudication {
+    pub fn result(self) -> GameResult {
+        match self {
+            Adjudication::Resign(false) => GameResult::OWins,
+            Adjudication::Resign(true) => GameResult::XWins,
+            Adjudication::Draw => GameResult::Draw,
+        }
+    }
+}
+
+// The number of empty cells in zones still in play which would complete a line
+// of either player, as a measure of how much is still being fought over.
+pub fn threat_count(board: Board) -> u32 {
+    let large = (board.2 >> 36) | (board.2 >> 45);
+    (0..9)
+        .filter(|&zone| (large >> zone) & 1 == 0)
+        .map(|zone| {
+            let (x_cells, o_cells) = zone_cells(board, zone);
+            ((winning_cells(x_cells) | winning_cells(o_cells)) & !(x_cells | o_cells)).count_ones()
+        })
+        .sum()
+}
+
+/**
+ * Follows the scores of a game as it is played, deciding when it should be adjudicated.
+ * The website's bot only records its own scores, in which case the draw rule
+ * counts the bot's moves rather than the moves of both sides.
+ */
+#[derive(Clone, Debug)]
+pub struct Adjudicator {
+    pub rules: AdjudicationRules,
+    // The number of losing scores in a row of X and O respectively.
+    resign_streaks: [usize; 2],
+    draw_streak: usize,
+}
+
+impl Adjudicator {
+    pub fn new(rules: AdjudicationRules) -> Self {
+        Adjudicator {
+            rules,
+            resign_streaks: [0; 2],
+            draw_streak: 0,
+        }
+    }
+
+    /**
+     * Records the score of a move by `side` (`false` for X), relative to that side,
+     * given the board after the move and the number of moves played in total.
+     * Returns the adjudication if the game should now end.
+     */
+    pub fn record(
+        &mut self,
+        side: bool,
+        score: Eval,
+        board: Board,
+        ply: usize,
+    ) -> Option<Adjudication> {
+        let rules = self.rules;
+        let streak = &mut self.resign_streaks[side as usize];
+        *streak = if score <= -rules.resign_score { *streak + 1 } else { 0 };
+        if rules.resign_moves > 0 && *streak >= rules.resign_moves {
+            return Some(Adjudication::Resign(side));
+        }
+
+        self.draw_streak = if score.abs() <= rules.draw_score { self.draw_streak + 1 } else { 0 };
+        if rules.draw_moves > 0
+            && self.draw_streak >= rules.draw_moves
+            && ply >= rules.draw_min_ply
+            && threat_count(board) <= rules.draw_max_threats
+        {
+            return Some(Adjudication::Draw);
+        }
+        None
+    }
+
+    // Records the score of the last move played in `game`, relative to the side that played it.
+    pub fn record_game(&mut self, game: &Game, score: Eval) -> Option<Adjudication> {
+        if game.moves().is_empty() {
+            return None;
+        }
+        self.record(!game.side_to_move(), score, game.board(), game.moves().len())
+    }
+}
@@ -0,0 +1,278 @@
+use serde::Serialize;
+
+use crate::{
+    board_string,
+    engine::{
+        generate_moves, mark_counts, play_move, Board, Eval, EvalParams, Move, NULL_MOVE,
+        OUTCOME_WIN,
+    },
+    eval_string,
+    game::Game,
+    highlights::{highlights, Highlights},
+    log::pv_strings,
+    move_string,
+    options::ScorePerspective,
+    search::{alpha_beta, iterative_deepening, root_move_lines, SearchContext, SearchResult},
+    tt::TranspositionTable,
+};
+
+// Score losses, relative to the best move, at which a move is annotated
+// as dubious (`?!`), a mistake (`?`) or a blunder (`??`).
+// Throwing away a forced win, or walking into a forced loss, is always a blunder.
+pub const INACCURACY_LOSS: Eval = 50;
+pub const MISTAKE_LOSS: Eval = 150;
+pub const BLUNDER_LOSS: Eval = 400;
+
+// Scores this close to a win are forced wins (see `eval_string`).
+const FORCED_WIN: Eval = OUTCOME_WIN - 100;
+
+/**
+ * The annotation symbol of a move, derived from the score it lost
+ * relative to the best move, and from whether any other move was playable.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Annotation {
+    // The only move that does not lose significantly, and it forces a win.
+    Brilliant,
+    // The only move that does not lose significantly.
+    Good,
+    Dubious,
+    Mistake,
+    Blunder,
+}
+
+impl Annotation {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Annotation::Brilliant => "!!",
+            Annotation::Good => "!",
+            Annotation::Dubious => "?!",
+            Annotation::Mistake => "?",
+            Annotation::Blunder => "??",
+        }
+    }
+}
+
+// The default margin by which every alternative to an only move must lose.
+pub const ONLY_MOVE_MARGIN: Eval = MISTAKE_LOSS;
+
+// The best of the scored moves (sorted best first), if every other move loses at least
+// `margin` against it. A move is not counted as an only move when it is the only legal one.
+pub fn only_move(scores: &[(Move, Eval)], margin: Eval) -> Option<Move> {
+    match scores {
+        [(best_move, best), (_, second), ..] if best.saturating_sub(*second) >= margin => {
+            Some(*best_move)
+        }
+        _ => None,
+    }
+}
+
+// Whether the best of the scored moves is an only move by `ONLY_MOVE_MARGIN`.
+pub fn is_only_move(scores: &[(Move, Eval)]) -> bool {
+    only_move(scores, ONLY_MOVE_MARGIN).is_some()
+}
+
+/**
+ * Searches a position to `depth` plies, and decides whether its best move is an only move:
+ * one which every alternative loses at least `margin` against. Rather than scoring every
+ * move, as `root_move_scores` does, each alternative is searched with a null window just
+ * above the score it would need to keep, stopping at the first that keeps it, so that
+ * positions with many playable moves are settled quickly. Returns the search result with
+ * the only move, if there is one, or `None` if the search is stopped.
+ */
+pub fn find_only_move(
+    board: Board,
+    side: bool,
+    depth: usize,
+    margin: Eval,
+    ctx: &mut SearchContext,
+) -> Option<(SearchResult, Option<Move>)> {
+    let result = iterative_deepening(board, side, depth, ctx)?;
+    let best_move = result.pv[0];
+    if best_move == NULL_MOVE || generate_moves(board).count() < 2 {
+        return Some((result, None));
+    }
+    // An alternative keeps the score if it scores above `result.eval - margin`.
+    let threshold = result.eval.saturating_sub(margin);
+    ctx.max_depth = result.depth;
+    for mv in generate_moves(board).filter(|&mv| mv != best_move) {
+        let (eval, _) = alpha_beta(
+            play_move(board, mv, side),
+            !side,
+            result.depth - 1,
+            -threshold - 1,
+            -threshold,
+            ctx,
+        );
+        if ctx.stopped {
+            return None;
+        }
+        if -eval > threshold {
+            return Some((result, None));
+        }
+    }
+    Some((result, Some(best_move)))
+}
+
+/**
+ * Annotates the move `played` given the scores of all legal moves, sorted best first.
+ * Good moves are only marked when they are the only move, as there is
+ * nothing notable about finding the best of several equally good moves.
+ */
+pub fn annotate(scores: &[(Move, Eval)], played: Move) -> Option<Annotation> {
+    let &(best_move, best) = scores.first()?;
+    let score = scores.iter().find(|&&(mv, _)| mv == played)?.1;
+    let loss = best.saturating_sub(score);
+    if played == best_move || loss == 0 {
+        if played == best_move && is_only_move(scores) {
+            return Some(if best >= FORCED_WIN {
+                Annotation::Brilliant
+            } else {
+                Annotation::Good
+            });
+        }
+        return None;
+    }
+    if loss >= BLUNDER_LOSS || (best >= FORCED_WIN && score < FORCED_WIN) {
+        Some(Annotation::Blunder)
+    } else if loss >= MISTAKE_LOSS {
+        Some(Annotation::Mistake)
+    } else if loss >= INACCURACY_LOSS {
+        Some(Annotation::Dubious)
+    } else {
+        None
+    }
+}
+
+// The analysis of a single move of a game.
+#[derive(Serialize, Clone, Debug)]
+pub struct MoveAnalysis {
+    // The number of the move in the game, counting from 1.
+    pub ply: usize,
+    #[serde(rename = "move")]
+    pub played: String,
+    pub best: String,
+    pub depth: usize,
+    // The scores of the best and the played move, as formatted by `eval_string`.
+    pub eval: String,
+    pub played_eval: String,
+    // The same scores as numbers, relative to the side to move whatever the perspective.
+    pub score: Eval,
+    pub played_score: Eval,
+    // The principal variation of the best move, starting with it.
+    pub pv: Vec<String>,
+    // How much worse the played move scored than the best move.
+    pub loss: Eval,
+    pub annotation: Option<&'static str>,
+    pub only_move: bool,
+    // What to draw over the position before the move (see `highlights`).
+    pub highlights: Highlights,
+}
+
+/**
+ * Analyzes every move of a game with a search of `depth` plies from the position
+ * before it, sharing a fresh transposition table of `hash_mb` megabytes.
+ * The evaluation terms are those of `eval_params`.
+ * Scores follow `perspective`. Returns `None` if the search is stopped.
+ */
+pub fn analyze_game(
+    game: &Game,
+    depth: usize,
+    tables: &(Vec<Eval>, Vec<Eval>),
+    eval_params: EvalParams,
+    hash_mb: usize,
+    perspective: ScorePerspective,
+    stop_condition: Option<&dyn Fn() -> bool>,
+) -> Option<Vec<MoveAnalysis>> {
+    let mut tt = TranspositionTable::new(hash_mb);
+    let mut analysis = Vec::with_capacity(game.moves().len());
+    for (ply, &played) in game.moves().iter().enumerate() {
+        let board = game.board_at(ply)?;
+        let side = ply % 2 == 1;
+        tt.new_search();
+        let mut ctx = SearchContext::new(tables, depth);
+        ctx.eval_params = eval_params;
+        ctx.tt = Some(&mut tt);
+        ctx.stop_condition = stop_condition;
+        let lines = root_move_lines(board, side, depth, &mut ctx)?;
+        let scores: Vec<(Move, Eval)> = lines.iter().map(|&(mv, eval, _)| (mv, eval)).collect();
+
+        let (best_move, best) = scores[0];
+        let score = scores.iter().find(|&&(mv, _)| mv == played)?.1;
+        let annotation = annotate(&scores, played);
+        analysis.push(MoveAnalysis {
+            ply: ply + 1,
+            played: move_string(played),
+            best: move_string(best_move),
+            depth,
+            eval: eval_string(perspective.normalise(best, !side), depth),
+            played_eval: eval_string(perspective.normalise(score, !side), depth),
+            score: best,
+            played_score: score,
+            pv: pv_strings(&lines[0].2),
+            loss: best.saturating_sub(score),
+            annotation: annotation.map(Annotation::symbol),
+            only_move: played == best_move && is_only_move(&scores),
+            highlights: highlights(board, best_move, side),
+        });
+    }
+    Some(analysis)
+}
+
+// The analysis of a single position, on its own rather than as part of a game.
+#[derive(Serialize, Clone, Debug)]
+pub struct PositionAnalysis {
+    pub board: String,
+    // The side to move, `x` or `o`.
+    pub side: &'static str,
+    // The depth of the deepest completed iteration.
+    pub depth: usize,
+    pub bestmove: String,
+    // The score, as formatted by `eval_string`.
+    pub eval: String,
+    pub pv: Vec<String>,
+    pub nodes: u64,
+    pub highlights: Highlights,
+}
+
+/**
+ * Analyzes a position with a search of `depth` plies using the given table,
+ * and the evaluation terms of `eval_params`. The side to move is inferred from
+ * the number of marks, as X moves first. Scores follow `perspective`.
+ * Returns `None` if the search is stopped before its first iteration completes.
+ */
+pub fn analyze_position(
+    board: Board,
+    depth: usize,
+    tables: &(Vec<Eval>, Vec<Eval>),
+    eval_params: EvalParams,
+    tt: &mut TranspositionTable,
+    perspective: ScorePerspective,
+    stop_condition: Option<&dyn Fn() -> bool>,
+) -> Option<PositionAnalysis> {
+    let (x_count, o_count) = mark_counts(board);
+    let side = x_count > o_count;
+    tt.new_search();
+    let mut ctx = SearchContext::new(tables, depth);
+    ctx.eval_params = eval_params;
+    ctx.tt = Some(tt);
+    ctx.stop_condition = stop_condition;
+    let result = iterative_deepening(board, side, depth, &mut ctx)?;
+    let highlights = highlights(board, result.pv[0], side);
+    let pv: Vec<String> = result
+        .pv
+        .iter()
+        .take_while(|&&mv| mv != NULL_MOVE)
+        .map(|&mv| move_string(mv))
+        .collect();
+    Some(PositionAnalysis {
+        board: board_string(board),
+        side: if side { "o" } else { "x" },
+        depth: result.depth,
+        bestmove: pv.first().cloned().unwrap_or_else(|| "none".to_string()),
+        eval: eval_string(perspective.normalise(result.eval, !side), result.depth),
+        pv,
+        nodes: ctx.nodes,
+        highlights,
+    })
+}
@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{analysis::MoveAnalysis, engine::Eval, game::Game, move_string, versioning::Format};
+
+/*
+ * A saved game with the engine's analysis of its moves, so that a frontend can store an
+ * analysed game and show it again later without searching every position anew.
+ * It is written as a JSON object such as
+ *
+ *     {"format":"uttt-annotated-game","version":1,"moves":"c/c c/nw","analysis":[...]}
+ *
+ * where `moves` is the game's transcript and `analysis` holds an entry for each analysed
+ * move, in the form of the entries returned by `analyze_game` (of which only the fields
+ * of `SavedMove` are kept). Not every move need be analysed. Analysing the game again,
+ * perhaps more deeply, gives analysis to `merge` into what was saved.
+ */
+
+// The value of the `format` field.
+pub const FORMAT_NAME: &str = "uttt-annotated-game";
+
+// The saved analysis of a move. Scores are relative to the side to move.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SavedMove {
+    // The number of the move in the game, counting from 1.
+    pub ply: usize,
+    #[serde(rename = "move")]
+    pub played: String,
+    pub best: String,
+    pub depth: usize,
+    pub score: Eval,
+    pub played_score: Eval,
+    // The principal variation of the best move, starting with it.
+    pub pv: Vec<String>,
+    // The annotation symbol of the move, such as `?!`, if it has one.
+    pub annotation: Option<String>,
+}
+
+impl From<&MoveAnalysis> for SavedMove {
+    fn from(analysis: &MoveAnalysis) -> Self {
+        SavedMove {
+            ply: analysis.ply,
+            played: analysis.played.clone(),
+            best: analysis.best.clone(),
+            depth: analysis.depth,
+            score: analysis.score,
+            played_score: analysis.played_score,
+            pv: analysis.pv.clone(),
+            annotation: analysis.annotation.map(str::to_string),
+        }
+    }
+}
+
+// A game with the analysis of some or all of its moves, in order of ply.
+#[derive(Clone, Debug)]
+pub struct AnnotatedGame {
+    pub game: Game,
+    pub analysis: Vec<SavedMove>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonGame {
+    format: String,
+    version: u8,
+    moves: String,
+    analysis: Vec<SavedMove>,
+}
+
+impl AnnotatedGame {
+    /**
+     * Pairs a game with analysis of its moves, such as that returned by `analyze_game`.
+     * Entries may be in any order, but each must be of a distinct move of the game,
+     * naming the move that was played.
+     */
+    pub fn new(game: Game, mut analysis: Vec<SavedMove>) -> Result<Self, &'static str> {
+        analysis.sort_by_key(|saved| saved.ply);
+        if analysis.windows(2).any(|pair| pair[0].ply == pair[1].ply) {
+            return Err("ply repeated");
+        }
+        for saved in &analysis {
+            let played = saved.ply.checked_sub(1).and_then(|index| game.moves().get(index));
+            match played {
+                Some(&mv) if move_string(mv) == saved.played => {}
+                Some(_) => return Err("move mismatched"),
+                None => return Err("ply invalid"),
+            }
+        }
+        Ok(AnnotatedGame { game, analysis })
+    }
+
+    // The analysis of the move at `ply`, counting from 1, if it has been analysed.
+    pub fn move_analysis(&self, ply: usize) -> Option<&SavedMove> {
+        self.analysis.iter().find(|saved| saved.ply == ply)
+    }
+}
+
+// Writes an annotated game in the current version.
+pub fn annotated_string(annotated: &AnnotatedGame) -> String {
+    let json = JsonGame {
+        format: FORMAT_NAME.to_string(),
+        version: Format::AnnotatedGame.current_version(),
+        moves: annotated.game.transcript(),
+        analysis: annotated.analysis.clone(),
+    };
+    serde_json::to_string(&json).unwrap()
+}
+
+pub fn parse_annotated(text: &str) -> Result<AnnotatedGame, &'static str> {
+    let json: JsonGame = serde_json::from_str(text).map_err(|_| "annotated game invalid")?;
+    if json.format != FORMAT_NAME {
+        return Err("format unknown");
+    }
+    if json.version == 0 {
+        return Err("version invalid");
+    }
+    // No version has been superseded yet, so there is nothing to migrate.
+    if json.version > Format::AnnotatedGame.current_version() {
+        return Err("version unsupported");
+    }
+    AnnotatedGame::new(Game::from_transcript(&json.moves)?, json.analysis)
+}
+
+/**
+ * Combines saved analysis of a game with new analysis of the same game, keeping for each
+ * move whichever analysis searched deeper, and the new one where they are equally deep.
+ * Moves analysed by only one of them keep that analysis. Returns an error if the two
+ * are of different games.
+ */
+pub fn merge(
+    saved: &AnnotatedGame,
+    fresh: &AnnotatedGame,
+) -> Result<AnnotatedGame, &'static str> {
+    if saved.game.moves() != fresh.game.moves() {
+        return Err("games differ");
+    }
+    let analysis = (1..=saved.game.moves().len())
+        .filter_map(|ply| match (saved.move_analysis(ply), fresh.move_analysis(ply)) {
+            (Some(old), Some(new)) if old.depth > new.depth => Some(old.clone()),
+            (old, new) => new.or(old).cloned(),
+        })
+        .collect();
+    Ok(AnnotatedGame {
+        game: fresh.game.clone(),
+        analysis,
+    })
+}
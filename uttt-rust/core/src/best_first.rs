@@ -0,0 +1,202 @@
+use crate::{
+    engine::*,
+    search::STOP_CHECK_INTERVAL,
+};
+
+/*
+ * An experimental best-first minimax search, for comparison with the main alpha-beta
+ * search on this game. The whole tree searched is kept in memory: starting from the root,
+ * the principal variation is followed to its leaf, which is expanded by evaluating each
+ * of its children statically, and the new score is backed up towards the root as far as
+ * it changes anything. The search therefore grows the tree unevenly, deepest along the
+ * lines it currently believes in. It shares the evaluation and move generation with the
+ * main search, but none of its pruning or move ordering.
+ *
+ * As every node is kept (40 bytes each), this is not built for WebAssembly,
+ * where the memory would be better spent on the transposition table.
+ */
+
+// The number of nodes kept when no smaller limit is given, about 160 MiB.
+pub const DEFAULT_MAX_NODES: usize = 1 << 22;
+
+// A position in the tree. Scores are relative to the side to move at the node,
+// and decisive scores are adjusted by the node's distance from the root,
+// as in the main search.
+#[derive(Clone, Copy)]
+struct Node {
+    board: Board,
+    score: Eval,
+    parent: u32,
+    // The children are stored consecutively from `first_child`, once expanded.
+    first_child: u32,
+    child_count: u8,
+    mv: u8,
+    ply: u8,
+    // Whether the game is over at this node.
+    terminal: bool,
+}
+
+pub struct BestFirstResult {
+    pub eval: Eval,
+    pub pv: Vec<Move>,
+    // The number of nodes in the tree, and how many of them were expanded.
+    pub nodes: usize,
+    pub expansions: usize,
+}
+
+pub struct BestFirstSearch<'a> {
+    pub tables: &'a (Vec<Eval>, Vec<Eval>),
+    pub eval_params: EvalParams,
+    pub stopped: bool,
+    // Polled periodically during the search. Returning `true` aborts it,
+    // and the result of the tree grown so far is returned.
+    pub stop_condition: Option<&'a dyn Fn() -> bool>,
+    tree: Vec<Node>,
+    // The side to move at the root, from which that of every node follows.
+    root_side: bool,
+}
+
+impl<'a> BestFirstSearch<'a> {
+    pub fn new(tables: &'a (Vec<Eval>, Vec<Eval>)) -> Self {
+        BestFirstSearch {
+            tables,
+            eval_params: EvalParams::DEFAULT,
+            stopped: false,
+            stop_condition: None,
+            tree: Vec::new(),
+            root_side: false,
+        }
+    }
+
+    // The child of an expanded node which is best for the side to move there.
+    fn best_child(&self, node: usize) -> usize {
+        let Node {
+            first_child,
+            child_count,
+            ..
+        } = self.tree[node];
+        let children = first_child as usize..first_child as usize + child_count as usize;
+        children.max_by_key(|&child| -self.tree[child].score).unwrap()
+    }
+
+    // The static score of a new node, and whether the game is over there.
+    fn score(&self, board: Board, side: bool, ply: usize) -> (Eval, bool) {
+        if generate_moves(board).next().is_some() {
+            return (evaluate_with(board, side, self.tables, &self.eval_params), false);
+        }
+        // As in the main search, a finished game is scored by the large grid alone.
+        let eval = match self.tables.large(TableIndex::large_grid(board)) {
+            OUTCOME_WIN if side => OUTCOME_LOSS + ply as Eval,
+            OUTCOME_WIN => OUTCOME_WIN - ply as Eval,
+            OUTCOME_LOSS if side => OUTCOME_WIN - ply as Eval,
+            OUTCOME_LOSS => OUTCOME_LOSS + ply as Eval,
+            _ => OUTCOME_DRAW,
+        };
+        (eval, true)
+    }
+
+    // Adds the children of a leaf to the tree, each scored statically.
+    fn expand(&mut self, node: usize) {
+        let Node { board, ply, .. } = self.tree[node];
+        let side = self.root_side ^ (ply % 2 == 1);
+        let first_child = self.tree.len();
+        for mv in generate_moves(board) {
+            let child = play_move(board, mv, side);
+            let (score, terminal) = self.score(child, !side, ply as usize + 1);
+            self.tree.push(Node {
+                board: child,
+                score,
+                parent: node as u32,
+                first_child: 0,
+                child_count: 0,
+                mv: mv as u8,
+                ply: ply + 1,
+                terminal,
+            });
+        }
+        self.tree[node].first_child = first_child as u32;
+        self.tree[node].child_count = (self.tree.len() - first_child) as u8;
+    }
+
+    // Updates the scores of a newly expanded node and its ancestors,
+    // stopping at the first whose score is unchanged.
+    fn back_up(&mut self, mut node: usize) {
+        loop {
+            let score = -self.tree[self.best_child(node)].score;
+            if score == self.tree[node].score && node != 0 {
+                return;
+            }
+            self.tree[node].score = score;
+            if node == 0 {
+                return;
+            }
+            node = self.tree[node].parent as usize;
+        }
+    }
+
+    /**
+     * Searches a position, with `side` to move, until the tree holds `max_nodes` nodes,
+     * the principal variation ends in a finished game, or the search is stopped.
+     * Returns `None` if the game is already over.
+     */
+    pub fn search(
+        &mut self,
+        board: Board,
+        side: bool,
+        max_nodes: usize,
+    ) -> Option<BestFirstResult> {
+        generate_moves(board).next()?;
+        self.stopped = false;
+        self.tree.clear();
+        self.root_side = side;
+        self.tree.push(Node {
+            board,
+            score: 0,
+            parent: 0,
+            first_child: 0,
+            child_count: 0,
+            mv: NULL_MOVE as u8,
+            ply: 0,
+            terminal: false,
+        });
+        let mut expansions = 0;
+        loop {
+            let mut node = 0;
+            while self.tree[node].child_count > 0 {
+                node = self.best_child(node);
+            }
+            // A finished game at the end of the principal variation settles its score.
+            if self.tree[node].terminal {
+                break;
+            }
+            // An expansion adds at most 81 nodes.
+            if self.tree.len() + 81 > max_nodes && expansions > 0 {
+                break;
+            }
+            self.expand(node);
+            self.back_up(node);
+            expansions += 1;
+            if expansions % STOP_CHECK_INTERVAL as usize == 0 {
+                if let Some(stop_condition) = self.stop_condition {
+                    self.stopped = stop_condition();
+                }
+                if self.stopped {
+                    break;
+                }
+            }
+        }
+
+        let mut pv = Vec::new();
+        let mut node = 0;
+        while self.tree[node].child_count > 0 {
+            node = self.best_child(node);
+            pv.push(self.tree[node].mv as Move);
+        }
+        Some(BestFirstResult {
+            eval: self.tree[0].score,
+            pv,
+            nodes: self.tree.len(),
+            expansions,
+        })
+    }
+}
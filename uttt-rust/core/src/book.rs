@@ -0,0 +1,279 @@
+use std::{collections::HashMap, convert::TryInto};
+
+use crate::{
+    engine::{generate_moves, Board, Eval, Move, NULL_MOVE},
+    game::{Game, GameResult},
+    rng::Rng,
+    symmetry::{canonical_board, canonical_move, inverse, transform_move},
+    versioning::{migrate_bytes, Format, VersionError},
+    zobrist::{hash_board, Hash},
+};
+
+/*
+ * The book is saved as a byte buffer, so that a book built offline (see `uttt-rust book`)
+ * can be shipped to the browser as a static asset, and one learnt in the browser kept in
+ * its storage. The format is the magic bytes `UTBK`, a version byte (see `versioning`),
+ * a little-endian `u32` position count, and then for each position: the hash of its
+ * canonical form (`u64`), a move count (one byte, at least 1), and for each move
+ * the move in the orientation of the canonical form (one byte), followed by its weight
+ * and its win, draw and loss counters (`f32` each), all little-endian.
+ * Positions are written in order of their hash, so that the same book always gives
+ * the same bytes.
+ */
+const MAGIC: &[u8; 4] = b"UTBK";
+const HEADER_SIZE: usize = 9;
+const POSITION_HEADER_SIZE: usize = 9;
+const MOVE_SIZE: usize = 17;
+
+// Number of moves at the start of each game that are learnt.
+pub const LEARN_PLIES: usize = 12;
+
+// Factor by which all learnt results are scaled down before each new game
+// is learnt, so that recent games outweigh old ones. At this rate,
+// a result counts for half as much after about 350 further games.
+pub const LEARN_DECAY: f64 = 0.998;
+
+// The number of games (counting decayed results) a book move must have been played in
+// before its share of draws is trusted for contempt (see `Book::draw_penalties`).
+pub const CONTEMPT_MIN_GAMES: f64 = 4.0;
+
+// The share of draws above which a book move is penalised for contempt.
+// Moves drawn less often than this are left alone, as balanced rather than sterile.
+pub const CONTEMPT_DRAW_RATE: f64 = 0.5;
+
+/**
+ * A book move, stored in the orientation of the canonical form of its position.
+ * Besides a fixed weight, each move has counters of the results of the games
+ * it was played in, from the point of view of the side that played it.
+ * The counters decay over time, so they need not be whole numbers.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BookMove {
+    pub mv: Move,
+    pub weight: f64,
+    pub wins: f64,
+    pub draws: f64,
+    pub losses: f64,
+}
+
+impl BookMove {
+    fn new(mv: Move) -> Self {
+        BookMove {
+            mv,
+            weight: 1.0,
+            wins: 0.0,
+            draws: 0.0,
+            losses: 0.0,
+        }
+    }
+
+    // The expected score of the move, counting a draw as half a win.
+    // A prior of one win and one loss keeps rarely played moves near one half,
+    // so that a single loss does not rule a move out.
+    pub fn expected_score(&self) -> f64 {
+        (self.wins + self.draws / 2.0 + 1.0) / (self.wins + self.draws + self.losses + 2.0)
+    }
+
+    // The share of the games of the move that were drawn,
+    // once it has been played in enough of them to tell.
+    pub fn draw_rate(&self) -> Option<f64> {
+        let games = self.wins + self.draws + self.losses;
+        Some(self.draws / games).filter(|_| games >= CONTEMPT_MIN_GAMES)
+    }
+
+    // The relative probability of choosing the move.
+    // Moves which keep losing become ever less likely to be chosen.
+    pub fn selection_weight(&self) -> f64 {
+        self.weight * self.expected_score()
+    }
+}
+
+/**
+ * An opening book which learns from finished games.
+ * Positions are keyed by the hash of their canonical form,
+ * so that symmetric positions share their moves and results.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct Book {
+    positions: HashMap<Hash, Vec<BookMove>>,
+}
+
+impl Book {
+    pub fn new() -> Self {
+        Book::default()
+    }
+
+    // The number of positions in the book.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.positions.clear();
+    }
+
+    // Scales down every learnt result by `factor`.
+    pub fn decay(&mut self, factor: f64) {
+        for book_move in self.positions.values_mut().flatten() {
+            book_move.wins *= factor;
+            book_move.draws *= factor;
+            book_move.losses *= factor;
+        }
+    }
+
+    /**
+     * Learns from the first `LEARN_PLIES` moves of a finished game, crediting each move
+     * with the result for the side that played it. Moves not yet in the book are added.
+     * Earlier results are decayed first, by `LEARN_DECAY`.
+     */
+    pub fn learn(&mut self, game: &Game, result: GameResult) {
+        self.decay(LEARN_DECAY);
+        for (ply, &mv) in game.moves().iter().enumerate().take(LEARN_PLIES) {
+            let board = match game.board_at(ply) {
+                Some(board) => board,
+                None => break,
+            };
+            let (canonical, canonical_mv) = canonical_move(board, mv);
+            let moves = self.positions.entry(hash_board(canonical)).or_default();
+            let index = match moves.iter().position(|book_move| book_move.mv == canonical_mv) {
+                Some(index) => index,
+                None => {
+                    moves.push(BookMove::new(canonical_mv));
+                    moves.len() - 1
+                }
+            };
+            let book_move = &mut moves[index];
+            // X moves at even plies.
+            match (result, ply % 2 == 1) {
+                (GameResult::Draw, _) => book_move.draws += 1.0,
+                (GameResult::XWins, false) | (GameResult::OWins, true) => book_move.wins += 1.0,
+                _ => book_move.losses += 1.0,
+            }
+        }
+    }
+
+    // The book moves of a position, transformed back into its orientation.
+    pub fn moves(&self, board: Board) -> Vec<BookMove> {
+        let (canonical, transform) = canonical_board(board);
+        self.positions
+            .get(&hash_board(canonical))
+            .map_or_else(Vec::new, |moves| {
+                moves
+                    .iter()
+                    .map(|&book_move| BookMove {
+                        mv: transform_move(book_move.mv, inverse(transform)),
+                        ..book_move
+                    })
+                    .collect()
+            })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut keys: Vec<&Hash> = self.positions.keys().collect();
+        keys.sort_unstable();
+        let mut bytes = Vec::with_capacity(HEADER_SIZE);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(Format::Book.current_version());
+        bytes.extend_from_slice(&(keys.len() as u32).to_le_bytes());
+        for key in keys {
+            let moves = &self.positions[key];
+            bytes.extend_from_slice(&key.to_le_bytes());
+            bytes.push(moves.len() as u8);
+            for book_move in moves {
+                bytes.push(book_move.mv as u8);
+                for value in [book_move.weight, book_move.wins, book_move.draws, book_move.losses] {
+                    bytes.extend_from_slice(&(value as f32).to_le_bytes());
+                }
+            }
+        }
+        bytes
+    }
+
+    // Reads a book saved by `to_bytes`, or returns an error if it is malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        if bytes.len() < HEADER_SIZE || &bytes[..4] != MAGIC {
+            return Err("book invalid");
+        }
+        let payload = match migrate_bytes(Format::Book, bytes[4], &bytes[5..]) {
+            Ok(payload) => payload,
+            Err(VersionError::Unsupported) => return Err("book version unsupported"),
+            Err(VersionError::Malformed) => return Err("book invalid"),
+        };
+        let count = u32::from_le_bytes(payload[..4].try_into().unwrap()) as usize;
+        let mut book = Book::new();
+        let mut at = 4;
+        for _ in 0..count {
+            let header = payload.get(at..at + POSITION_HEADER_SIZE).ok_or("book invalid")?;
+            let key = Hash::from_le_bytes(header[..8].try_into().unwrap());
+            let move_count = header[8] as usize;
+            at += POSITION_HEADER_SIZE;
+            let body = payload.get(at..at + move_count * MOVE_SIZE).ok_or("book invalid")?;
+            let mut moves = Vec::with_capacity(move_count);
+            for chunk in body.chunks_exact(MOVE_SIZE) {
+                let value = |index: usize| {
+                    let start = 1 + 4 * index;
+                    f32::from_le_bytes(chunk[start..start + 4].try_into().unwrap()) as f64
+                };
+                let book_move = BookMove {
+                    mv: chunk[0] as Move,
+                    weight: value(0),
+                    wins: value(1),
+                    draws: value(2),
+                    losses: value(3),
+                };
+                let values = [book_move.weight, book_move.wins, book_move.draws, book_move.losses];
+                let counted = values.iter().all(|value| value.is_finite() && *value >= 0.0);
+                if book_move.mv >= NULL_MOVE || !counted {
+                    return Err("book invalid");
+                }
+                moves.push(book_move);
+            }
+            at += move_count * MOVE_SIZE;
+            if moves.is_empty() || book.positions.insert(key, moves).is_some() {
+                return Err("book invalid");
+            }
+        }
+        if at != payload.len() {
+            return Err("book invalid");
+        }
+        Ok(book)
+    }
+
+    /**
+     * Penalties for the legal moves of a position whose book games were heavily drawn,
+     * so that a search playing for a win can steer away from lines known to be sterile.
+     * A move drawn more often than `CONTEMPT_DRAW_RATE` loses a share of `contempt`
+     * rising to all of it for a move whose every game was drawn. Symmetric moves share
+     * their results, and so their penalties. Moves without a penalty are not listed.
+     */
+    pub fn draw_penalties(&self, board: Board, contempt: Eval) -> Vec<(Move, Eval)> {
+        if contempt == 0 {
+            return Vec::new();
+        }
+        let moves = match self.positions.get(&hash_board(canonical_board(board).0)) {
+            Some(moves) => moves,
+            None => return Vec::new(),
+        };
+        generate_moves(board)
+            .filter_map(|mv| {
+                let (_, canonical_mv) = canonical_move(board, mv);
+                let book_move = moves.iter().find(|book_move| book_move.mv == canonical_mv)?;
+                let excess = book_move.draw_rate()? - CONTEMPT_DRAW_RATE;
+                let penalty = (contempt as f64 * excess / (1.0 - CONTEMPT_DRAW_RATE)).round();
+                Some((mv, penalty as Eval)).filter(|&(_, penalty)| penalty > 0)
+            })
+            .collect()
+    }
+
+    // Chooses a book move for a position at random, by selection weight,
+    // or returns `None` if the position is not in the book.
+    pub fn choose(&self, board: Board, rng: &mut Rng) -> Option<Move> {
+        let moves = self.moves(board);
+        let weights: Vec<f64> = moves.iter().map(BookMove::selection_weight).collect();
+        rng.choose_weighted(&weights).map(|index| moves[index].mv)
+    }
+}
@@ -0,0 +1,137 @@
+use serde::Serialize;
+
+use crate::{
+    adjudication::AdjudicationRules,
+    engine::Eval,
+    rng::Rng,
+    selfplay::{match_score, PlayerSettings},
+};
+
+/*
+ * Approximate ratings of the engine searching to each depth, so that frontends can
+ * describe the strength of their bot levels. Ratings are measured by `calibrate`,
+ * which plays a match between each depth and the next, and turns the score of each match
+ * into a rating difference as in the Elo system. The differences are chained from the
+ * shallowest depth, whose rating is fixed at `ANCHOR_RATING`, so the ratings only say
+ * how the depths compare with each other, and are on no external scale. Those found by
+ * the last calibration are kept in `DEPTH_RATINGS`, and reported by `engine_info`.
+ */
+
+// The rating given to the shallowest depth calibrated.
+pub const ANCHOR_RATING: f64 = 1000.0;
+
+// The rating of each depth, as measured by `uttt-rust calibrate --max-depth 8 --pairs 100`.
+pub const DEPTH_RATINGS: [(usize, u32); 8] = [
+    (1, 1000),
+    (2, 1191),
+    (3, 1359),
+    (4, 1504),
+    (5, 1641),
+    (6, 1766),
+    (7, 1885),
+    (8, 1987),
+];
+
+// The lowest rating of each description, strongest first.
+const LABELS: [(u32, &str); 6] = [
+    (2000, "master"),
+    (1800, "expert"),
+    (1600, "strong"),
+    (1400, "intermediate"),
+    (1200, "casual"),
+    (0, "beginner"),
+];
+
+// A depth with its rating and a description of its strength.
+#[derive(Serialize, Clone, Copy, Debug)]
+pub struct StrengthLevel {
+    pub depth: usize,
+    pub rating: u32,
+    pub label: &'static str,
+}
+
+// A description of the strength of a rating, from `beginner` to `master`.
+pub fn strength_label(rating: u32) -> &'static str {
+    LABELS.iter().find(|&&(min, _)| rating >= min).unwrap().1
+}
+
+// The strength of each calibrated depth, shallowest first.
+pub fn strength_levels() -> Vec<StrengthLevel> {
+    DEPTH_RATINGS
+        .iter()
+        .map(|&(depth, rating)| StrengthLevel {
+            depth,
+            rating,
+            label: strength_label(rating),
+        })
+        .collect()
+}
+
+/**
+ * The rating difference implied by a score (counting a draw as half a win) over `games`
+ * games. A clean sweep implies an infinite difference, so the score is kept at least half
+ * a game away from either extreme.
+ */
+pub fn rating_difference(score: f64, games: usize) -> f64 {
+    let margin = 0.5 / games.max(1) as f64;
+    let score = score.clamp(margin, 1.0 - margin);
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+// The measured rating of a depth, with the score it made against the depth before it.
+pub struct DepthRating {
+    pub depth: usize,
+    pub score: Option<f64>,
+    pub rating: f64,
+}
+
+/**
+ * Measures the rating of each depth in `depths`, which should be in increasing order,
+ * by playing `pairs` pairs of games between each depth and the one before it, from random
+ * openings of `opening_plies` moves. Adjudication and the solver are as in `MatchSettings`.
+ * `on_depth` is called as each rating is found, as deeper matches take a while.
+ */
+pub fn calibrate(
+    depths: &[usize],
+    players: PlayerSettings,
+    tables: &(Vec<Eval>, Vec<Eval>),
+    (pairs, opening_plies): (usize, usize),
+    (rules, solver_plies): (Option<AdjudicationRules>, usize),
+    rng: &mut Rng,
+    on_depth: &mut dyn FnMut(&DepthRating),
+) -> Vec<DepthRating> {
+    let mut ratings: Vec<DepthRating> = Vec::with_capacity(depths.len());
+    for &depth in depths {
+        let rating = match ratings.last() {
+            None => DepthRating {
+                depth,
+                score: None,
+                rating: ANCHOR_RATING,
+            },
+            Some(previous) => {
+                let deeper = PlayerSettings { depth, ..players };
+                let shallower = PlayerSettings {
+                    depth: previous.depth,
+                    ..players
+                };
+                let score = match_score(
+                    [deeper, shallower],
+                    [tables, tables],
+                    pairs,
+                    opening_plies,
+                    rules,
+                    solver_plies,
+                    rng,
+                );
+                DepthRating {
+                    depth,
+                    score: Some(score),
+                    rating: previous.rating + rating_difference(score, 2 * pairs),
+                }
+            }
+        };
+        on_depth(&rating);
+        ratings.push(rating);
+    }
+    ratings
+}
@@ -0,0 +1,238 @@
+/*
+ * A C interface to the engine, so that native apps (such as on iOS and Android)
+ * can use the same engine as the website. The header `include/uttt.h` is generated
+ * from this module by cbindgen whenever the crate is built with the `capi` feature.
+ *
+ * An engine is created with `uttt_engine_new` and must be released with
+ * `uttt_engine_free`. Each engine has its own transposition table and position,
+ * so separate engines may be used from separate threads.
+ * Functions returning `int` return `UTTT_OK` on success, or a negative error code.
+ */
+
+use std::{
+    ffi::CStr,
+    os::raw::{c_char, c_int, c_void},
+    ptr,
+};
+
+use crate::{
+    board_from_string, load_tables, move_string,
+    engine::{check_structure, correct_zone, Board, Eval, Move, EMPTY_BOARD, MAX_PLY},
+    search::{iterative_deepening, SearchContext},
+    tt::{TranspositionTable, MAX_HASH_MB, MIN_HASH_MB},
+};
+
+pub const UTTT_OK: c_int = 0;
+// A pointer argument was null, or a string was not valid UTF-8.
+pub const UTTT_ERROR_ARGUMENT: c_int = -1;
+// The board string could not be parsed, or describes an impossible position.
+pub const UTTT_ERROR_BOARD: c_int = -2;
+// The depth was zero or greater than the maximum depth.
+pub const UTTT_ERROR_DEPTH: c_int = -3;
+// The search was stopped before completing its first iteration.
+pub const UTTT_ERROR_ABORTED: c_int = -4;
+// The output buffer is too small for the string and its terminator.
+pub const UTTT_ERROR_BUFFER: c_int = -5;
+
+// The move value meaning "no move", as for the best move of a finished game.
+// This is `NULL_MOVE`, written out so that it appears in the header.
+pub const UTTT_NO_MOVE: u32 = 81;
+
+/**
+ * An engine with its own position and transposition table.
+ * This is opaque to C, which only handles it by pointer.
+ */
+pub struct UtttEngine {
+    tables: (Vec<Eval>, Vec<Eval>),
+    tt: TranspositionTable,
+    board: Board,
+    // The engine's side to move, `false` being X.
+    side: bool,
+}
+
+/**
+ * The outcome of a completed search. Moves are numbered `9 * zone + cell`,
+ * and the score is relative to the side to move.
+ */
+#[repr(C)]
+pub struct UtttSearchResult {
+    pub best_move: u32,
+    // The expected reply to the best move, or `UTTT_NO_MOVE` if there is none.
+    pub ponder_move: u32,
+    pub depth: u32,
+    pub seldepth: u32,
+    pub eval: i32,
+    pub nodes: u64,
+}
+
+/**
+ * Polled periodically during a search with the `user_data` given to the search.
+ * Returning `true` stops the search.
+ */
+pub type UtttStopCallback = Option<extern "C" fn(user_data: *mut c_void) -> bool>;
+
+/**
+ * Called before each root move is searched with the current depth,
+ * the move, and its 1-based position in the root move list.
+ */
+pub type UtttProgressCallback =
+    Option<extern "C" fn(user_data: *mut c_void, depth: u32, mv: u32, number: u32)>;
+
+/**
+ * Creates an engine with a transposition table of `hash_mb` megabytes
+ * (clamped to the supported range), set to the empty board with X to move.
+ */
+#[no_mangle]
+pub extern "C" fn uttt_engine_new(hash_mb: u32) -> *mut UtttEngine {
+    let megabytes = (hash_mb as usize).clamp(MIN_HASH_MB, MAX_HASH_MB);
+    let engine = UtttEngine {
+        tables: load_tables(),
+        tt: TranspositionTable::new(megabytes),
+        board: EMPTY_BOARD,
+        side: false,
+    };
+    Box::into_raw(Box::new(engine))
+}
+
+/**
+ * Releases an engine created by `uttt_engine_new`.
+ *
+ * # Safety
+ *
+ * `engine` must be null, or a pointer returned by `uttt_engine_new`
+ * which has not already been released.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn uttt_engine_free(engine: *mut UtttEngine) {
+    if !engine.is_null() {
+        drop(Box::from_raw(engine));
+    }
+}
+
+/**
+ * Sets the position to search from a board string, with X to move if `x_to_move`.
+ * A zone field pointing to a won or full zone is treated as allowing any zone.
+ *
+ * # Safety
+ *
+ * `engine` must be a live engine, and `board` a null-terminated string.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn uttt_engine_set_position(
+    engine: *mut UtttEngine,
+    board: *const c_char,
+    x_to_move: bool,
+) -> c_int {
+    if engine.is_null() || board.is_null() {
+        return UTTT_ERROR_ARGUMENT;
+    }
+    let board = match CStr::from_ptr(board).to_str() {
+        Ok(board) => board,
+        Err(_) => return UTTT_ERROR_ARGUMENT,
+    };
+    let b = match board_from_string(board) {
+        Some(b) => correct_zone(b),
+        None => return UTTT_ERROR_BOARD,
+    };
+    if check_structure(b).is_err() {
+        return UTTT_ERROR_BOARD;
+    }
+    let engine = &mut *engine;
+    engine.board = b;
+    engine.side = !x_to_move;
+    UTTT_OK
+}
+
+/**
+ * Clears the transposition table, as between unrelated games.
+ *
+ * # Safety
+ *
+ * `engine` must be a live engine.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn uttt_engine_new_game(engine: *mut UtttEngine) {
+    if let Some(engine) = engine.as_mut() {
+        engine.tt.clear();
+    }
+}
+
+/**
+ * Searches the current position to `depth` plies, writing the result of the deepest
+ * completed iteration to `result`. Either callback may be null. If the search is
+ * stopped, the result of the deepest completed iteration is still written,
+ * and `UTTT_ERROR_ABORTED` is only returned if no iteration completed.
+ *
+ * # Safety
+ *
+ * `engine` must be a live engine, and `result` must point to writable memory.
+ * The callbacks are called on the calling thread, before this function returns.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn uttt_engine_search(
+    engine: *mut UtttEngine,
+    depth: u32,
+    stop: UtttStopCallback,
+    progress: UtttProgressCallback,
+    user_data: *mut c_void,
+    result: *mut UtttSearchResult,
+) -> c_int {
+    if engine.is_null() || result.is_null() {
+        return UTTT_ERROR_ARGUMENT;
+    }
+    let depth = depth as usize;
+    if depth == 0 || depth > MAX_PLY {
+        return UTTT_ERROR_DEPTH;
+    }
+    let engine = &mut *engine;
+    let should_stop = || stop.is_some_and(|stop| stop(user_data));
+    let report_root_move = |depth: usize, mv: Move, number: usize| {
+        if let Some(progress) = progress {
+            progress(user_data, depth as u32, mv as u32, number as u32);
+        }
+    };
+    engine.tt.new_search();
+    let mut ctx = SearchContext::new(&engine.tables, depth);
+    ctx.stop_condition = Some(&should_stop);
+    ctx.observer = Some(&report_root_move);
+    ctx.tt = Some(&mut engine.tt);
+    match iterative_deepening(engine.board, engine.side, depth, &mut ctx) {
+        Some(r) => {
+            ptr::write(
+                result,
+                UtttSearchResult {
+                    best_move: r.pv[0] as u32,
+                    ponder_move: r.pv[1] as u32,
+                    depth: r.depth as u32,
+                    seldepth: r.seldepth as u32,
+                    eval: r.eval,
+                    nodes: ctx.nodes,
+                },
+            );
+            UTTT_OK
+        }
+        None => UTTT_ERROR_ABORTED,
+    }
+}
+
+/**
+ * Writes the name of a move (such as `ne/sw`, or `none`) to `buffer`
+ * as a null-terminated string of at most `length` bytes.
+ *
+ * # Safety
+ *
+ * `buffer` must point to at least `length` writable bytes.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn uttt_move_string(mv: u32, buffer: *mut c_char, length: usize) -> c_int {
+    if buffer.is_null() {
+        return UTTT_ERROR_ARGUMENT;
+    }
+    let name = move_string(mv as Move);
+    if name.len() >= length {
+        return UTTT_ERROR_BUFFER;
+    }
+    ptr::copy_nonoverlapping(name.as_ptr() as *const c_char, buffer, name.len());
+    *buffer.add(name.len()) = 0;
+    UTTT_OK
+}
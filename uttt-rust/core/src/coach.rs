@@ -0,0 +1,243 @@
+use serde::Serialize;
+
+use crate::{
+    analysis::{annotate, Annotation, INACCURACY_LOSS},
+    engine::{
+        generate_moves, line_presence, play_move, winning_cells, Board, Eval, EvalParams, Move,
+        ZONE_ANY,
+    },
+    move_string,
+    search::{root_move_scores, SearchContext},
+    tt::TranspositionTable,
+};
+
+// Depth of the search used to classify moves. Beginners' mistakes are
+// mostly tactical, so a shallow search is enough to find them.
+pub const COACH_DEPTH: usize = 6;
+
+// Depth of the search judging how natural a move looks to a beginner, who weighs up
+// the position after their own move without looking at the replies to it.
+// Moves scoring within `NATURAL_MARGIN` of the best at that depth look natural.
+pub const NATURAL_DEPTH: usize = 1;
+pub const NATURAL_MARGIN: Eval = INACCURACY_LOSS;
+
+/**
+ * A reason given to a beginner for why a move is good or bad,
+ * found from the immediate threats before and after it.
+ */
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CoachReason {
+    WinsGame,
+    // Another move would have won the game at once.
+    MissesGameWin,
+    // The opponent can win the game with their next move.
+    AllowsGameWin,
+    WinsZone,
+    // Another move would have won a zone.
+    MissesZoneWin,
+    // The opponent can win a zone with their next move.
+    SendsToZoneWin,
+    // The opponent may move in any zone.
+    GrantsFreeMove,
+    // The move wins a zone that leaves two or more ways to complete the large grid.
+    CreatesLargeFork,
+}
+
+// What a move achieves immediately, for `side` (`false` for X), who made it.
+struct MoveOutcome {
+    wins_zone: bool,
+    wins_game: bool,
+}
+
+fn outcome(board: Board, mv: Move, side: bool) -> MoveOutcome {
+    let after = play_move(board, mv, side);
+    let shift = if side { 45 } else { 36 };
+    MoveOutcome {
+        wins_zone: (after.2 >> shift) & 0b111111111 != (board.2 >> shift) & 0b111111111,
+        wins_game: line_presence(after.2 >> shift),
+    }
+}
+
+// The number of open cells of the large grid that would complete a line for `side`.
+fn large_threats(board: Board, side: bool) -> u32 {
+    let (x_large, o_large) = (board.2 >> 36, board.2 >> 45);
+    let own = if side { o_large } else { x_large };
+    (winning_cells(own) & !(x_large | o_large) & 0b111111111).count_ones()
+}
+
+/**
+ * Finds the reasons that apply to a legal move by `side` (`false` for X),
+ * by comparing what it and the other legal moves achieve at once,
+ * and what the opponent can achieve in reply.
+ */
+pub fn move_reasons(board: Board, mv: Move, side: bool) -> Vec<CoachReason> {
+    let mut reasons = Vec::new();
+    let played = outcome(board, mv, side);
+    let alternatives: Vec<MoveOutcome> = generate_moves(board)
+        .filter(|&m| m != mv)
+        .map(|m| outcome(board, m, side))
+        .collect();
+    let after = play_move(board, mv, side);
+    let replies: Vec<MoveOutcome> =
+        generate_moves(after).map(|m| outcome(after, m, !side)).collect();
+
+    if played.wins_game {
+        reasons.push(CoachReason::WinsGame);
+        return reasons;
+    }
+    if alternatives.iter().any(|alternative| alternative.wins_game) {
+        reasons.push(CoachReason::MissesGameWin);
+    }
+    if replies.iter().any(|reply| reply.wins_game) {
+        reasons.push(CoachReason::AllowsGameWin);
+    }
+    if played.wins_zone {
+        reasons.push(CoachReason::WinsZone);
+    } else if alternatives.iter().any(|alternative| alternative.wins_zone) {
+        reasons.push(CoachReason::MissesZoneWin);
+    }
+    if replies.iter().any(|reply| reply.wins_zone) {
+        reasons.push(CoachReason::SendsToZoneWin);
+    }
+    if (after.2 >> 54) & 0b1111 == ZONE_ANY {
+        reasons.push(CoachReason::GrantsFreeMove);
+    }
+    if played.wins_zone && large_threats(after, side) >= 2 && large_threats(board, side) < 2 {
+        reasons.push(CoachReason::CreatesLargeFork);
+    }
+    reasons
+}
+
+// The verdict on a proposed move, in a form suitable for serialising as JSON.
+#[derive(Serialize, Clone, Debug)]
+pub struct CoachReport {
+    // One of `best`, `good`, `inaccuracy`, `mistake` or `blunder`.
+    pub classification: &'static str,
+    pub reasons: Vec<CoachReason>,
+    // The best move found, and how much worse the proposed move scored.
+    pub best: String,
+    pub loss: Eval,
+}
+
+/**
+ * Classifies a legal move by `side` (`false` for X) with a search of `COACH_DEPTH`
+ * plies, in the terms of `annotate`, and lists the reasons that apply to it.
+ * The evaluation terms are those of `eval_params`.
+ * Returns `None` if the search is stopped or the move is not legal.
+ */
+pub fn coach_move(
+    board: Board,
+    mv: Move,
+    side: bool,
+    tables: &(Vec<Eval>, Vec<Eval>),
+    eval_params: EvalParams,
+    tt: &mut TranspositionTable,
+    stop_condition: Option<&dyn Fn() -> bool>,
+) -> Option<CoachReport> {
+    tt.new_search();
+    let mut ctx = SearchContext::new(tables, COACH_DEPTH);
+    ctx.eval_params = eval_params;
+    ctx.tt = Some(tt);
+    ctx.stop_condition = stop_condition;
+    let scores = root_move_scores(board, side, COACH_DEPTH, &mut ctx)?;
+    let (best_move, best) = scores[0];
+    let score = scores.iter().find(|&&(m, _)| m == mv)?.1;
+    let loss = best.saturating_sub(score);
+    Some(CoachReport {
+        classification: classify(&scores, mv, loss),
+        reasons: move_reasons(board, mv, side),
+        best: move_string(best_move),
+        loss,
+    })
+}
+
+// Classifies a move, which loses `loss` against the best, in the terms of `CoachReport`.
+fn classify(scores: &[(Move, Eval)], mv: Move, loss: Eval) -> &'static str {
+    match annotate(scores, mv) {
+        Some(Annotation::Dubious) => "inaccuracy",
+        Some(Annotation::Mistake) => "mistake",
+        Some(Annotation::Blunder) => "blunder",
+        _ if loss == 0 => "best",
+        _ => "good",
+    }
+}
+
+// A move to warn a beginner about, in a form suitable for serialising as JSON.
+#[derive(Serialize, Clone, Debug)]
+pub struct MoveWarning {
+    #[serde(rename = "move")]
+    pub mv: String,
+    // As for `CoachReport`.
+    pub classification: &'static str,
+    pub reasons: Vec<CoachReason>,
+    // How much worse the move scored than the best, by a search of `COACH_DEPTH` plies
+    // and of `NATURAL_DEPTH` plies respectively.
+    pub loss: Eval,
+    pub natural_loss: Eval,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TrapReport {
+    pub best: String,
+    pub worst: MoveWarning,
+    // Moves which look natural, but are mistakes or blunders, worst first.
+    pub traps: Vec<MoveWarning>,
+}
+
+/**
+ * Finds the worst legal move for `side` (`false` for X) by a search of `COACH_DEPTH` plies,
+ * and the traps: moves within `NATURAL_MARGIN` of the best by a search of `NATURAL_DEPTH`
+ * plies, which the deeper search finds to be mistakes or blunders, such as sending the
+ * opponent to a zone they can win. The evaluation terms are those of `eval_params`.
+ * Returns `None` if the search is stopped or there is no legal move.
+ */
+pub fn find_traps(
+    board: Board,
+    side: bool,
+    tables: &(Vec<Eval>, Vec<Eval>),
+    eval_params: EvalParams,
+    tt: &mut TranspositionTable,
+    stop_condition: Option<&dyn Fn() -> bool>,
+) -> Option<TrapReport> {
+    let mut ctx = SearchContext::new(tables, NATURAL_DEPTH);
+    ctx.eval_params = eval_params;
+    ctx.stop_condition = stop_condition;
+    let natural_scores = root_move_scores(board, side, NATURAL_DEPTH, &mut ctx)?;
+
+    tt.new_search();
+    let mut ctx = SearchContext::new(tables, COACH_DEPTH);
+    ctx.eval_params = eval_params;
+    ctx.tt = Some(tt);
+    ctx.stop_condition = stop_condition;
+    let scores = root_move_scores(board, side, COACH_DEPTH, &mut ctx)?;
+
+    let (best_move, best) = *scores.first()?;
+    let natural_best = natural_scores[0].1;
+    let warning = |(mv, score): (Move, Eval)| {
+        let natural_score = natural_scores.iter().find(|&&(m, _)| m == mv).unwrap().1;
+        let loss = best.saturating_sub(score);
+        MoveWarning {
+            mv: move_string(mv),
+            classification: classify(&scores, mv, loss),
+            reasons: move_reasons(board, mv, side),
+            loss,
+            natural_loss: natural_best.saturating_sub(natural_score),
+        }
+    };
+    // The scores are sorted best first, so the traps are found worst first.
+    let traps = scores
+        .iter()
+        .rev()
+        .map(|&score| warning(score))
+        .filter(|warning| {
+            warning.natural_loss <= NATURAL_MARGIN
+                && matches!(warning.classification, "mistake" | "blunder")
+        })
+        .collect();
+    Some(TrapReport {
+        best: move_string(best_move),
+        worst: warning(*scores.last()?),
+        traps,
+    })
+}
@@ -0,0 +1,113 @@
+use crate::{
+    eval_string,
+    engine::Move,
+    highlights::cell_coordinates,
+    protocol::{Position, Protocol, Request, SearchOutcome},
+};
+
+/*
+ * The protocol of CodinGame's Ultimate Tic-Tac-Toe arena,
+ * where cells are given as `<row> <column>` of the 9x9 grid, counting from the top left.
+ * Each turn, the referee gives the opponent's last move (`-1 -1` if there is none),
+ * then the number of valid actions followed by each action on its own line,
+ * and expects the chosen move in reply.
+ */
+
+// Default time limits for each move, in milliseconds. The arena allows 1000ms
+// for the first move and 100ms for each move after it, so these leave a margin
+// for the time taken to stop the search and respond.
+pub const DEFAULT_FIRST_TIME_MS: u64 = 900;
+pub const DEFAULT_TIME_MS: u64 = 85;
+
+pub struct CodinGame {
+    // The time to search the engine's first move for.
+    pub first_time_ms: u64,
+    // The time to search each later move for.
+    pub time_ms: u64,
+}
+
+impl Default for CodinGame {
+    fn default() -> Self {
+        CodinGame {
+            first_time_ms: DEFAULT_FIRST_TIME_MS,
+            time_ms: DEFAULT_TIME_MS,
+        }
+    }
+}
+
+// Returns the move at a `row` and `column` of the 9x9 grid, if they are on the grid.
+pub fn move_from_row_column(row: u64, column: u64) -> Option<Move> {
+    if row >= 9 || column >= 9 {
+        return None;
+    }
+    Some(9 * (3 * (row / 3) + column / 3) + 3 * (row % 3) + column % 3)
+}
+
+// Formats a move as the referee expects it, as `<row> <column>`.
+pub fn row_column_string(mv: Move) -> String {
+    let [row, column] = cell_coordinates(mv);
+    format!("{row} {column}")
+}
+
+// Parses a line of `<row> <column>`, where `-1 -1` means no move.
+fn parse_action(line: &str) -> Result<Option<Move>, &'static str> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    match fields[..] {
+        ["-1", "-1"] => Ok(None),
+        [row, column] => {
+            let row = row.parse().map_err(|_| "action invalid")?;
+            let column = column.parse().map_err(|_| "action invalid")?;
+            move_from_row_column(row, column).map(Some).ok_or("action invalid")
+        }
+        _ => Err("action invalid"),
+    }
+}
+
+impl Protocol for CodinGame {
+    fn read_request(
+        &mut self,
+        lines: &mut dyn Iterator<Item = String>,
+    ) -> Result<Option<Request>, String> {
+        let opponent_move = match lines.next() {
+            Some(line) => parse_action(&line)?,
+            None => return Ok(None),
+        };
+        let count: usize = lines
+            .next()
+            .and_then(|line| line.trim().parse().ok())
+            .ok_or("action count invalid")?;
+        let valid_moves = (0..count)
+            .map(|_| match lines.next() {
+                Some(line) => parse_action(&line)?.ok_or("action invalid"),
+                None => Err("input ended"),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Some(Request {
+            position: Position::OpponentMove(opponent_move),
+            valid_moves: Some(valid_moves),
+            time_ms: None,
+        }))
+    }
+
+    fn format_move(&self, mv: Move, _search: Option<&SearchOutcome>) -> String {
+        row_column_string(mv)
+    }
+
+    fn time_limit_ms(&self, ply: usize) -> u64 {
+        match ply {
+            0 | 1 => self.first_time_ms,
+            _ => self.time_ms,
+        }
+    }
+
+    // The arena shows what the engine writes to standard error alongside the game.
+    fn log_line(&self, search: &SearchOutcome) -> Option<String> {
+        Some(format!(
+            "depth {} score {} nodes {} time {}",
+            search.result.depth,
+            eval_string(search.result.eval, search.result.depth),
+            search.nodes,
+            search.time_ms,
+        ))
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{
+    engine::{mark_counts, zone_cells, Board, ZONE_ANY},
+    game::{board_result, GameResult},
+    zones::{zone_status, ZoneStatus},
+    ZONE_ARRAY_LOWER, ZONE_ARRAY_UPPER,
+};
+
+// Joins names as in a sentence: `a`, `a and b`, or `a, b and c`.
+fn join_names(names: &[String]) -> String {
+    match names {
+        [] => String::new(),
+        [name] => name.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+/**
+ * Describes a board in words, for screen readers, rather than drawing it.
+ * The description gives the zones won by each player and the drawn zones,
+ * then either the result of the game, or the side to move (inferred from
+ * the number of marks) with the zones or cells it may play in.
+ * Cells are named by their zone in capitals and their position in lowercase.
+ *
+ * ```text
+ * X controls NW and C. O controls E. O to move in the SE zone;
+ * cells SE/n and SE/e are open.
+ * ```
+ */
+pub fn describe_board(board: Board) -> String {
+    let mut won_x = Vec::new();
+    let mut won_o = Vec::new();
+    let mut drawn = Vec::new();
+    let mut open = Vec::new();
+    for zone in 0..9 {
+        let name = ZONE_ARRAY_UPPER[zone as usize].to_string();
+        match zone_status(board, zone) {
+            ZoneStatus::WonX => won_x.push(name),
+            ZoneStatus::WonO => won_o.push(name),
+            ZoneStatus::Drawn => drawn.push(name),
+            ZoneStatus::Open => open.push(zone),
+        }
+    }
+
+    let mut sentences = Vec::new();
+    if won_x.is_empty() && won_o.is_empty() {
+        sentences.push("No zone has been won.".to_string());
+    }
+    if !won_x.is_empty() {
+        sentences.push(format!("X controls {}.", join_names(&won_x)));
+    }
+    if !won_o.is_empty() {
+        sentences.push(format!("O controls {}.", join_names(&won_o)));
+    }
+    match drawn.len() {
+        0 => {}
+        1 => sentences.push(format!("{} is drawn.", drawn[0])),
+        _ => sentences.push(format!("{} are drawn.", join_names(&drawn))),
+    }
+
+    match board_result(board) {
+        Some(GameResult::XWins) => sentences.push("X has won the game.".to_string()),
+        Some(GameResult::OWins) => sentences.push("O has won the game.".to_string()),
+        Some(GameResult::Draw) => sentences.push("The game is drawn.".to_string()),
+        None => {
+            let (x_count, o_count) = mark_counts(board);
+            let side = if x_count > o_count { "O" } else { "X" };
+            let zone = (board.2 >> 54) & 0b1111;
+            if zone == ZONE_ANY || !open.contains(&zone) {
+                let zones: Vec<String> = open
+                    .iter()
+                    .map(|&z| ZONE_ARRAY_UPPER[z as usize].to_string())
+                    .collect();
+                let verb = if zones.len() == 1 { "is" } else { "are" };
+                sentences.push(format!(
+                    "{side} to move in any zone; {} {verb} open.",
+                    join_names(&zones)
+                ));
+            } else {
+                let (x_cells, o_cells) = zone_cells(board, zone);
+                let empty = !(x_cells | o_cells) & 0b111111111;
+                let zone_name = ZONE_ARRAY_UPPER[zone as usize];
+                let cells: Vec<String> = (0..9)
+                    .filter(|&cell| (empty >> cell) & 1 == 1)
+                    .map(|cell| format!("{zone_name}/{}", ZONE_ARRAY_LOWER[cell]))
+                    .collect();
+                let (noun, verb) = if cells.len() == 1 { ("cell", "is") } else { ("cells", "are") };
+                sentences.push(format!(
+                    "{side} to move in the {zone_name} zone; {noun} {} {verb} open.",
+                    join_names(&cells)
+                ));
+            }
+        }
+    }
+    sentences.join(" ")
+}
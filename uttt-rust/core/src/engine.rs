@@ -0,0 +1,1118 @@
+/**
+ * The bitboard structure is represented here as a tuple of 3 `u64`s.
+ * Though the elements do not have inherent names, all elements
+ * are always destructured with the `us`, `them` and `share` names.
+ */
+pub type Board = (u64, u64, u64);
+pub type Move = u64;
+pub type Eval = i32;
+
+// Weights for representing win, loss and draw outcomes.
+pub const OUTCOME_WIN: Eval = 1000000;
+pub const OUTCOME_DRAW: Eval = 0;
+pub const OUTCOME_LOSS: Eval = -1000000;
+
+// Weights for line scoring.
+const BIG_TWO_COUNT: Eval = 90;
+const BIG_ONE_COUNT: Eval = 20;
+const SMALL_TWO_COUNT: Eval = 8;
+const SMALL_ONE_COUNT: Eval = 1;
+
+// Weights for positional scoring.
+const CENTRE: Eval = 9;
+const CORNER: Eval = 7;
+const EDGE: Eval = 5;
+const SQ_BIG: Eval = 25;
+
+// Weights for shapes in a small grid which line and position scoring misjudge:
+// two or more lines each a mark short of completion, a corner held with the centre,
+// and a centre whose every line is blocked. The first is negative, as a zone is only
+// won once, so a second threat in it adds less than its line count suggests.
+// Against the tables without these, searching 5 plies from random openings
+// played with both colours, they scored 52% over 3000 games.
+const SMALL_FORK: Eval = -5;
+const SMALL_CENTRE_CORNER: Eval = 1;
+const SMALL_BLOCKED_CENTRE: Eval = 6;
+
+// The lines through the centre of a grid: W-C-E, N-C-S, NW-C-SE and NE-C-SW.
+const CENTRE_LINES: [u64; 4] = [0b_000_111_000, 0b_010_010_010, 0b_100_010_001, 0b_001_010_100];
+
+// Bonus for the side to move, which is a move ahead of the other in completing its lines.
+// Without it, the score of a position swings with whose turn it is in the leaves searched:
+// from one iteration to the next, scores of random positions moved by 27 on average,
+// against 12 with this bonus, which was the least of the values tried.
+const TEMPO: Eval = 10;
+
+// Weights for the zone the side to move is sent to, from its point of view:
+// a free choice of zone, a zone it can win with its next move,
+// and a zone in which it can no longer make a line. Against the same engine without
+// these terms, searching 5 plies from 1500 random openings played with both colours,
+// they scored 59%, with the zone that can be won at once accounting for most of that.
+const SEND_FREE: Eval = 30;
+const SEND_THREAT: Eval = 60;
+const SEND_DEAD: Eval = 30;
+
+// Weight of each legal move of the side to move, mostly telling a free choice of zone from
+// a single zone, but also how much room is left in either. Against the same engine without
+// it, searching 5 plies from 2000 random openings played with both colours, it scored 55%,
+// against 52% to 53% for weights of 1 to 3 and falling away from 8 up.
+const MOBILITY: Eval = 5;
+
+// Masks for use in changing bitboards.
+const LINE: u64 = 0b111;
+const CHUNK: u64 = 0b111111111;
+pub(crate) const DBLCHUNK: u64 = (CHUNK << 9) | CHUNK;
+const EXCLZONE: u64 = !(0b1111u64 << 54);
+const CORNER_MASK: u64 = 0b_101_000_101;
+const EDGE_MASK: u64 = 0b_010_101_010;
+const CENTRE_MASK: u64 = 0b_000_010_000;
+
+// Internal representation for `zone` value, outside of the 0-8 range,
+// to indicate that the player can play in any zone.
+pub const ZONE_ANY: u64 = 9;
+
+// The board at the start of the game, with no marks and any zone playable.
+pub const EMPTY_BOARD: Board = (0, 0, ZONE_ANY << 54);
+
+// Since values 0-80 are best used for move representation,
+// 81 is used to represent a "null move".
+pub const NULL_MOVE: Move = 81;
+
+// The absolute upper bound of total plies for this game is 81,
+// since there are exactly 81 places that can be played.
+// However, we select a lower number to balance the amount of unused
+// storage in the stack against a flexible upper bound of total depth searchable.
+pub const MAX_PLY: usize = 32;
+
+/*
+ * Due to the potential unreadability of an if-block in an arithmetic expression,
+ * the `toggle_shift` and `toggle_eval` functions provide functions to adjust
+ * a number based on a `bool` flag.
+ */
+
+#[inline]
+const fn toggle_shift(side: bool, num: u64) -> u64 {
+    // Returns either the given `u64` or `0`.
+    if side {
+        num
+    } else {
+        0
+    }
+}
+
+#[inline]
+pub(crate) const fn toggle_eval(side: bool, num: Eval) -> Eval {
+    // Returns either the given `Eval` or the negative of it.
+    if side {
+        -num
+    } else {
+        num
+    }
+}
+
+/*
+ * A grid is represented by the least significant 9 bits in a `u64`.
+ * The lines in a grid are represented by the following combinations of zones:
+ * NW-N-NE, W-C-E, SW-S-SE, NW-W-SW, N-C-S, NE-E-SE, NW-C-SE, NE-C-SW.
+ */
+
+// Returns a 24-bit value where each set of 3 bits
+// represents the occupancy pattern of that line.
+// A 1 bit means that particular position in that line is occupied.
+const fn compute_lines(grid: u64) -> u64 {
+    0b_000_100_000_000_100_000_000_100 * (grid & 1)
+        + 0b_000_000_000_000_010_000_100_000 * ((grid >> 1) & 1)
+        + 0b_100_000_000_000_001_100_000_000 * ((grid >> 2) & 1)
+        + 0b_000_000_000_100_000_000_000_010 * ((grid >> 3) & 1)
+        + 0b_010_010_000_010_000_000_010_000 * ((grid >> 4) & 1)
+        + 0b_000_000_000_001_000_010_000_000 * ((grid >> 5) & 1)
+        + 0b_001_000_100_000_000_000_000_001 * ((grid >> 6) & 1)
+        + 0b_000_000_010_000_000_000_001_000 * ((grid >> 7) & 1)
+        + 0b_000_001_001_000_000_001_000_000 * ((grid >> 8) & 1)
+}
+
+// Returns an 8-bit value that represents
+// the occupancy status of each line in a 3x3 grid
+// where a 1 bit means that line is formed.
+const fn compute_line_presence(grid: u64) -> bool {
+    0 != ((0b10110110 | ((grid & 1) * 0xff))
+        & (0b11101110 | (((grid >> 1) & 1) * 0xff))
+        & (0b01011110 | (((grid >> 2) & 1) * 0xff))
+        & (0b11110101 | (((grid >> 3) & 1) * 0xff))
+        & (0b00101101 | (((grid >> 4) & 1) * 0xff))
+        & (0b11011101 | (((grid >> 5) & 1) * 0xff))
+        & (0b01110011 | (((grid >> 6) & 1) * 0xff))
+        & (0b11101011 | (((grid >> 7) & 1) * 0xff))
+        & (0b10011011 | (((grid >> 8) & 1) * 0xff)))
+}
+
+/*
+ * The functions above are evaluated for every one of the 512 grids at compile time,
+ * as looking up the result is cheaper than the arithmetic in the hot paths
+ * of move generation and move application. The tables take under 4 KiB.
+ */
+
+const fn build_lines_table() -> [u32; 512] {
+    let mut table = [0; 512];
+    let mut grid = 0;
+    while grid < 512 {
+        table[grid] = compute_lines(grid as u64) as u32;
+        grid += 1;
+    }
+    table
+}
+
+const fn build_line_presence_table() -> [bool; 512] {
+    let mut table = [false; 512];
+    let mut grid = 0;
+    while grid < 512 {
+        table[grid] = compute_line_presence(grid as u64);
+        grid += 1;
+    }
+    table
+}
+
+// For each grid, the cells which would form a line if marked.
+// Cells that are already marked are included if they are part of a line.
+const fn build_winning_cells_table() -> [u16; 512] {
+    let mut table = [0; 512];
+    let mut grid = 0;
+    while grid < 512 {
+        let mut cell = 0;
+        while cell < 9 {
+            if compute_line_presence(grid as u64 | (1 << cell)) {
+                table[grid] |= 1 << cell;
+            }
+            cell += 1;
+        }
+        grid += 1;
+    }
+    table
+}
+
+static LINES_TABLE: [u32; 512] = build_lines_table();
+static LINE_PRESENCE_TABLE: [bool; 512] = build_line_presence_table();
+static WINNING_CELLS_TABLE: [u16; 512] = build_winning_cells_table();
+
+// Returns a 24-bit value where each set of 3 bits
+// represents the occupancy pattern of that line (see `compute_lines`).
+// Only the least significant 9 bits of the grid are used.
+#[inline]
+pub fn lines(grid: u64) -> u64 {
+    LINES_TABLE[(grid & CHUNK) as usize] as u64
+}
+
+// Returns whether a line is formed in a 3x3 grid.
+// Only the least significant 9 bits of the grid are used.
+#[inline]
+pub fn line_presence(grid: u64) -> bool {
+    LINE_PRESENCE_TABLE[(grid & CHUNK) as usize]
+}
+
+// Returns the cells of a 3x3 grid, as a 9-bit mask, which would form a line if marked,
+// including marked cells that already form part of a line.
+// Only the least significant 9 bits of the grid are used.
+#[inline]
+pub fn winning_cells(grid: u64) -> u64 {
+    WINNING_CELLS_TABLE[(grid & CHUNK) as usize] as u64
+}
+
+/**
+ * The weights of the heuristic evaluation, gathered together so that they can be tuned.
+ * The line and position weights are built into the lookup tables by `init_with`,
+ * so changing them needs new tables, while the other terms are applied by
+ * `evaluate_with` as each position is evaluated.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EvalParams {
+    pub big_two_count: Eval,
+    pub big_one_count: Eval,
+    pub small_two_count: Eval,
+    pub small_one_count: Eval,
+    pub centre: Eval,
+    pub corner: Eval,
+    pub edge: Eval,
+    pub sq_big: Eval,
+    pub small_fork: Eval,
+    pub small_centre_corner: Eval,
+    pub small_blocked_centre: Eval,
+    pub tempo: Eval,
+    pub send_free: Eval,
+    pub send_threat: Eval,
+    pub send_dead: Eval,
+    pub mobility: Eval,
+}
+
+impl EvalParams {
+    // The weights the engine plays with.
+    pub const DEFAULT: EvalParams = EvalParams {
+        big_two_count: BIG_TWO_COUNT,
+        big_one_count: BIG_ONE_COUNT,
+        small_two_count: SMALL_TWO_COUNT,
+        small_one_count: SMALL_ONE_COUNT,
+        centre: CENTRE,
+        corner: CORNER,
+        edge: EDGE,
+        sq_big: SQ_BIG,
+        small_fork: SMALL_FORK,
+        small_centre_corner: SMALL_CENTRE_CORNER,
+        small_blocked_centre: SMALL_BLOCKED_CENTRE,
+        tempo: TEMPO,
+        send_free: SEND_FREE,
+        send_threat: SEND_THREAT,
+        send_dead: SEND_DEAD,
+        mobility: MOBILITY,
+    };
+
+    // Each weight by name, in a fixed order, for tuners to work through.
+    fn fields_mut(&mut self) -> [(&'static str, &mut Eval); 16] {
+        [
+            ("big_two_count", &mut self.big_two_count),
+            ("big_one_count", &mut self.big_one_count),
+            ("small_two_count", &mut self.small_two_count),
+            ("small_one_count", &mut self.small_one_count),
+            ("centre", &mut self.centre),
+            ("corner", &mut self.corner),
+            ("edge", &mut self.edge),
+            ("sq_big", &mut self.sq_big),
+            ("small_fork", &mut self.small_fork),
+            ("small_centre_corner", &mut self.small_centre_corner),
+            ("small_blocked_centre", &mut self.small_blocked_centre),
+            ("tempo", &mut self.tempo),
+            ("send_free", &mut self.send_free),
+            ("send_threat", &mut self.send_threat),
+            ("send_dead", &mut self.send_dead),
+            ("mobility", &mut self.mobility),
+        ]
+    }
+
+    // The name and value of each weight.
+    pub fn values(&self) -> Vec<(&'static str, Eval)> {
+        let mut params = *self;
+        params.fields_mut().iter().map(|(name, value)| (*name, **value)).collect()
+    }
+
+    // Sets a weight by name, returning an error if there is no weight of that name.
+    pub fn set(&mut self, name: &str, value: Eval) -> Result<(), &'static str> {
+        match self.fields_mut().iter_mut().find(|(n, _)| *n == name) {
+            Some((_, field)) => {
+                **field = value;
+                Ok(())
+            }
+            None => Err("parameter unknown"),
+        }
+    }
+}
+
+impl Default for EvalParams {
+    fn default() -> Self {
+        EvalParams::DEFAULT
+    }
+}
+
+/**
+ * Scores the shapes of one side's marks in a small grid which the line and position
+ * weights miss, given the number of its open lines holding two of its marks.
+ * Line counts add up independently, so they cannot tell that two such lines are
+ * a fork, nor that a centre mark hemmed in on every line is worth little.
+ */
+fn small_shape_eval(own: u64, other: u64, open_twos: Eval, params: &EvalParams) -> Eval {
+    let mut eval = 0;
+    if open_twos >= 2 {
+        eval += params.small_fork;
+    }
+    if own & CENTRE_MASK != 0 {
+        eval += params.small_centre_corner * (own & CORNER_MASK).count_ones() as Eval;
+        if CENTRE_LINES.iter().all(|&line| other & line != 0) {
+            eval -= params.small_blocked_centre;
+        }
+    }
+    eval
+}
+
+/**
+ * This function is to be executed at the very start, and only once,
+ * to populate the lookup tables to be used in the heuristic evaluation.
+ * Since each of the two lookup tables contain 262144 `Eval` values,
+ * Vec<Eval> is returned instead of an array, to avoid stack overflow.
+ */
+pub fn init() -> (Vec<Eval>, Vec<Eval>) {
+    init_with(&EvalParams::DEFAULT)
+}
+
+// Populates the lookup tables as `init` does, with the weights of `params`.
+pub fn init_with(params: &EvalParams) -> (Vec<Eval>, Vec<Eval>) {
+    TableBuilder::new(params).finish()
+}
+
+// The number of arrangements of one side's marks in a small grid, each of which
+// gives a row of both lookup tables.
+pub const TABLE_ROWS: usize = 512;
+
+/**
+ * Builds the lookup tables of `init_with` a number of rows at a time, so that
+ * a caller with a single thread, such as a page in the browser, can show its progress
+ * between steps and choose when to pay for it, rather than freezing on first use.
+ */
+pub struct TableBuilder {
+    params: EvalParams,
+    // For each integer from 0 to 511, the number of 1 bits it has.
+    pop_count: Vec<Eval>,
+    // These lookup tables store partial heuristic evaluations for all possible
+    // arrangements of a grid, for both large and small grid metrics.
+    // These values are calculated from the perspective of player X,
+    // so will have to be negated for player O,
+    // as this program uses a symmetrical heuristic.
+    eval_table_large: Vec<Eval>,
+    eval_table_small: Vec<Eval>,
+    // The next arrangement of X's marks to build the rows of.
+    next_row: usize,
+}
+
+impl TableBuilder {
+    pub fn new(params: &EvalParams) -> Self {
+        TableBuilder {
+            params: *params,
+            pop_count: (0..512).map(|i| (0..9).fold(0, |acc, j| acc + ((i >> j) & 1))).collect(),
+            eval_table_large: vec![0; 262144],
+            eval_table_small: vec![0; 262144],
+            next_row: 0,
+        }
+    }
+
+    // The share of the rows built so far, from 0 to 1.
+    pub fn progress(&self) -> f64 {
+        self.next_row as f64 / TABLE_ROWS as f64
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.next_row == TABLE_ROWS
+    }
+
+    // Builds up to `rows` further rows, returning whether the tables are then complete.
+    pub fn step(&mut self, rows: usize) -> bool {
+        let end = (self.next_row + rows).min(TABLE_ROWS);
+        let (params, pop_count) = (&self.params, &self.pop_count);
+        let (eval_table_large, eval_table_small) =
+            (&mut self.eval_table_large, &mut self.eval_table_small);
+
+        // We test all the possible arrangements, which is where
+        // `us` and `them` each take a value from 0 to 511 each.
+        for us in (self.next_row..end).map(|us| us as u64) {
+            for them in (0..512).map(|them| them as u64) {
+                // These evaluation values will be incrementally updated.
+                let mut eval_large: Eval = 0;
+                let mut eval_small: Eval = 0;
+
+                // Retrieve the lines that each side makes as a bit array,
+                // allowing the number of occupancies in each line to be found.
+                let us_lines = lines(us);
+                let them_lines = lines(them);
+
+                // Early escape boolean flags, since no more evaluation is needed
+                // if one particular side has made a 3-in-a-row.
+                let mut us_won: bool = false;
+                let mut them_won: bool = false;
+
+                // The number of lines in which each side has two marks and the other none.
+                let mut us_open_twos: Eval = 0;
+                let mut them_open_twos: Eval = 0;
+
+                // We process the bits returned from `lines` in groups of 3.
+                for i in (0..24).step_by(3) {
+                    // Count how many cells each side occupies in this line.
+                    let us_count = pop_count[((us_lines >> i) & LINE) as usize];
+                    let them_count = pop_count[((them_lines >> i) & LINE) as usize];
+
+                    // If both sides already occupy a place in this line,
+                    // this line is no longer winnable for either side.
+                    if us_count != 0 && them_count != 0 {
+                        continue;
+                    }
+                    // Player X has won a line: X wins this configuration already.
+                    if us_count == 3 {
+                        us_won = true;
+                        break;
+                    }
+                    // Player O has won a line: O wins this configuration already.
+                    if them_count == 3 {
+                        them_won = true;
+                        break;
+                    }
+
+                    us_open_twos += (us_count == 2) as Eval;
+                    them_open_twos += (them_count == 2) as Eval;
+
+                    // Add on scores for occupying more of a line for both sides.
+
+                    eval_large += match us_count {
+                        2 => params.big_two_count,
+                        1 => params.big_one_count,
+                        _ => 0,
+                    } - match them_count {
+                        2 => params.big_two_count,
+                        1 => params.big_one_count,
+                        _ => 0,
+                    };
+                    eval_small += match us_count {
+                        2 => params.small_two_count,
+                        1 => params.small_one_count,
+                        _ => 0,
+                    } - match them_count {
+                        2 => params.small_two_count,
+                        1 => params.small_one_count,
+                        _ => 0,
+                    };
+                }
+
+                // Add on scores for occupancies in certain positions.
+                let eval_pos = params.corner
+                    * (pop_count[(us & CORNER_MASK) as usize]
+                        - pop_count[(them & CORNER_MASK) as usize])
+                    + params.edge
+                        * (pop_count[(us & EDGE_MASK) as usize]
+                            - pop_count[(them & EDGE_MASK) as usize])
+                    + params.centre
+                        * (pop_count[(us & CENTRE_MASK) as usize]
+                            - pop_count[(them & CENTRE_MASK) as usize]);
+
+                // Update large table with evaluation if a decisive result is reached,
+                // otherwise update both small and large table with suitable heuristics.
+                if us_won {
+                    eval_table_large[((them << 9) | us) as usize] = OUTCOME_WIN;
+                } else if them_won {
+                    eval_table_large[((them << 9) | us) as usize] = OUTCOME_LOSS;
+                } else if pop_count[(us | them) as usize] == 9 {
+                    eval_table_large[((them << 9) | us) as usize] = OUTCOME_DRAW;
+                } else {
+                    eval_table_large[((them << 9) | us) as usize] =
+                        eval_large + eval_pos * params.sq_big;
+                    eval_table_small[((them << 9) | us) as usize] = eval_small
+                        + eval_pos
+                        + small_shape_eval(us, them, us_open_twos, params)
+                        - small_shape_eval(them, us, them_open_twos, params);
+                }
+            }
+        }
+        self.next_row = end;
+        self.is_complete()
+    }
+
+    // Builds any rows not yet built, and returns the tables.
+    pub fn finish(mut self) -> (Vec<Eval>, Vec<Eval>) {
+        self.step(TABLE_ROWS);
+        (self.eval_table_large, self.eval_table_small)
+    }
+}
+
+/*
+ * The functions below all assume that we are starting with a valid board position.
+ * Only valid positions will be reached if the program only ever uses its own functions
+ * to play moves on the boards.
+ */
+
+// To avoid running `.collect()` once every time a move list is generated
+// only to be iterated over again in the functions it is used in,
+// this function instead returns an iterator trait object.
+pub fn generate_moves(board: Board) -> impl Iterator<Item = Move> {
+    // This function uses an enum to wrap all the iterators returned
+    // and implements Iterator for it, exactly how auto_enums would.
+    enum LegalMoves<_T1, _T2, _T3> {
+        NoMoves,
+        AllZones(_T1),
+        FirstSeven(_T2),
+        LastTwo(_T3),
+    }
+
+    impl<_T1, _T2, _T3> Iterator for LegalMoves<_T1, _T2, _T3>
+    where
+        _T1: Iterator,
+        _T2: Iterator<Item = <_T1 as Iterator>::Item>,
+        _T3: Iterator<Item = <_T1 as Iterator>::Item>,
+    {
+        type Item = <_T1 as Iterator>::Item;
+
+        #[inline]
+        fn next(&mut self) -> Option<Self::Item> {
+            match self {
+                Self::NoMoves => None,
+                Self::AllZones(x) => x.next(),
+                Self::FirstSeven(x) => x.next(),
+                Self::LastTwo(x) => x.next(),
+            }
+        }
+
+        #[inline]
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            match self {
+                Self::NoMoves => (0, Some(0)),
+                Self::AllZones(x) => x.size_hint(),
+                Self::FirstSeven(x) => x.size_hint(),
+                Self::LastTwo(x) => x.size_hint(),
+            }
+        }
+    }
+
+    let (us, them, share) = board;
+
+    if line_presence(share >> 36) || line_presence(share >> 45) {
+        return LegalMoves::NoMoves;
+    }
+
+    // Extract the zone to be played from the board.
+    let zone = (share >> 54) & 0b1111;
+
+    // The moves themselves correspond to actual integers that access the bitboards directly,
+    // hence we avoid using `map`, instead only using `filter` and `chain`.
+    match zone {
+        // If the player is allowed to play in any zone they wish, select all blank squares
+        // that are not in a zone that has a corresponding occupied large grid.
+        ZONE_ANY => {
+            let nw_to_sw = us | them;
+            let s_to_se = (share >> 18) | share;
+            let large = (share >> 36) | (share >> 45);
+
+            LegalMoves::AllZones(
+                (0..63)
+                    .filter(move |i| ((nw_to_sw >> i) & 1) == 0 && ((large >> (i / 9)) & 1) == 0)
+                    .chain((63..81).filter(move |i| {
+                        ((s_to_se >> (i - 63)) & 1) == 0 && ((large >> (i / 9)) & 1) == 0
+                    })),
+            )
+        }
+
+        // For zones S and SE, we access `share`.
+        7 | 8 => {
+            let s_to_se = (share >> 18) | share;
+            LegalMoves::LastTwo(
+                (9 * zone..9 * zone + 9).filter(move |i| ((s_to_se >> (i - 63)) & 1) == 0),
+            )
+        }
+
+        // For zones NW to SW, we access `us` and `them`.
+        _ => {
+            let nw_to_sw = us | them;
+            LegalMoves::FirstSeven(
+                (9 * zone..9 * zone + 9).filter(move |i| ((nw_to_sw >> i) & 1) == 0),
+            )
+        }
+    }
+}
+
+/**
+ * The legal moves of a position as a mask, in which bit `m` is set if move `m` is legal,
+ * found from the empty cells of the playable zones rather than by generating the moves.
+ */
+pub fn legal_move_mask(board: Board) -> u128 {
+    let (us, them, share) = board;
+    if line_presence(share >> 36) || line_presence(share >> 45) {
+        return 0;
+    }
+    // The empty cells of zones NW to SW are in `us` and `them`, and those of S and SE
+    // in the low 18 bits of `share`, for X and O alike.
+    let nw_to_sw = !(us | them) as u128 & ((1 << 63) - 1);
+    let s_to_se = !(share | (share >> 18)) as u128 & DBLCHUNK as u128;
+    let empty = nw_to_sw | (s_to_se << 63);
+    let zone_mask = |zone: u64| (CHUNK as u128) << (9 * zone);
+    match (share >> 54) & 0b1111 {
+        ZONE_ANY => {
+            let large = ((share >> 36) | (share >> 45)) & CHUNK;
+            (0..9).filter(|zone| (large >> zone) & 1 == 0).fold(0, |mask, zone| {
+                mask | (empty & zone_mask(zone))
+            })
+        }
+        zone => empty & zone_mask(zone),
+    }
+}
+
+// The number of legal moves in a position, as `generate_moves(board).count()` gives it.
+pub fn count_legal_moves(board: Board) -> u32 {
+    legal_move_mask(board).count_ones()
+}
+
+/**
+ * For a given move played by a given player, returs the new board state.
+ * Since Board is a tuple of primitive types, copies should be cheap enough,
+ * eliminating the desire to construct a function that mutates the passed Board.
+ */
+pub fn play_move(board: Board, mv: Move, side: bool) -> Board {
+    // Each move makes an incremental change to the board,
+    // so we create mutable copies of the `u64` components of the board.
+    let (mut us, mut them, mut share) = board;
+
+    // `line_occupancy` stores whether a line within the relevant zone is formed by us
+    // after this move is made.
+
+    let line_occupancy = if mv > 62 {
+        share |= 1 << (mv - 63 + toggle_shift(side, 18));
+
+        // implicit return in block
+        line_presence(share >> (9 * (mv / 9) - 63 + toggle_shift(side, 18)))
+    } else if !side {
+        us |= 1 << mv;
+
+        // implicit return in block
+        line_presence(us >> (9 * (mv / 9)))
+    } else {
+        them |= 1 << mv;
+
+        // implicit return in block
+        line_presence(them >> (9 * (mv / 9)))
+    };
+
+    // If this move forms a line in our zone, occupy the corresponding large grid.
+    if line_occupancy {
+        share |= 1 << (36 + toggle_shift(side, 9) + mv / 9);
+    }
+
+    // `next_chunk` contains all occupancies of the zone we are moving next in.
+    // The next zone to be played in is determined by the position of the current move
+    // relative to other cells in its zone, found by `mv % 9`.
+    // This determines if we access `share` or `us` and `them`.
+
+    let next_chunk = if mv % 9 > 6 {
+        ((share | (share >> 18)) >> (9 * ((mv % 9) - 7))) & CHUNK
+    } else {
+        ((us | them) >> (9 * (mv % 9))) & CHUNK
+    };
+
+    // The next player is allowed to play in any zone if either:
+    // the zone indicated by the most recent move corresponds to a large grid that is won,
+    // or the zone is completely filled with zero vacant cells.
+
+    let zone = if next_chunk == CHUNK || (((share | (share >> 9)) >> (36 + mv % 9)) & 1) == 1 {
+        ZONE_ANY
+    } else {
+        mv % 9
+    };
+
+    // We overwrite the bits in `share` completely with the new value of `zone`.
+    let new_board = (us, them, (share & EXCLZONE) | (zone << 54));
+
+    // Playing a legal move on a valid board must always produce a valid board.
+    debug_assert_eq!(check_structure(new_board), Ok(()));
+
+    new_board
+}
+
+/**
+ * An index into the lookup tables: the arrangement of a grid, with X's cells in the low
+ * nine bits and O's in the next nine. Every table read goes through one, which checks
+ * in debug builds that no cell is owned by both sides, so that a board with corrupt
+ * bits is caught where it is evaluated rather than silently scored.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TableIndex(usize);
+
+impl TableIndex {
+    #[inline]
+    pub fn new(x_cells: u64, o_cells: u64) -> Self {
+        debug_assert!(x_cells <= CHUNK && o_cells <= CHUNK, "grid cells out of range");
+        debug_assert!(x_cells & o_cells == 0, "grid cell owned by both sides");
+        TableIndex(((o_cells << 9) | x_cells) as usize)
+    }
+
+    // The index of the large grid of a board, from the zones won by each side.
+    #[inline]
+    pub fn large_grid(board: Board) -> Self {
+        TableIndex::new((board.2 >> 36) & CHUNK, (board.2 >> 45) & CHUNK)
+    }
+
+    #[inline]
+    pub fn get(self) -> usize {
+        self.0
+    }
+}
+
+/**
+ * Read access to the evaluation lookup tables, so that `evaluate` does not depend on
+ * how they are stored. The tables of `init`, whether generated at runtime or embedded
+ * at build time (see `load_tables`), are a pair of `Vec`s, and `PackedTables` holds
+ * the same values in half the memory. Other sets of tables, such as those of variants
+ * or of tuning runs, need only provide these two lookups.
+ */
+pub trait EvalTables {
+    // The evaluation for X of an arrangement of the large grid.
+    fn large(&self, index: TableIndex) -> Eval;
+    // The evaluation for X of an arrangement of a small grid in play.
+    fn small(&self, index: TableIndex) -> Eval;
+}
+
+impl EvalTables for (Vec<Eval>, Vec<Eval>) {
+    #[inline]
+    fn large(&self, index: TableIndex) -> Eval {
+        self.0[index.0]
+    }
+
+    #[inline]
+    fn small(&self, index: TableIndex) -> Eval {
+        self.1[index.0]
+    }
+}
+
+/**
+ * The lookup tables with each value stored in 16 bits, halving their memory, for devices
+ * where that matters more than the cost of widening them. Decisive values of the large
+ * grid do not fit, so they are stored as the extremes of the range, which no heuristic
+ * value is allowed to reach.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackedTables {
+    large: Vec<i16>,
+    small: Vec<i16>,
+}
+
+impl PackedTables {
+    /**
+     * Packs tables such as those of `init`, or returns an error if a heuristic value
+     * does not fit in 16 bits, as may happen with extreme evaluation weights.
+     */
+    pub fn new(tables: &(Vec<Eval>, Vec<Eval>)) -> Result<Self, &'static str> {
+        let pack = |table: &[Eval]| -> Result<Vec<i16>, &'static str> {
+            table
+                .iter()
+                .map(|&eval| match eval {
+                    OUTCOME_WIN => Ok(i16::MAX),
+                    OUTCOME_LOSS => Ok(i16::MIN),
+                    _ if eval > i16::MIN as Eval && eval < i16::MAX as Eval => Ok(eval as i16),
+                    _ => Err("table value out of range"),
+                })
+                .collect()
+        };
+        Ok(PackedTables {
+            large: pack(&tables.0)?,
+            small: pack(&tables.1)?,
+        })
+    }
+}
+
+impl EvalTables for PackedTables {
+    #[inline]
+    fn large(&self, index: TableIndex) -> Eval {
+        match self.large[index.0] {
+            i16::MAX => OUTCOME_WIN,
+            i16::MIN => OUTCOME_LOSS,
+            eval => eval as Eval,
+        }
+    }
+
+    #[inline]
+    fn small(&self, index: TableIndex) -> Eval {
+        self.small[index.0] as Eval
+    }
+}
+
+/**
+ * Heuristic for evaluating a particular board state for a given side.
+ * This function uses the precomputed values from `init()`,
+ * passed as a reference in its parameter.
+ */
+pub fn evaluate<T: EvalTables + ?Sized>(board: Board, side: bool, tables: &T) -> Eval {
+    evaluate_with(board, side, tables, &EvalParams::DEFAULT)
+}
+
+/**
+ * Evaluates a board as `evaluate` does, applying the terms of `params` which are not
+ * built into the tables. `side` is taken to be the side to move.
+ */
+pub fn evaluate_with<T: EvalTables + ?Sized>(
+    board: Board,
+    side: bool,
+    tables: &T,
+    params: &EvalParams,
+) -> Eval {
+    let share = board.2;
+
+    // First, check the evaluation of the large grid.
+    let eval = tables.large(TableIndex::large_grid(board));
+
+    // If the large grid has reached a decisive result, the game is over,
+    // with either a win or loss depending on the side currently evaluating this position.
+    if eval == OUTCOME_WIN || eval == OUTCOME_LOSS {
+        return toggle_eval(side, eval);
+    }
+
+    // If the large grid does not have a won line,
+    // but is completely filled, the game is a draw.
+    let large = ((share >> 36) | (share >> 45)) & CHUNK;
+    if large == CHUNK {
+        return OUTCOME_DRAW;
+    }
+
+    // We use `toggle_eval` to adjust the evaluation for the side we are evaluating for,
+    // adding the scoring of the small grids on top of the scoring for the large grid.
+    toggle_eval(side, eval + small_grid_evals(board, large, tables))
+        + params.tempo
+        + sending_eval(board, side, params)
+        + params.mobility * count_legal_moves(board) as Eval
+}
+
+/**
+ * Scores the zone that the side to move has been sent to, from its point of view.
+ * Search only sees the consequences of where a move sends the opponent a ply later,
+ * so at the leaves this stands in for them: being free to play in any zone, or sent
+ * to a zone with a winning cell open, is good for the side to move, while being sent
+ * to a zone in which every line is blocked wastes its move.
+ */
+#[inline]
+fn sending_eval(board: Board, side: bool, params: &EvalParams) -> Eval {
+    let zone = board.2 >> 54;
+    if zone == ZONE_ANY {
+        return params.send_free;
+    }
+    let (x_cells, o_cells) = zone_cells(board, zone);
+    let (own, other) = if side { (o_cells, x_cells) } else { (x_cells, o_cells) };
+    let empty = !(own | other) & CHUNK;
+    if winning_cells(own) & empty != 0 {
+        params.send_threat
+    } else if !line_presence(!other) {
+        -params.send_dead
+    } else {
+        0
+    }
+}
+
+/**
+ * Sums the precomputed evaluations of the small grids that are still in play,
+ * given the occupancy of the large grid.
+ */
+#[cfg(not(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128")))]
+#[inline]
+fn small_grid_evals<T: EvalTables + ?Sized>(board: Board, large: u64, tables: &T) -> Eval {
+    let (us, them, share) = board;
+
+    // Due to the different components that the zones NW to SW and S to SE are stored,
+    // we once again chain two iterators together to prevent having to check
+    // the condition each time.
+    (0..7)
+        .map(|i| {
+            let us_data = (us >> (9 * i)) & CHUNK;
+            let them_data = (them >> (9 * i)) & CHUNK;
+
+            // Zones that are comlpetely filled or correspond to an occupied large grid
+            // are not scored. Since the values are added,
+            // we return a zero for this situation.
+            if ((large >> i) & 1) == 1 || (us_data | them_data) == CHUNK {
+                0
+            } else {
+                // Incrementally add the precomputed evaluation of the small grid.
+                tables.small(TableIndex::new(us_data, them_data))
+            }
+        })
+        .chain((7..9).map(|i| {
+            let us_data = (share >> (9 * i - 63)) & CHUNK;
+            let them_data = (share >> (9 * i - 45)) & CHUNK;
+
+            if ((large >> i) & 1) == 1 || (us_data | them_data) == CHUNK {
+                0
+            } else {
+                tables.small(TableIndex::new(us_data, them_data))
+            }
+        }))
+        .sum()
+}
+
+/**
+ * A branch-free version of the above using WebAssembly SIMD, enabled by the `simd`
+ * feature when building with `-C target-feature=+simd128`. WebAssembly has no gather
+ * instruction, so the nine table lookups remain scalar, but the zones are then
+ * masked and summed four at a time instead of branching on each one.
+ * Every zone is looked up, including those not in play, which is always in bounds.
+ */
+#[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+#[inline]
+fn small_grid_evals<T: EvalTables + ?Sized>(board: Board, large: u64, tables: &T) -> Eval {
+    use core::arch::wasm32::*;
+
+    // The occupancy and evaluation of each zone, padded to a multiple of four lanes
+    // with full zones, which are not scored.
+    let mut occupied = [CHUNK as u32; 12];
+    let mut evals = [0 as Eval; 12];
+    for zone in 0..9 {
+        let (x_cells, o_cells) = zone_cells(board, zone as u64);
+        occupied[zone] = (x_cells | o_cells) as u32;
+        evals[zone] = tables.small(TableIndex::new(x_cells, o_cells));
+    }
+
+    let chunk = u32x4_splat(CHUNK as u32);
+    let large = u32x4_splat(large as u32);
+    let mut total = i32x4_splat(0);
+    for group in 0..3 {
+        let lanes = 4 * group;
+        let occupied = u32x4(
+            occupied[lanes],
+            occupied[lanes + 1],
+            occupied[lanes + 2],
+            occupied[lanes + 3],
+        );
+        let evals = i32x4(evals[lanes], evals[lanes + 1], evals[lanes + 2], evals[lanes + 3]);
+        // The bit of each zone in the large grid.
+        let zone_bits = u32x4(1 << lanes, 1 << (lanes + 1), 1 << (lanes + 2), 1 << (lanes + 3));
+        let won = u32x4_ne(v128_and(large, zone_bits), u32x4_splat(0));
+        let full = u32x4_eq(occupied, chunk);
+        total = i32x4_add(total, v128_andnot(evals, v128_or(won, full)));
+    }
+    i32x4_extract_lane::<0>(total)
+        + i32x4_extract_lane::<1>(total)
+        + i32x4_extract_lane::<2>(total)
+        + i32x4_extract_lane::<3>(total)
+}
+
+/**
+ * Breaks the heuristic evaluation of a board down into its components,
+ * from X's point of view: the score of the large grid, and the score of each
+ * small grid, or `None` for zones that are won or full and so not scored.
+ * Unless the game is over, `evaluate` for X is the sum of these,
+ * with the terms for the side to move added when X is to move and taken away when O is:
+ * the tempo bonus, the score of the zone sent to (see `sending_eval`), and the mobility
+ * bonus for the number of legal moves, none of which belong to any one grid.
+ */
+pub fn zone_evals<T: EvalTables + ?Sized>(board: Board, tables: &T) -> (Eval, [Option<Eval>; 9]) {
+    let share = board.2;
+    let large = ((share >> 36) | (share >> 45)) & CHUNK;
+    let mut zones = [None; 9];
+    for (zone, eval) in zones.iter_mut().enumerate() {
+        let (x_cells, o_cells) = zone_cells(board, zone as u64);
+        if (large >> zone) & 1 == 0 && (x_cells | o_cells) != CHUNK {
+            *eval = Some(tables.small(TableIndex::new(x_cells, o_cells)));
+        }
+    }
+    (tables.large(TableIndex::large_grid(board)), zones)
+}
+
+// Counts the leaf nodes of the game tree to the given depth,
+// which is used to verify move generation and move application.
+// Finished games are counted as leaves regardless of the remaining depth.
+pub fn perft(board: Board, side: bool, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let mut move_list = generate_moves(board).peekable();
+    if move_list.peek().is_none() {
+        return 1;
+    }
+    move_list
+        .map(|mv| perft(play_move(board, mv, side), !side, depth - 1))
+        .sum()
+}
+
+/**
+ * Plays a game from the empty board in which each byte of `choices` selects
+ * a legal move by its index (modulo the number of legal moves), stopping early
+ * if the game ends. Returns the board reached and the side to move (`false` for X).
+ * Any sequence of bytes gives a position reachable by legal play,
+ * so this can be used to generate arbitrary valid boards, such as for fuzzing.
+ */
+pub fn board_from_choices(choices: &[u8]) -> (Board, bool) {
+    let mut board = EMPTY_BOARD;
+    let mut side = false;
+    for &choice in choices {
+        let moves: Vec<Move> = generate_moves(board).collect();
+        if moves.is_empty() {
+            break;
+        }
+        board = play_move(board, moves[choice as usize % moves.len()], side);
+        side = !side;
+    }
+    (board, side)
+}
+
+// Returns the small grids of X and O respectively in the given zone,
+// each as the least significant 9 bits of a `u64`.
+#[inline]
+pub fn zone_cells(board: Board, zone: u64) -> (u64, u64) {
+    let (us, them, share) = board;
+    if zone > 6 {
+        ((share >> (9 * zone - 63)) & CHUNK, (share >> (9 * zone - 45)) & CHUNK)
+    } else {
+        ((us >> (9 * zone)) & CHUNK, (them >> (9 * zone)) & CHUNK)
+    }
+}
+
+/**
+ * Verifies the invariants of the bitboard representation that hold
+ * regardless of how the position was reached: unused bits are clear,
+ * no cell is owned by both players, the large grid matches the lines
+ * formed in the small grids, and the zone field is a playable zone or `any`.
+ * Returns a description of the first violated invariant.
+ */
+pub fn check_structure(board: Board) -> Result<(), &'static str> {
+    let (us, them, share) = board;
+
+    if (us >> 63) != 0 || (them >> 63) != 0 || (share >> 58) != 0 {
+        return Err("unused bits set");
+    }
+    if (us & them) != 0 || (share & (share >> 18) & DBLCHUNK) != 0 {
+        return Err("cell owned by both players");
+    }
+
+    for zone in 0..9 {
+        let (x_cells, o_cells) = zone_cells(board, zone);
+        let x_won = line_presence(x_cells);
+        let o_won = line_presence(o_cells);
+        if x_won && o_won {
+            return Err("zone won by both players");
+        }
+        if x_won != (((share >> (36 + zone)) & 1) == 1)
+            || o_won != (((share >> (45 + zone)) & 1) == 1)
+        {
+            return Err("large grid inconsistent with small grids");
+        }
+    }
+
+    if (share >> 54) & 0b1111 > ZONE_ANY {
+        return Err("zone field invalid");
+    }
+    if !zone_available(board) {
+        return Err("zone field points to a won or full zone");
+    }
+
+    Ok(())
+}
+
+// Whether the zone field allows at least one move, that is, it is either `ZONE_ANY`
+// or points to a zone that is neither won nor full. Out of range values are
+// left for `check_structure` to report.
+pub fn zone_available(board: Board) -> bool {
+    let share = board.2;
+    let zone = (share >> 54) & 0b1111;
+    if zone >= ZONE_ANY {
+        return true;
+    }
+    let (x_cells, o_cells) = zone_cells(board, zone);
+    (((share >> 36) | (share >> 45)) >> zone) & 1 == 0 && (x_cells | o_cells) != CHUNK
+}
+
+// Replaces a zone field that points to a won or full zone with `ZONE_ANY`,
+// as `play_move` would have done, so that the board has the legal moves intended.
+pub fn correct_zone(board: Board) -> Board {
+    if zone_available(board) {
+        board
+    } else {
+        let (us, them, share) = board;
+        (us, them, (share & EXCLZONE) | (ZONE_ANY << 54))
+    }
+}
+
+// Returns the number of marks of X and O respectively.
+pub fn mark_counts(board: Board) -> (u32, u32) {
+    let (us, them, share) = board;
+    (
+        us.count_ones() + (share & 0x3ffff).count_ones(),
+        them.count_ones() + ((share >> 18) & 0x3ffff).count_ones(),
+    )
+}
+
+/**
+ * Verifies all invariants of a position reached by legal play from the empty board.
+ * In addition to `check_structure`, X moves first, so X must have
+ * either as many marks as O, or exactly one more.
+ */
+pub fn check_invariants(board: Board) -> Result<(), &'static str> {
+    check_structure(board)?;
+    let (x_count, o_count) = mark_counts(board);
+    if x_count != o_count && x_count != o_count + 1 {
+        return Err("piece counts inconsistent with alternating play");
+    }
+    Ok(())
+}
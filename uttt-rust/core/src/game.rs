@@ -0,0 +1,212 @@
+use serde::Serialize;
+
+use crate::{
+    engine::{
+        generate_moves, line_presence, play_move, zone_cells, Board, Move, EMPTY_BOARD, ZONE_ANY,
+    },
+    move_from_string, move_string,
+    versioning::{migrate_text, tag_text, Format, VersionError},
+    ZONE_ARRAY_LOWER,
+};
+
+// The result of a finished game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameResult {
+    XWins,
+    OWins,
+    Draw,
+}
+
+impl GameResult {
+    // The conventional notation of the result, from X's point of view.
+    pub fn notation(self) -> &'static str {
+        match self {
+            GameResult::XWins => "1-0",
+            GameResult::OWins => "0-1",
+            GameResult::Draw => "1/2-1/2",
+        }
+    }
+
+    pub fn from_notation(notation: &str) -> Option<Self> {
+        match notation {
+            "1-0" => Some(GameResult::XWins),
+            "0-1" => Some(GameResult::OWins),
+            "1/2-1/2" => Some(GameResult::Draw),
+            _ => None,
+        }
+    }
+}
+
+// The result of the game at a board, or `None` if it is still in progress.
+pub fn board_result(board: Board) -> Option<GameResult> {
+    if line_presence(board.2 >> 36) {
+        Some(GameResult::XWins)
+    } else if line_presence(board.2 >> 45) {
+        Some(GameResult::OWins)
+    } else if generate_moves(board).next().is_none() {
+        Some(GameResult::Draw)
+    } else {
+        None
+    }
+}
+
+/**
+ * Something notable that happened as a result of a move, such as for the frontend
+ * to play an animation or a sound. Sides are `x` or `o` and zones are named
+ * as in move strings, so that the events serialise as they are presented.
+ */
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MoveEvent {
+    ZoneWon { side: &'static str, zone: &'static str },
+    // The zone was filled without either side winning it.
+    ZoneDrawn { zone: &'static str },
+    // The next player may move in any zone.
+    FreeMoveGranted,
+    GameOver { result: &'static str },
+}
+
+/**
+ * Plays a move by `side` (`false` for X), returning the new board together with
+ * the events that the move caused, in the order listed in `MoveEvent`.
+ * The move must be legal.
+ */
+pub fn apply_move(board: Board, mv: Move, side: bool) -> (Board, Vec<MoveEvent>) {
+    let after = play_move(board, mv, side);
+    let zone = mv / 9;
+    let mut events = Vec::new();
+    let large_bit = |board: Board| (board.2 >> (36 + 9 * side as u64 + zone)) & 1;
+    let (x_cells, o_cells) = zone_cells(after, zone);
+    if large_bit(after) != large_bit(board) {
+        events.push(MoveEvent::ZoneWon {
+            side: if side { "o" } else { "x" },
+            zone: ZONE_ARRAY_LOWER[zone as usize],
+        });
+    } else if (x_cells | o_cells) == 0b111111111 {
+        // Moves are never made in won zones, so the zone is drawn.
+        events.push(MoveEvent::ZoneDrawn {
+            zone: ZONE_ARRAY_LOWER[zone as usize],
+        });
+    }
+    let result = board_result(after);
+    match result {
+        Some(result) => events.push(MoveEvent::GameOver {
+            result: result.notation(),
+        }),
+        None if (after.2 >> 54) & 0b1111 == ZONE_ANY => events.push(MoveEvent::FreeMoveGranted),
+        None => {}
+    }
+    (after, events)
+}
+
+// Parses a game record of a result in the notation of `GameResult`,
+// followed by the transcript of its moves, such as `1-0 c/c c/nw nw/c`.
+// The result is given explicitly as games can end by resignation or timeout.
+// The record may begin with a version tag (see `versioning`).
+pub fn parse_game_record(record: &str) -> Result<(Game, GameResult), &'static str> {
+    let record = migrate_text(Format::GameRecord, record).map_err(VersionError::description)?;
+    let record = record.trim();
+    let (result, transcript) = record.split_once(char::is_whitespace).unwrap_or((record, ""));
+    let result = GameResult::from_notation(result).ok_or("result invalid")?;
+    Ok((Game::from_transcript(transcript)?, result))
+}
+
+// Writes a game record in the current version of the format, tagged with that version.
+pub fn game_record_string(game: &Game, result: GameResult) -> String {
+    let record = format!("{} {}", result.notation(), game.transcript());
+    tag_text(Format::GameRecord, record.trim_end())
+}
+
+/**
+ * A game played from the empty board, keeping every position reached
+ * so that moves can be undone and earlier positions revisited.
+ * X always moves first, so the side to move follows from the number of moves.
+ */
+#[derive(Clone, Debug)]
+pub struct Game {
+    moves: Vec<Move>,
+    // The position before each move, followed by the current position.
+    boards: Vec<Board>,
+}
+
+impl Game {
+    pub fn new() -> Self {
+        Game {
+            moves: Vec::new(),
+            boards: vec![EMPTY_BOARD],
+        }
+    }
+
+    // Replays a transcript of moves separated by whitespace, such as `c/c c/nw`.
+    pub fn from_transcript(transcript: &str) -> Result<Self, &'static str> {
+        let mut game = Game::new();
+        for token in transcript.split_whitespace() {
+            let mv = move_from_string(token).ok_or("move invalid")?;
+            game.play(mv)?;
+        }
+        Ok(game)
+    }
+
+    // The moves played so far, separated by spaces.
+    pub fn transcript(&self) -> String {
+        self.moves
+            .iter()
+            .map(|&mv| move_string(mv))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn board(&self) -> Board {
+        *self.boards.last().unwrap()
+    }
+
+    // The side to move, using the same convention as `play_move` (`false` for X).
+    pub fn side_to_move(&self) -> bool {
+        self.moves.len() % 2 == 1
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    // The position before the move at `ply` (counting from 0),
+    // or the current position if `ply` is the number of moves played.
+    pub fn board_at(&self, ply: usize) -> Option<Board> {
+        self.boards.get(ply).copied()
+    }
+
+    // Plays a move for the side to move, if it is legal.
+    pub fn play(&mut self, mv: Move) -> Result<(), &'static str> {
+        self.make_move(mv).map(|_| ())
+    }
+
+    // Plays a move for the side to move, if it is legal, returning the events it caused.
+    pub fn make_move(&mut self, mv: Move) -> Result<Vec<MoveEvent>, &'static str> {
+        let board = self.board();
+        if !generate_moves(board).any(|m| m == mv) {
+            return Err("move illegal");
+        }
+        let (after, events) = apply_move(board, mv, self.side_to_move());
+        self.boards.push(after);
+        self.moves.push(mv);
+        Ok(events)
+    }
+
+    // Takes back the last move, returning it.
+    pub fn undo(&mut self) -> Option<Move> {
+        let mv = self.moves.pop()?;
+        self.boards.pop();
+        Some(mv)
+    }
+
+    // The result of the game, or `None` if it is still in progress.
+    pub fn result(&self) -> Option<GameResult> {
+        board_result(self.board())
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
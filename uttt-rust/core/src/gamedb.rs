@@ -0,0 +1,345 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryInto,
+};
+
+use serde::Serialize;
+
+use crate::{
+    engine::{mark_counts, Board, Move},
+    game::{Game, GameResult},
+    move_string,
+    opening_tree::{MoveStats, ResultCounts},
+    pattern::Pattern,
+    record::{parse_record, record_string, GameRecord},
+    symmetry::{canonical_board, canonical_line, canonical_move, inverse, transform_move},
+    versioning::{migrate_bytes, Format, VersionError},
+    zobrist::{hash_game, hash_position, Hash},
+};
+
+/*
+ * A database of game records, with every position they reach indexed by the hash
+ * of its canonical form, so that the games reaching a position (or any of its symmetric
+ * variants) are found without replaying them all. Moves found in games are transformed
+ * to match the position queried.
+ *
+ * The database is saved as a byte buffer, which native callers write to a file and
+ * the browser keeps in its own storage. The format is the magic bytes `UTGD`,
+ * a version byte (see `versioning`), a little-endian `u32` record count, and then
+ * each record as a little-endian `u32` length followed by its text in the format
+ * of `record_string`. The index is not saved, but rebuilt as the records are read.
+ */
+
+const MAGIC: &[u8; 4] = b"UTGD";
+const HEADER_SIZE: usize = 9;
+
+pub struct GameDb {
+    records: Vec<GameRecord>,
+    // The game and ply at which each position is reached, keyed by the hash
+    // of its canonical form with the side to move. A position is reached
+    // at most once in a game, as every move adds a mark.
+    index: HashMap<Hash, Vec<(u32, u8)>>,
+    // The games with each hash of their moves (see `hash_game`).
+    games: HashMap<Hash, Vec<u32>>,
+}
+
+// A game reaching a position, as returned by `GameDb::games_reaching`.
+#[derive(Serialize, Clone, Debug)]
+pub struct GameHit {
+    pub game: usize,
+    // The number of moves played before the position was reached.
+    pub ply: usize,
+    // The move played from the position, transformed to match the position queried,
+    // or `None` if the game ended (or was abandoned) there.
+    #[serde(rename = "move")]
+    pub mv: Option<String>,
+    pub result: Option<&'static str>,
+    pub headers: Vec<(String, String)>,
+}
+
+// The results of the games opening with a line of moves, as returned by `GameDb::line_stats`.
+#[derive(Serialize, Clone, Debug)]
+pub struct LineStats {
+    // The moves of the line separated by spaces, in the least of its symmetric forms.
+    pub line: String,
+    pub plies: usize,
+    #[serde(flatten)]
+    pub counts: ResultCounts,
+    // The proportion of games won by the side that played the last move of the line.
+    pub win_rate: f64,
+    // The average result for that side, counting a draw as half a win.
+    pub score: f64,
+}
+
+// Statistics of the openings of the finished games, as returned by `GameDb::opening_stats`.
+#[derive(Serialize, Clone, Debug)]
+pub struct OpeningStats {
+    pub games: u64,
+    pub first_moves: Vec<LineStats>,
+    // The lines of two moves, each a first move and a reply to it.
+    pub replies: Vec<LineStats>,
+    // The lines of every length up to the depth asked for.
+    pub lines: Vec<LineStats>,
+}
+
+// The side to move in a board, `false` for X, going by the number of marks.
+fn side_to_move(board: Board) -> bool {
+    let (x_count, o_count) = mark_counts(board);
+    x_count > o_count
+}
+
+impl GameDb {
+    pub fn new() -> Self {
+        GameDb {
+            records: Vec::new(),
+            index: HashMap::new(),
+            games: HashMap::new(),
+        }
+    }
+
+    // The number of games in the database.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn record(&self, game: usize) -> Option<&GameRecord> {
+        self.records.get(game)
+    }
+
+    pub fn records(&self) -> &[GameRecord] {
+        &self.records
+    }
+
+    // Adds a game, indexing each position in it, and returns its number.
+    pub fn add(&mut self, record: GameRecord) -> usize {
+        let game = self.records.len();
+        for ply in 0..=record.game.moves().len() {
+            if let Some(board) = record.game.board_at(ply) {
+                let (canonical, _) = canonical_board(board);
+                let key = hash_position(canonical, ply % 2 == 1);
+                self.index.entry(key).or_default().push((game as u32, ply as u8));
+            }
+        }
+        let key = hash_game(record.game.moves());
+        self.games.entry(key).or_default().push(game as u32);
+        self.records.push(record);
+        game
+    }
+
+    // The first game in the database with the same moves as `game`,
+    // or with those of one of its symmetric variants.
+    pub fn duplicate_of(&self, game: &Game) -> Option<usize> {
+        let line = canonical_line(game.moves());
+        self.games
+            .get(&hash_game(game.moves()))?
+            .iter()
+            .map(|&g| g as usize)
+            .find(|&g| canonical_line(self.records[g].game.moves()) == line)
+    }
+
+    // Adds a game as `add` does, unless it is a duplicate (see `duplicate_of`),
+    // returning the number of the game added, or of the game it duplicates as an error.
+    pub fn add_unique(&mut self, record: GameRecord) -> Result<usize, usize> {
+        match self.duplicate_of(&record.game) {
+            Some(game) => Err(game),
+            None => Ok(self.add(record)),
+        }
+    }
+
+    // The games reaching a position, and the ply at which each reaches it, in the
+    // order they were added. Hash collisions are ruled out by comparing the boards.
+    fn positions(&self, board: Board) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let (canonical, _) = canonical_board(board);
+        let key = hash_position(canonical, side_to_move(board));
+        self.index
+            .get(&key)
+            .into_iter()
+            .flatten()
+            .map(|&(game, ply)| (game as usize, ply as usize))
+            .filter(move |&(game, ply)| {
+                self.records[game]
+                    .game
+                    .board_at(ply)
+                    .is_some_and(|b| canonical_board(b).0 == canonical)
+            })
+    }
+
+    // Transforms a move played from a game's board, symmetric to `board`, to match `board`.
+    fn move_from(&self, board: Board, game: usize, ply: usize) -> Option<Move> {
+        let game = &self.records[game].game;
+        let mv = *game.moves().get(ply)?;
+        let (_, canonical_mv) = canonical_move(game.board_at(ply)?, mv);
+        let (_, transform) = canonical_board(board);
+        Some(transform_move(canonical_mv, inverse(transform)))
+    }
+
+    // Lists every game reaching a position, in any of its symmetric variants.
+    pub fn games_reaching(&self, board: Board) -> Vec<GameHit> {
+        self.positions(board)
+            .map(|(game, ply)| {
+                let record = &self.records[game];
+                GameHit {
+                    game,
+                    ply,
+                    mv: self.move_from(board, game, ply).map(move_string),
+                    result: record.result.map(GameResult::notation),
+                    headers: record.headers.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /**
+     * Finds the games reaching a position which matches a pattern, or with `symmetric`,
+     * any of its symmetric variants. Each game is listed once, at the first such position,
+     * with the move played from it as it was played.
+     */
+    pub fn search(&self, pattern: &Pattern, symmetric: bool) -> Vec<GameHit> {
+        let patterns = match symmetric {
+            true => pattern.variants(),
+            false => vec![pattern.clone()],
+        };
+        let mut hits = Vec::new();
+        for (game, record) in self.records.iter().enumerate() {
+            let found = (0..=record.game.moves().len()).find(|&ply| {
+                let board = record.game.board_at(ply).unwrap();
+                patterns.iter().any(|pattern| pattern.matches(board))
+            });
+            if let Some(ply) = found {
+                hits.push(GameHit {
+                    game,
+                    ply,
+                    mv: record.game.moves().get(ply).map(|&mv| move_string(mv)),
+                    result: record.result.map(GameResult::notation),
+                    headers: record.headers.clone(),
+                });
+            }
+        }
+        hits
+    }
+
+    // Sums up the results of the finished games by the move played from a position,
+    // most played first. Symmetric moves in a symmetric position are counted together.
+    pub fn move_stats(&self, board: Board) -> Vec<MoveStats> {
+        let mut moves: BTreeMap<Move, ResultCounts> = BTreeMap::new();
+        for (game, ply) in self.positions(board) {
+            if let (Some(mv), Some(result)) =
+                (self.move_from(board, game, ply), self.records[game].result)
+            {
+                moves.entry(mv).or_default().add(result);
+            }
+        }
+        let side = side_to_move(board);
+        let mut stats: Vec<MoveStats> = moves
+            .into_iter()
+            .map(|(mv, counts)| MoveStats {
+                mv: move_string(mv),
+                score: counts.score(side),
+                counts,
+            })
+            .collect();
+        stats.sort_by_key(|stats| std::cmp::Reverse(stats.counts.games));
+        stats
+    }
+
+    // Sums up the results of the finished games by their opening line, for every line
+    // of up to `max_plies` moves, shortest first and then most played first.
+    pub fn line_stats(&self, max_plies: usize) -> Vec<LineStats> {
+        let mut lines: BTreeMap<Vec<Move>, ResultCounts> = BTreeMap::new();
+        for record in &self.records {
+            if let Some(result) = record.result {
+                let moves = record.game.moves();
+                for plies in 1..=max_plies.min(moves.len()) {
+                    lines.entry(canonical_line(&moves[..plies])).or_default().add(result);
+                }
+            }
+        }
+        let mut stats: Vec<LineStats> = lines
+            .into_iter()
+            .map(|(line, counts)| {
+                // X plays the moves at odd positions in the line.
+                let side = line.len() % 2 == 0;
+                LineStats {
+                    line: line.iter().map(|&mv| move_string(mv)).collect::<Vec<_>>().join(" "),
+                    plies: line.len(),
+                    win_rate: counts.win_rate(side),
+                    score: counts.score(side),
+                    counts,
+                }
+            })
+            .collect();
+        stats.sort_by_key(|stats| (stats.plies, std::cmp::Reverse(stats.counts.games)));
+        stats
+    }
+
+    // Statistics of the first moves, the replies to them, and the opening lines
+    // of up to `max_plies` moves, of the finished games.
+    pub fn opening_stats(&self, max_plies: usize) -> OpeningStats {
+        let stats = self.line_stats(max_plies.max(2));
+        let of_length = |plies: usize| stats.iter().filter(move |s| s.plies == plies).cloned();
+        OpeningStats {
+            games: self.records.iter().filter(|r| r.result.is_some()).count() as u64,
+            first_moves: of_length(1).collect(),
+            replies: of_length(2).collect(),
+            lines: stats.iter().filter(|s| s.plies <= max_plies).cloned().collect(),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_SIZE);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(Format::GameDb.current_version());
+        bytes.extend_from_slice(&(self.records.len() as u32).to_le_bytes());
+        for record in &self.records {
+            let text = record_string(record);
+            bytes.extend_from_slice(&(text.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(text.as_bytes());
+        }
+        bytes
+    }
+
+    // Reads a database saved by `to_bytes`, or returns an error if it is malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        if bytes.len() < HEADER_SIZE || &bytes[..4] != MAGIC {
+            return Err("database invalid");
+        }
+        let payload = match migrate_bytes(Format::GameDb, bytes[4], &bytes[5..]) {
+            Ok(payload) => payload,
+            Err(VersionError::Unsupported) => return Err("database version unsupported"),
+            Err(VersionError::Malformed) => return Err("database invalid"),
+        };
+        let read_u32 = |at: usize| -> Result<usize, &'static str> {
+            match payload.get(at..at + 4) {
+                Some(b) => Ok(u32::from_le_bytes(b.try_into().unwrap()) as usize),
+                None => Err("database invalid"),
+            }
+        };
+
+        let count = read_u32(0)?;
+        let mut db = GameDb::new();
+        let mut at = 4;
+        for _ in 0..count {
+            let length = read_u32(at)?;
+            let text = payload
+                .get(at + 4..at + 4 + length)
+                .and_then(|text| std::str::from_utf8(text).ok())
+                .ok_or("database invalid")?;
+            db.add(parse_record(text)?);
+            at += 4 + length;
+        }
+        if at != payload.len() {
+            return Err("database invalid");
+        }
+        Ok(db)
+    }
+}
+
+impl Default for GameDb {
+    fn default() -> Self {
+        GameDb::new()
+    }
+}
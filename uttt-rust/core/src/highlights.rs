@@ -0,0 +1,85 @@
+use serde::Serialize;
+
+use crate::{
+    engine::{play_move, winning_cells, zone_cells, Board, Move, NULL_MOVE, ZONE_ANY},
+    move_string, ZONE_ARRAY_LOWER,
+};
+
+/*
+ * Annotations for drawing over the board, so that the frontend need not work out
+ * the consequences of moves itself. Cells are given as `[row, column]` of the 9x9 grid,
+ * counting from the top left, as well as by their move string where that is clearer.
+ */
+
+// Returns the `[row, column]` of a cell in the 9x9 grid.
+pub fn cell_coordinates(mv: Move) -> [u64; 2] {
+    let (zone, cell) = (mv / 9, mv % 9);
+    [3 * (zone / 3) + cell / 3, 3 * (zone % 3) + cell % 3]
+}
+
+// An arrow from the cell of a move to the centre of the zone it sends the opponent to.
+// Moves that free the opponent to play anywhere have no arrow.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Arrow {
+    pub from: [u64; 2],
+    pub to: [u64; 2],
+}
+
+// An empty cell which would win its zone for `side` (`x` or `o`).
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Threat {
+    pub cell: String,
+    pub at: [u64; 2],
+    pub side: &'static str,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Highlights {
+    // The arrow of the best move, if any.
+    pub arrow: Option<Arrow>,
+    // The zone the best move sends the opponent to, or `any`.
+    pub sends_to: Option<&'static str>,
+    // Every threat in the zones still in play, for both sides.
+    pub threats: Vec<Threat>,
+}
+
+/**
+ * Computes the highlights of a position, given the best move found for `side`
+ * (`false` for X), the side to move. The best move may be the null move,
+ * in which case only the threats are given.
+ */
+pub fn highlights(board: Board, best: Move, side: bool) -> Highlights {
+    let (arrow, sends_to) = if best < NULL_MOVE {
+        let zone = (play_move(board, best, side).2 >> 54) & 0b1111;
+        let arrow = (zone < ZONE_ANY).then(|| Arrow {
+            from: cell_coordinates(best),
+            to: cell_coordinates(9 * zone + 4),
+        });
+        (arrow, Some(ZONE_ARRAY_LOWER.get(zone as usize).copied().unwrap_or("any")))
+    } else {
+        (None, None)
+    };
+
+    let large = (board.2 >> 36) | (board.2 >> 45);
+    let mut threats = Vec::new();
+    for zone in (0..9).filter(|&zone| (large >> zone) & 1 == 0) {
+        let (x_cells, o_cells) = zone_cells(board, zone);
+        let empty = !(x_cells | o_cells);
+        for (cells, name) in [(x_cells, "x"), (o_cells, "o")] {
+            let open = winning_cells(cells) & empty;
+            for cell in (0..9).filter(|&cell| (open >> cell) & 1 == 1) {
+                threats.push(Threat {
+                    cell: move_string(9 * zone + cell),
+                    at: cell_coordinates(9 * zone + cell),
+                    side: name,
+                });
+            }
+        }
+    }
+
+    Highlights {
+        arrow,
+        sends_to,
+        threats,
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{
+    engine::{generate_moves, line_presence, play_move, Board, Eval, Move},
+    rng::Rng,
+};
+
+/*
+ * Move selection which imitates a human player of a given rating.
+ * Rather than always playing the best move of a shallow search, which makes
+ * for strangely erratic play, a move is chosen at random from a softmax
+ * distribution over the scores of all root moves. Small mistakes are then
+ * common and large ones rare, and weaker players make more of both.
+ */
+
+/*
+ * However weak the play, some moves are never chosen where there is an alternative:
+ * those after which the opponent can win the game, or a zone, with its very next move.
+ * Missing a deep tactic looks human, but handing over a zone at once looks broken.
+ * This only looks two plies ahead, so it costs little even beside the shallowest search.
+ */
+
+// What a move allows the opponent to win with its reply, worst last.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MoveSafety {
+    Safe,
+    LosesZone,
+    LosesGame,
+}
+
+// What the opponent of `side` can win with its reply to `mv`.
+pub fn move_safety(board: Board, mv: Move, side: bool) -> MoveSafety {
+    let after = play_move(board, mv, side);
+    // The large grid of each side is 9 bits from bit 36 (X) or bit 45 (O) of `share`.
+    let opponent_large = |board: Board| (board.2 >> if side { 36 } else { 45 }) & 0b111111111;
+    let zones_before = opponent_large(after).count_ones();
+    let mut safety = MoveSafety::Safe;
+    for reply in generate_moves(after) {
+        let large = opponent_large(play_move(after, reply, !side));
+        if line_presence(large) {
+            return MoveSafety::LosesGame;
+        }
+        if large.count_ones() > zones_before {
+            safety = MoveSafety::LosesZone;
+        }
+    }
+    safety
+}
+
+/**
+ * The moves of `moves` (legal moves for `side`) which give the least away to the opponent's
+ * reply by `move_safety`, keeping their order: the safe moves if there are any, otherwise
+ * those which only lose a zone, and only if every move loses the game, all of them.
+ */
+pub fn safest_moves(board: Board, side: bool, moves: &[Move]) -> Vec<Move> {
+    let safety: Vec<MoveSafety> = moves.iter().map(|&mv| move_safety(board, mv, side)).collect();
+    match safety.iter().min() {
+        Some(&least) => {
+            moves.iter().zip(&safety).filter(|&(_, &s)| s == least).map(|(&mv, _)| mv).collect()
+        }
+        None => Vec::new(),
+    }
+}
+
+// The range of supported ratings, and the softmax temperature (in evaluation
+// units) at each end. Temperatures in between are interpolated geometrically.
+pub const MIN_RATING: u32 = 400;
+pub const MAX_RATING: u32 = 2400;
+const MIN_RATING_TEMPERATURE: f64 = 200.0;
+const MAX_RATING_TEMPERATURE: f64 = 4.0;
+
+// Decisive scores are clamped to this magnitude, so that a forced win is
+// strongly preferred but can still be overlooked by weaker players.
+const SCORE_CLAMP: Eval = 1000;
+
+// The softmax temperature for a target rating, clamped to the supported range.
+pub fn temperature_for_rating(rating: u32) -> f64 {
+    let rating = rating.clamp(MIN_RATING, MAX_RATING);
+    let fraction = (rating - MIN_RATING) as f64 / (MAX_RATING - MIN_RATING) as f64;
+    MIN_RATING_TEMPERATURE * (MAX_RATING_TEMPERATURE / MIN_RATING_TEMPERATURE).powf(fraction)
+}
+
+// Chooses one of the scored moves (with scores relative to the side to move)
+// with probability proportional to `exp(score / temperature)`.
+pub fn choose_move(scores: &[(Move, Eval)], temperature: f64, rng: &mut Rng) -> Option<Move> {
+    let clamped = |eval: Eval| eval.clamp(-SCORE_CLAMP, SCORE_CLAMP) as f64;
+    let best = scores.iter().map(|&(_, eval)| clamped(eval)).fold(f64::MIN, f64::max);
+    // Scores are taken relative to the best, so that the weights cannot overflow.
+    let weights: Vec<f64> = scores
+        .iter()
+        .map(|&(_, eval)| ((clamped(eval) - best) / temperature).exp())
+        .collect();
+    rng.choose_weighted(&weights).map(|index| scores[index].0)
+}
@@ -0,0 +1,112 @@
+use serde::Serialize;
+
+use crate::{
+    calibration::{strength_levels, StrengthLevel},
+    engine::{Eval, Move, MAX_PLY, NULL_MOVE, OUTCOME_DRAW, OUTCOME_LOSS, OUTCOME_WIN, ZONE_ANY},
+    ZONE_ARRAY_LOWER, ZONE_ARRAY_UPPER,
+};
+
+/**
+ * Identification of the engine and the capabilities of this particular build,
+ * so that frontends can label the engine and enable features conditionally.
+ */
+#[derive(Serialize, Clone, Debug)]
+pub struct EngineInfo {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub authors: &'static str,
+    // Rule variants that the engine can play.
+    pub variants: Vec<&'static str>,
+    // Techniques used by the search.
+    pub search: Vec<&'static str>,
+    // Names accepted by `set_option`.
+    pub options: Vec<&'static str>,
+    // Cargo features this build was compiled with.
+    pub features: Vec<&'static str>,
+    // The approximate rating of each search depth (see `calibration`).
+    pub strength: Vec<StrengthLevel>,
+}
+
+pub fn engine_info() -> EngineInfo {
+    let features = [
+        ("render", cfg!(feature = "render")),
+        ("book", cfg!(feature = "book")),
+        ("solver", cfg!(feature = "solver")),
+        ("embedded-tables", cfg!(feature = "embedded-tables")),
+        ("simd", cfg!(feature = "simd")),
+    ];
+    EngineInfo {
+        // The engine keeps its name across the core and binding crates.
+        name: "uttt-rust",
+        version: env!("CARGO_PKG_VERSION"),
+        authors: env!("CARGO_PKG_AUTHORS"),
+        variants: vec!["standard"],
+        search: vec![
+            "alpha-beta",
+            "fail-soft",
+            "iterative-deepening",
+            "transposition-table",
+            "internal-iterative-deepening",
+        ],
+        options: vec![
+            "stats",
+            "perspective",
+            "hash",
+            "strict",
+            "resignscore",
+            "resignmoves",
+            "drawscore",
+            "drawmoves",
+            "moveoverhead",
+            "onlymovemargin",
+            "troll",
+            "tempo",
+            "evalnoise",
+            "noiseseed",
+            "blunders",
+            "contempt",
+            "latency",
+            "tables",
+        ],
+        features: features
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| *name)
+            .collect(),
+        strength: strength_levels(),
+    }
+}
+
+/**
+ * Constants of the engine's representation which frontends depend on,
+ * so that they need not be duplicated (and kept in sync) in JS.
+ */
+#[derive(Serialize, Clone, Debug)]
+pub struct EngineConstants {
+    // The maximum search depth, in plies.
+    pub max_ply: usize,
+    pub outcome_win: Eval,
+    pub outcome_draw: Eval,
+    pub outcome_loss: Eval,
+    // The move value meaning "no move", as in an unfilled PV slot.
+    pub null_move: Move,
+    // The zone value meaning that any zone may be played in.
+    pub zone_any: u64,
+    // Zone names in index order, as used in move and board strings.
+    pub zones: [&'static str; 9],
+    // The same names in upper case, as used by the ASCII board rendering.
+    pub zones_upper: [&'static str; 9],
+}
+
+pub fn engine_constants() -> EngineConstants {
+    EngineConstants {
+        max_ply: MAX_PLY,
+        outcome_win: OUTCOME_WIN,
+        outcome_draw: OUTCOME_DRAW,
+        outcome_loss: OUTCOME_LOSS,
+        null_move: NULL_MOVE,
+        zone_any: ZONE_ANY,
+        zones: ZONE_ARRAY_LOWER,
+        zones_upper: ZONE_ARRAY_UPPER,
+    }
+}
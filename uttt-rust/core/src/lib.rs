@@ -0,0 +1,495 @@
+/*
+ * The engine, in plain Rust without any WebAssembly dependencies, so that it can be
+ * used by native tools as well as by the bindings in the `uttt-wasm` crate.
+ */
+
+// Used as a temporary measure before more features are used
+#![allow(unused_imports)]
+#![allow(dead_code)]
+
+use std::convert::TryInto;
+
+use crate::{
+    engine::*,
+    versioning::{migrate_text, Format},
+};
+
+pub mod adjudication;
+pub mod analysis;
+pub mod annotated;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod best_first;
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "book")]
+pub mod book;
+pub mod calibration;
+pub mod coach;
+pub mod codingame;
+pub mod describe;
+pub mod engine;
+pub mod game;
+#[cfg(feature = "book")]
+pub mod gamedb;
+pub mod highlights;
+pub mod humanlike;
+pub mod info;
+pub mod log;
+#[cfg(feature = "dev-tools")]
+pub mod movegen_check;
+pub mod movelist;
+pub mod ndjson;
+pub mod notation;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod observer;
+#[cfg(feature = "book")]
+pub mod opening_tree;
+pub mod options;
+pub mod pattern;
+pub mod ponder;
+pub mod protocol;
+pub mod record;
+pub mod regression;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "render")]
+pub mod render;
+pub mod replay;
+pub mod rng;
+pub mod search;
+pub mod selfplay;
+pub mod selftest;
+#[cfg(feature = "solver")]
+pub mod solver;
+pub mod spsa;
+pub mod state_url;
+pub mod symmetry;
+pub mod tablebase;
+pub mod testsuite;
+pub mod timeman;
+pub mod timer;
+pub mod tt;
+pub mod versioning;
+pub mod win_tables;
+pub mod zobrist;
+pub mod zones;
+
+// Returns the evaluation lookup tables, either by decoding the copy
+// embedded at build time, or by generating them with `init()`.
+pub fn load_tables() -> (Vec<Eval>, Vec<Eval>) {
+    #[cfg(feature = "embedded-tables")]
+    {
+        const TABLE_SIZE: usize = 262144;
+        let bytes = include_bytes!(concat!(env!("OUT_DIR"), "/tables.bin"));
+        let evals: Vec<Eval> = bytes
+            .chunks_exact(4)
+            .map(|chunk| Eval::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect();
+        let (large, small) = evals.split_at(TABLE_SIZE);
+        (large.to_vec(), small.to_vec())
+    }
+    #[cfg(not(feature = "embedded-tables"))]
+    {
+        init()
+    }
+}
+
+// Arrays to readily convert integers in the 0-8 range to the
+// name of their corresponding zone.
+pub(crate) const ZONE_ARRAY_UPPER: [&str; 9] = ["NW", "N", "NE", "W", "C", "E", "SW", "S", "SE"];
+pub(crate) const ZONE_ARRAY_LOWER: [&str; 9] = ["nw", "n", "ne", "w", "c", "e", "sw", "s", "se"];
+
+// How `print_board` draws a board.
+#[cfg(feature = "render")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoardStyle {
+    Plain,
+    // Colours the marks with ANSI escape codes (X red, O blue) for terminals,
+    // shades the zones that may be played in, and marks the last move, if any.
+    Ansi { last_move: Option<Move> },
+}
+
+// ANSI escape codes used by `BoardStyle::Ansi`.
+#[cfg(feature = "render")]
+const ANSI_X: &str = "31";
+#[cfg(feature = "render")]
+const ANSI_O: &str = "34";
+#[cfg(feature = "render")]
+const ANSI_ACTIVE_ZONE: &str = "100";
+#[cfg(feature = "render")]
+const ANSI_LAST_MOVE: &str = "1;4";
+
+// Wraps a string in the given ANSI escape codes, if any.
+#[cfg(feature = "render")]
+fn ansi_style(text: &str, codes: &[&str]) -> String {
+    if codes.is_empty() {
+        text.to_string()
+    } else {
+        format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+    }
+}
+
+// Used to output an ASCII art representation of the board.
+#[cfg(feature = "render")]
+pub fn print_board(board: Board, style: BoardStyle) -> String {
+
+    let mut lines: Vec<String> = Vec::new();
+
+    // Destructure and retrieve values from board.
+    let (us, them, share) = board;
+    let zone = (share >> 54) & 0b1111;
+
+    // Map each of the bits to the coresponding string representations.
+    // That is, "X" for Player X, "O" for Player O, and "." for non-occupied.
+    let small = (0..63)
+        .map(|i| {
+            if ((us >> i) & 1) == 1 {
+                "X".to_string()
+            } else if ((them >> i) & 1) == 1 {
+                "O".to_string()
+            } else {
+                ".".to_string()
+            }
+        })
+        .chain((0..18).map(|i| {
+            if ((share >> i) & 1) == 1 {
+                "X".to_string()
+            } else if ((share >> (i + 18)) & 1) == 1 {
+                "O".to_string()
+            } else {
+                ".".to_string()
+            }
+        }))
+        .collect::<Vec<_>>();
+
+    // Similar mapping for large grid.
+    let large = (0..9)
+        .map(|i| {
+            if ((share >> (i + 36)) & 1) == 1 {
+                "X".to_string()
+            } else if ((share >> (i + 45)) & 1) == 1 {
+                "O".to_string()
+            } else {
+                ".".to_string()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // Cells are indexed as moves, so the zone of each is its index divided by 9.
+    let (small, large) = match style {
+        BoardStyle::Plain => (small, large),
+        BoardStyle::Ansi { last_move } => {
+            let active = generate_moves(board).fold(0u64, |zones, mv| zones | 1 << (mv / 9));
+            let mark_codes = |mark: &str| match mark {
+                "X" => vec![ANSI_X],
+                "O" => vec![ANSI_O],
+                _ => vec![],
+            };
+            let small = small
+                .iter()
+                .enumerate()
+                .map(|(i, mark)| {
+                    let mut codes = mark_codes(mark);
+                    if (active >> (i / 9)) & 1 == 1 {
+                        codes.push(ANSI_ACTIVE_ZONE);
+                    }
+                    if last_move == Some(i as Move) {
+                        codes.push(ANSI_LAST_MOVE);
+                    }
+                    ansi_style(mark, &codes)
+                })
+                .collect::<Vec<_>>();
+            let large = large
+                .iter()
+                .map(|mark| ansi_style(mark, &mark_codes(mark)))
+                .collect::<Vec<_>>();
+            (small, large)
+        }
+    };
+
+    // After organising occupancies into Vec, iterate through and print.
+    lines.push("---+---+---".to_string());
+    for i in (0..81).step_by(27) {
+        for j in (0..9).step_by(3) {
+            let line = (0..27)
+                .step_by(9)
+                .map(|k| small[i + j + k..i + j + k + 3].join(""))
+                .collect::<Vec<_>>()
+                .join("|");
+            lines.push(line);
+        }
+        lines.push("---+---+---".to_string());
+    }
+    for i in (0..9).step_by(3) {
+        lines.push(large[i..i + 3].join(""));
+    }
+    let line = format!(
+        "ZONE: {}",
+        ZONE_ARRAY_UPPER.get(zone as usize).unwrap_or(&"ANY")
+    );
+    lines.push(line);
+
+    lines.join("\n")
+}
+
+// Converts a `u64` move representation to a string,
+// or `none` for the null move or any other value that is not a move.
+pub fn move_string(mv: Move) -> String {
+    if mv >= 81 {
+        return "none".to_string();
+    }
+    format!(
+        "{0}/{1}",
+        ZONE_ARRAY_LOWER[(mv / 9) as usize],
+        ZONE_ARRAY_LOWER[(mv % 9) as usize]
+    )
+}
+
+// Returns the internal move representation from its string representation.
+pub fn move_from_string(move_string: &str) -> Option<Move> {
+    let zone_and_square: Vec<_> = move_string.split('/').collect();
+    if zone_and_square.len() != 2 {
+        return None;
+    }
+    let zone = ZONE_ARRAY_LOWER
+        .iter()
+        .position(|&z| z == zone_and_square[0]);
+    let square = ZONE_ARRAY_LOWER
+        .iter()
+        .position(|&s| s == zone_and_square[1]);
+    if let (Some(z), Some(s)) = (zone, square) {
+        Some(9 * z as u64 + s as u64)
+    } else {
+        None
+    }
+}
+
+// Converts a `i32` heuristic evaluation value to a string.
+// Heuristic scores are printed with a sign, in the units of the evaluation
+// weights, where a single mark in an open line of a small grid is worth 1.
+// Decisive scores are printed as `W<n>` or `L<n>` for a win or loss in `n` plies.
+// A score of 0 is printed as `+0` whether or not the position is drawn, as search scores
+// do not tell; a draw proven by the solver is printed by `draw_string` instead.
+// The sign convention depends on the perspective the score was taken from,
+// which the caller must normalise beforehand (see `ScorePerspective`).
+pub fn eval_string(eval: i32, max_depth: usize) -> String {
+    if eval <= OUTCOME_LOSS + max_depth as i32 {
+        format!("L{0}", eval - OUTCOME_LOSS)
+    } else if eval >= OUTCOME_WIN - max_depth as i32 {
+        format!("W{0}", OUTCOME_WIN - eval)
+    } else {
+        format!("{:+0}", eval)
+    }
+}
+
+// Converts a proven draw to a string, as `D<n>` for a game over within at most `n` plies
+// (see `MateSearch::draw_distance`), so that a dead draw can be told from a balanced position.
+pub fn draw_string(plies: usize) -> String {
+    format!("D{plies}")
+}
+
+// Parses a score in the format of `eval_string` or `draw_string`, the inverse of those
+// functions, where the distance of a draw is not kept. Distances beyond `MAX_PLY` plies,
+// and scores beyond those of a win or loss, are rejected, as no search reports them.
+pub fn eval_from_string(eval_string: &str) -> Option<i32> {
+    let distance = |plies: &str| plies.parse::<usize>().ok().filter(|&n| n <= MAX_PLY);
+    if let Some(plies) = eval_string.strip_prefix('W') {
+        distance(plies).map(|n| OUTCOME_WIN - n as i32)
+    } else if let Some(plies) = eval_string.strip_prefix('L') {
+        distance(plies).map(|n| OUTCOME_LOSS + n as i32)
+    } else if let Some(plies) = eval_string.strip_prefix('D') {
+        distance(plies).map(|_| OUTCOME_DRAW)
+    } else {
+        let eval = eval_string.parse().ok()?;
+        Some(eval).filter(|eval| (OUTCOME_LOSS..=OUTCOME_WIN).contains(eval))
+    }
+}
+
+// Compressed inline string representation for compact passing of Board setups.
+pub fn board_string(board: Board) -> String {
+    let (us, them, share) = board;
+    let zone = (share >> 54) & 0b1111;
+    let cells = (0..81).step_by(27).flat_map(move |i| {
+        (0..9).step_by(3).map(move |j| {
+            (0..27)
+                .step_by(9)
+                .flat_map(move |k| i + j + k..i + j + k + 3)
+        })
+    });
+    format!(
+        "{} {}",
+        cells
+            .map(|v| v
+                .map(|i| {
+                    if i > 62 {
+                        if ((share >> (i - 63)) & 1) == 1 {
+                            "x".to_string()
+                        } else if ((share >> (i - 45)) & 1) == 1 {
+                            "o".to_string()
+                        } else {
+                            ".".to_string()
+                        }
+                    } else if ((us >> i) & 1) == 1 {
+                        "x".to_string()
+                    } else if ((them >> i) & 1) == 1 {
+                        "o".to_string()
+                    } else {
+                        ".".to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(""))
+            .collect::<Vec<_>>()
+            .join("/")
+            .replace(".........", "9")
+            .replace("........", "8")
+            .replace(".......", "7")
+            .replace("......", "6")
+            .replace(".....", "5")
+            .replace("....", "4")
+            .replace("...", "3")
+            .replace("..", "2")
+            .replace('.', "1"),
+        // Zone values above `ZONE_ANY` do not occur in valid boards,
+        // but are printed as `any` rather than panicking.
+        ZONE_ARRAY_LOWER.get(zone as usize).unwrap_or(&"any")
+    )
+}
+
+// Returns a board from the raw values of its three `u64` components,
+// given as decimal numbers separated by whitespace.
+pub fn board_from_values(board_values: &str) -> Option<Board> {
+    let segments: Vec<&str> = board_values.split_whitespace().collect();
+    if segments.len() != 3 {
+        return None;
+    }
+    if let (Ok(us), Ok(them), Ok(share)) = (
+        segments[0].parse::<u64>(),
+        segments[1].parse::<u64>(),
+        segments[2].parse::<u64>(),
+    ) {
+        Some((us, them, share))
+    } else {
+        None
+    }
+}
+
+// Length of the binary encoding of a board, from `board_to_bytes`.
+pub const BOARD_BYTES: usize = 24;
+
+// Set in the third component of an encoded board when O is to move.
+// The bits between the zone field and this bit are unused, and must be clear.
+const SIDE_BIT: u64 = 1 << 63;
+const UNUSED_BITS: u64 = 0b11111 << 58;
+
+// Packs a board and the side to move (`false` for X) into a fixed 24 bytes,
+// for storing positions in bulk: the three `u64` components in little-endian order,
+// with the side to move in the otherwise unused top bit of the third.
+pub fn board_to_bytes(board: Board, side: bool) -> [u8; BOARD_BYTES] {
+    let (us, them, share) = board;
+    let share = if side { share | SIDE_BIT } else { share };
+    let mut bytes = [0; BOARD_BYTES];
+    bytes[..8].copy_from_slice(&us.to_le_bytes());
+    bytes[8..16].copy_from_slice(&them.to_le_bytes());
+    bytes[16..].copy_from_slice(&share.to_le_bytes());
+    bytes
+}
+
+// Returns the board and side to move from their encoding by `board_to_bytes`,
+// or `None` if the length is wrong or an unused bit is set.
+// The board itself is not checked for consistency.
+pub fn board_from_bytes(bytes: &[u8]) -> Option<(Board, bool)> {
+    if bytes.len() != BOARD_BYTES {
+        return None;
+    }
+    let component = |i: usize| u64::from_le_bytes(bytes[8 * i..8 * i + 8].try_into().unwrap());
+    let (us, them, share) = (component(0), component(1), component(2));
+    if share & UNUSED_BITS != 0 {
+        return None;
+    }
+    Some(((us, them, share & !SIDE_BIT), share & SIDE_BIT != 0))
+}
+
+// Returns an internal board representation from its string representation.
+// Any malformed input, such as an unknown character or a row of the wrong
+// length, is rejected rather than misaligning the remaining cells.
+// The string may begin with a version tag (see `versioning`).
+pub fn board_from_string(board_string: &str) -> Option<Board> {
+    let (mut us, mut them, mut share) = (0u64, 0u64, 0u64);
+    let board_string = migrate_text(Format::BoardString, board_string).ok()?;
+    let cell_and_zone: Vec<_> = board_string.split_whitespace().collect();
+    if cell_and_zone.len() != 2 {
+        return None;
+    }
+    let (cell, zone) = (cell_and_zone[0], cell_and_zone[1]);
+    if let Some(z) = ZONE_ARRAY_LOWER.iter().position(|&z| z == zone) {
+        share |= (z as u64) << 54;
+    } else if zone == "any" {
+        share |= ZONE_ANY << 54;
+    } else {
+        return None;
+    }
+    // Each digit stands for that many empty cells, and is expanded separately,
+    // so that `18` is a run of one followed by a run of eight.
+    let mut cells: Vec<char> = Vec::with_capacity(81);
+    for row in cell.split('/') {
+        let row_start = cells.len();
+        for c in row.chars() {
+            match c {
+                'x' | 'o' | '.' => cells.push(c),
+                '1'..='9' => cells.extend(std::iter::repeat_n('.', (c as u8 - b'0') as usize)),
+                _ => return None,
+            }
+            if cells.len() - row_start > 9 {
+                return None;
+            }
+        }
+        if cells.len() - row_start != 9 {
+            return None;
+        }
+    }
+    if cells.len() != 81 {
+        return None;
+    }
+    cells
+        .into_iter()
+        .zip((0..81).step_by(27).flat_map(move |i| {
+            (0..9).step_by(3).flat_map(move |j| {
+                (0..27)
+                    .step_by(9)
+                    .flat_map(move |k| i + j + k..i + j + k + 3)
+            })
+        }))
+        .for_each(|(c, i)| {
+            if i > 62 {
+                if c == 'x' {
+                    share |= 1 << (i - 63);
+                } else if c == 'o' {
+                    share |= 1 << (i - 45);
+                }
+            } else if c == 'x' {
+                us |= 1 << i;
+            } else if c == 'o' {
+                them |= 1 << i;
+            }
+        });
+    let first_seven_us = us;
+    let first_seven_them = them;
+    for i in 0..7 {
+        if line_presence(first_seven_us >> (9 * i)) {
+            share |= 1 << (36 + i);
+        } else if line_presence(first_seven_them >> (9 * i)) {
+            share |= 1 << (45 + i);
+        }
+    }
+    let last_two_us = share;
+    let last_two_them = share >> 18;
+    for i in 7..9 {
+        if line_presence(last_two_us >> (9 * i - 63)) {
+            share |= 1 << (36 + i);
+        } else if line_presence(last_two_them >> (9 * i - 63)) {
+            share |= 1 << (45 + i);
+        }
+    }
+    Some((us, them, share))
+}
@@ -0,0 +1,95 @@
+use std::{
+    io::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use serde::Serialize;
+
+use crate::{
+    engine::{Board, Eval, Move, NULL_MOVE},
+    move_string,
+    zobrist::hash_position,
+};
+
+/*
+ * Structured logging of searches and moves as JSON lines, for tooling that aggregates
+ * the output of long runs such as tuning matches. Nothing is logged until a sink is set
+ * with `set_sink`, as the native command line interface does for `--log json`,
+ * and checking whether logging is on costs a single atomic load, so the searches of
+ * the browser build, which never logs, are not slowed down.
+ */
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static SINK: Mutex<Option<Box<dyn Write + Send>>> = Mutex::new(None);
+
+// A log record, written as a JSON object with its kind in the `event` field.
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum LogRecord {
+    // A completed search, with the result of its deepest iteration.
+    Search {
+        // The hash of the position searched, as 16 hexadecimal digits.
+        hash: String,
+        depth: usize,
+        seldepth: usize,
+        score: Eval,
+        nodes: u64,
+        time_ms: u64,
+        pv: Vec<String>,
+    },
+    // A move played by the engine in a game, with the score of the search that chose it.
+    Move {
+        hash: String,
+        ply: usize,
+        side: &'static str,
+        #[serde(rename = "move")]
+        mv: String,
+        score: Eval,
+        depth: usize,
+    },
+    // A finished game of a match.
+    Game {
+        game: usize,
+        result: &'static str,
+        termination: &'static str,
+        moves: String,
+    },
+}
+
+// Sends log records to `sink`, one JSON object per line, from now on.
+pub fn set_sink(sink: Box<dyn Write + Send>) {
+    *SINK.lock().unwrap() = Some(sink);
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+// Whether log records are being written, so that callers can skip building them.
+#[inline]
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+// Writes a record to the sink, if there is one. Failures to write are ignored,
+// as logging must never interrupt the work being logged.
+pub fn log(record: &LogRecord) {
+    if !enabled() {
+        return;
+    }
+    if let Some(sink) = SINK.lock().unwrap().as_mut() {
+        if let Ok(line) = serde_json::to_string(record) {
+            let _ = writeln!(sink, "{line}");
+        }
+    }
+}
+
+// The hash of a position as written in log records.
+pub fn position_hash(board: Board, side: bool) -> String {
+    format!("{:016x}", hash_position(board, side))
+}
+
+// A principal variation as written in log records, up to its first empty slot.
+pub fn pv_strings(pv: &[Move]) -> Vec<String> {
+    pv.iter().take_while(|&&mv| mv != NULL_MOVE).map(|&mv| move_string(mv)).collect()
+}
@@ -0,0 +1,1009 @@
+/**
+ * Native command line interface to the engine, for tooling that runs
+ * outside the browser. Usage:
+ *
+ *     uttt-rust testsuite <file> [--time <ms>] [--depth <plies>] [--hash <mb>]
+ *     uttt-rust opening-tree <file> [--plies <plies>]
+ *     uttt-rust book <file> --out <file>
+ *     uttt-rust bench [--depth <plies>] [--shallow-ordering] [--verify <n>] [--best-first]
+ *     uttt-rust analyze [<file>] [--depth <plies>] [--time <ms>] [--hash <mb>]
+ *     uttt-rust match [--games <n>] [--depth <plies>] [--depth-b <plies>] [--hash <mb>]
+ *                     [--openings <file>] [--seed <n>] [--solver <plies>] [--no-adjudication]
+ *                     [--records <file>] [--training <file> [--augment]]
+ *     uttt-rust show [<file>] [--plain]
+ *     uttt-rust play <text|codingame> [--time <ms>] [--first-time <ms>] [--hash <mb>]
+ *     uttt-rust ndjson [<file>] [--depth <plies>] [--hash <mb>]
+ *     uttt-rust import [<file>] [--notation <auto|internal|tuple|rowcol>]
+ *     uttt-rust tune [--iterations <n>] [--pairs <n>] [--depth <plies>] [--hash <mb>]
+ *                    [--time <ms> [--increment <ms>]] [--seed <n>] [--params <name,...>]
+ *     uttt-rust calibrate [--max-depth <plies>] [--pairs <n>] [--hash <mb>] [--seed <n>]
+ *     uttt-rust regress [<suite>] [--positions <n>] [--seed <n>] [--depth <plies>] [--hash <mb>]
+ *                       [--a <settings>] [--b <settings>] [--save <file>] [--compare <file>]
+ *                       [--tolerance <eval>]
+ *     uttt-rust movegen-check [--depth <plies>] [--positions <n>] [--seed <n>]
+ *     uttt-rust tablebase --out <file> [--max-empty <cells>] [--games <n>] [--seed <n>]
+    uttt-rust win-tables --out <file> [--games <n>] [--depth <plies>] [--seed <n>]
+ *     uttt-rust win-tables --out <file> [--games <n>] [--depth <plies>] [--seed <n>]
+ *
+ * Any command may be given `--log json`, which writes a JSON object to standard error
+ * for every search made, every move the engine plays in a game, and every game of a match
+ * (see `log`), for tooling that aggregates the results of long runs.
+ *
+ * `analyze` reads board strings or game transcripts, one per line, from the file
+ * (or standard input if it is absent), and writes a JSON object for each line.
+ * `show` reads the same input and draws each board, in colour if the output is
+ * a terminal (unless `--plain` is given), marking the last move of transcripts.
+ *
+ * `play` plays over standard input and output in the given protocol: the engine's own
+ * text protocol (see `TextProtocol`), or that of CodinGame's Ultimate Tic-Tac-Toe arena,
+ * searching each move for the given time (and for the first move, for CodinGame).
+ *
+ * `ndjson` converts a file of match records to NDJSON (see `ndjson`), a line per game.
+ * With `--depth`, each game is analysed move by move, and the analysis embedded in its line.
+ *
+ * `import` reads a game written by another implementation (see `notation`), detecting
+ * its notation unless one is given, and prints its transcript for `analyze`.
+ *
+ * A match is played between engine A (`--depth`) and engine B (`--depth-b`),
+ * which swap sides after every game, so that each opening is played from both sides.
+ * With `--records`, every game is also written to the file as a match record (see `record`).
+ * With `--training`, every searched position is written to the file as a training sample
+ * (see `training_line`), and with `--augment`, in each of its eight symmetric variants.
+ *
+ * `tune` tunes the parameters named by `--params` (or all of them) by SPSA (see `spsa`),
+ * printing the parameters after each iteration, and those reached at the end. Games are
+ * played at `--depth`, or with `--time`, on a clock of that many milliseconds with
+ * `--increment` added after each move, when the time management factors are tuned too.
+ *
+ * `calibrate` measures the rating of each depth from 1 to `--max-depth` (see `calibration`),
+ * printing each as it is found, followed by the ratings as `DEPTH_RATINGS` is written.
+ *
+ * `regress` searches the positions of a suite (or random positions) with engine A,
+ * configured by `--a`, and compares its moves and scores with those of engine B,
+ * configured by `--b`, or with results saved from another build by `--save` and read back
+ * by `--compare` (see `regression`). Settings are comma-separated `<name>=<value>` pairs.
+ * Every divergence is printed, and the command fails if there are any.
+ *
+ * `movegen-check`, in builds with the `dev-tools` feature, compares the two move generators,
+ * and the masks of `legal_move_mask`, on a whole game tree to `--depth` and on
+ * the positions of random games (see `movegen_check`), printing any positions where they
+ * disagree, and fails if there are any.
+ *
+ * `book` learns the games of a file of game records (as for `opening-tree`) in order,
+ * and saves the book to the file given by `--out`, for shipping to the browser.
+ *
+ * `tablebase` solves the late positions of `--games` random games, each from the first
+ * position with at most `--max-empty` empty cells in open zones, and saves the tablebase
+ * to the file given by `--out` (see `tablebase`), for shipping to the browser.
+ *
+ * `win-tables` plays `--games` games of self-play at `--depth`, each from a random opening,
+ * and saves the lookup tables learnt from their results to the file given by `--out`
+ * (see `win_tables`), for shipping to the browser.
+ */
+use std::{
+    env, fs,
+    io::{self, BufRead},
+    process,
+};
+
+use uttt_core::{
+    board_from_string, board_string, eval_string, load_tables, move_string,
+    adjudication::AdjudicationRules,
+    calibration::calibrate as calibrate_depths,
+    codingame::{self, CodinGame},
+    analysis::{analyze_game, analyze_position},
+    best_first::{BestFirstSearch, DEFAULT_MAX_NODES},
+    engine::{
+        board_from_choices, check_structure, correct_zone, perft, Eval, EvalParams, Move, MAX_PLY,
+    },
+    game::{Game, GameResult},
+    log::{self, LogRecord},
+    ndjson::{write_entries, GameEntry},
+    notation::{import_game, Notation},
+    rng::Rng,
+    options::ScorePerspective,
+    protocol::{self, Protocol, TextProtocol},
+    record::{parse_records, record_string, GameRecord},
+    regression::{
+        compare, parse_result_line, random_positions, result_line, run_positions,
+        RegressionConfig,
+    },
+    spsa::{tune as spsa_tune, SpsaSettings, TunedParams},
+    selfplay::{
+        play_game, random_opening, training_line, training_samples, MatchSettings, PlayerSettings,
+    },
+    testsuite::{parse_test_position, run_test_position},
+    search::{iterative_deepening, SearchContext, Verification},
+    tablebase::{self, DEFAULT_MAX_EMPTY},
+    win_tables,
+    timeman::{TimeControl, TimeParams},
+    timer::{nodes_per_second, Timer},
+    tt::{TranspositionTable, DEFAULT_HASH_MB},
+};
+
+#[cfg(feature = "dev-tools")]
+use uttt_core::movegen_check::{check_random, check_tree, MovegenReport};
+#[cfg(feature = "book")]
+use uttt_core::{book::Book, game::parse_game_record, opening_tree::opening_tree_from_records};
+#[cfg(feature = "render")]
+use uttt_core::{print_board, BoardStyle};
+
+const USAGE: &str = "usage:
+    uttt-rust testsuite <file> [--time <ms>] [--depth <plies>] [--hash <mb>]
+    uttt-rust opening-tree <file> [--plies <plies>]
+    uttt-rust book <file> --out <file>
+    uttt-rust bench [--depth <plies>] [--shallow-ordering] [--verify <n>] [--best-first]
+    uttt-rust analyze [<file>] [--depth <plies>] [--time <ms>] [--hash <mb>]
+    uttt-rust match [--games <n>] [--depth <plies>] [--depth-b <plies>] [--hash <mb>]
+                    [--openings <file>] [--seed <n>] [--solver <plies>] [--no-adjudication]
+                    [--records <file>] [--training <file> [--augment]]
+    uttt-rust show [<file>] [--plain]
+    uttt-rust play <text|codingame> [--time <ms>] [--first-time <ms>] [--hash <mb>]
+    uttt-rust ndjson [<file>] [--depth <plies>] [--hash <mb>]
+    uttt-rust import [<file>] [--notation <auto|internal|tuple|rowcol>]
+    uttt-rust tune [--iterations <n>] [--pairs <n>] [--depth <plies>] [--hash <mb>]
+                   [--time <ms> [--increment <ms>]] [--seed <n>] [--params <name,...>]
+    uttt-rust calibrate [--max-depth <plies>] [--pairs <n>] [--hash <mb>] [--seed <n>]
+    uttt-rust regress [<suite>] [--positions <n>] [--seed <n>] [--depth <plies>] [--hash <mb>]
+                      [--a <settings>] [--b <settings>] [--save <file>] [--compare <file>]
+                      [--tolerance <eval>]
+    uttt-rust movegen-check [--depth <plies>] [--positions <n>] [--seed <n>]
+    uttt-rust tablebase --out <file> [--max-empty <cells>] [--games <n>] [--seed <n>]
+any command may be followed by [--log json]";
+
+// Default time limit per test position, in milliseconds.
+const DEFAULT_TIME_MS: u64 = 1000;
+
+// Default number of moves of each game counted in an opening tree.
+#[cfg(feature = "book")]
+const DEFAULT_TREE_PLIES: usize = 8;
+
+// Default search depth of each benchmark position, and the depth of its perft.
+const DEFAULT_BENCH_DEPTH: usize = 10;
+const BENCH_PERFT_DEPTH: usize = 6;
+
+// Benchmark positions, given as choices for `board_from_choices`
+// so that they need not be updated if the board representation changes.
+const BENCH_CHOICES: [&[u8]; 4] = [
+    &[],
+    &[40, 3, 17, 250, 8, 1],
+    &[7, 7, 100, 2, 61, 5, 33, 9, 4, 12, 0, 18],
+    &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20],
+];
+
+// Default search depth for analysis without a time limit.
+const DEFAULT_ANALYZE_DEPTH: usize = 8;
+
+// Defaults for matches. Without an openings file, each pair of games starts
+// from a random opening of `DEFAULT_OPENING_PLIES` moves.
+const DEFAULT_GAMES: usize = 10;
+const DEFAULT_MATCH_DEPTH: usize = 6;
+const DEFAULT_OPENING_PLIES: usize = 4;
+const DEFAULT_SOLVER_PLIES: usize = 7;
+
+// Defaults for tuning, where many short games are worth more than a few deep ones.
+const DEFAULT_TUNE_ITERATIONS: usize = 200;
+const DEFAULT_TUNE_PAIRS: usize = 2;
+const DEFAULT_TUNE_DEPTH: usize = 4;
+const DEFAULT_TUNE_STEP: f64 = 1.0;
+const DEFAULT_TUNE_LEARNING_RATE: f64 = 1.0;
+
+// Defaults for calibration, enough games for each rating to be within about 50 points.
+const DEFAULT_CALIBRATION_MAX_DEPTH: usize = 8;
+const DEFAULT_CALIBRATION_PAIRS: usize = 100;
+
+// Defaults for regression runs, which compare results exactly unless told otherwise.
+const DEFAULT_REGRESSION_POSITIONS: usize = 200;
+const DEFAULT_REGRESSION_DEPTH: usize = 6;
+
+// Defaults for checking the move generators, which take a second or two.
+#[cfg(feature = "dev-tools")]
+const DEFAULT_MOVEGEN_CHECK_DEPTH: usize = 5;
+#[cfg(feature = "dev-tools")]
+const DEFAULT_MOVEGEN_CHECK_POSITIONS: u64 = 5_000_000;
+
+// Default number of games whose late positions are solved for a tablebase.
+const DEFAULT_TABLEBASE_GAMES: usize = 100;
+
+// Defaults for learning lookup tables, from many quick games with varied openings.
+const DEFAULT_WIN_TABLE_GAMES: usize = 1000;
+const DEFAULT_WIN_TABLE_DEPTH: usize = 4;
+const DEFAULT_WIN_TABLE_OPENING_PLIES: usize = 8;
+
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    if let Err(e) = set_up_logging(&mut args) {
+        eprintln!("{e}");
+        process::exit(2);
+    }
+    let result = match args.first().map(String::as_str) {
+        Some("testsuite") => testsuite(&args[1..]),
+        Some("bench") => bench(&args[1..]),
+        Some("analyze") => analyze(&args[1..]),
+        Some("match") => play_match(&args[1..]),
+        Some("play") => play(&args[1..]),
+        Some("ndjson") => ndjson(&args[1..]),
+        Some("import") => import(&args[1..]),
+        Some("tune") => tune(&args[1..]),
+        Some("calibrate") => calibrate(&args[1..]),
+        Some("regress") => regress(&args[1..]),
+        Some("tablebase") => build_tablebase(&args[1..]),
+        Some("win-tables") => build_win_tables(&args[1..]),
+        #[cfg(feature = "dev-tools")]
+        Some("movegen-check") => movegen_check(&args[1..]),
+        #[cfg(feature = "render")]
+        Some("show") => show(&args[1..]),
+        #[cfg(feature = "book")]
+        Some("opening-tree") => opening_tree(&args[1..]),
+        #[cfg(feature = "book")]
+        Some("book") => book(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+    if let Err(e) = result {
+        eprintln!("{e}");
+        process::exit(2);
+    }
+}
+
+// Handles `--log <format>`, removing it from the arguments so that commands need not
+// know of it, and starts logging to standard error in that format.
+fn set_up_logging(args: &mut Vec<String>) -> Result<(), String> {
+    if let Some(i) = args.iter().position(|arg| arg == "--log") {
+        match args.get(i + 1).map(String::as_str) {
+            Some("json") => log::set_sink(Box::new(io::stderr())),
+            Some(_) => return Err("log format unknown".to_string()),
+            None => return Err(USAGE.to_string()),
+        }
+        args.drain(i..i + 2);
+    }
+    Ok(())
+}
+
+// Returns the value following `flag` in the arguments, parsed,
+// or `default` if the flag is absent.
+fn flag_value<T: std::str::FromStr>(args: &[String], flag: &str, default: T) -> Result<T, String> {
+    match args.iter().position(|arg| arg == flag) {
+        Some(i) => args
+            .get(i + 1)
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| format!("invalid value for {flag}")),
+        None => Ok(default),
+    }
+}
+
+// Reads the lines of a file, or of standard input if the path is absent or `-`.
+fn read_lines(path: Option<&String>) -> Result<Vec<String>, String> {
+    match path.map(String::as_str) {
+        None | Some("-") => io::stdin()
+            .lock()
+            .lines()
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string()),
+        Some(path) => Ok(fs::read_to_string(path)
+            .map_err(|e| format!("{path}: {e}"))?
+            .lines()
+            .map(String::from)
+            .collect()),
+    }
+}
+
+// Runs every position of a test suite file and reports how many were solved.
+fn testsuite(args: &[String]) -> Result<(), String> {
+    let path = args.first().ok_or_else(|| USAGE.to_string())?;
+    let time_ms = flag_value(args, "--time", DEFAULT_TIME_MS)?;
+    let max_depth = flag_value(args, "--depth", MAX_PLY)?;
+    let hash_mb = flag_value(args, "--hash", DEFAULT_HASH_MB)?;
+
+    let contents = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    let tables = load_tables();
+
+    let (mut solved, mut total) = (0, 0);
+    for (line_number, line) in contents.lines().enumerate() {
+        let position = match parse_test_position(line) {
+            Some(Ok(position)) => position,
+            Some(Err(e)) => return Err(format!("{path}:{}: {e}", line_number + 1)),
+            None => continue,
+        };
+        total += 1;
+        let id = if position.id.is_empty() {
+            format!("line {}", line_number + 1)
+        } else {
+            position.id.clone()
+        };
+        match run_test_position(&position, &tables, time_ms, max_depth, hash_mb) {
+            Some(outcome) => {
+                solved += outcome.solved as usize;
+                println!(
+                    "{} {} bestmove {} expected {} depth {} eval {}",
+                    id,
+                    if outcome.solved { "solved" } else { "unsolved" },
+                    move_string(outcome.best_move),
+                    position
+                        .best_moves
+                        .iter()
+                        .map(|&mv| move_string(mv))
+                        .collect::<Vec<_>>()
+                        .join(","),
+                    outcome.depth,
+                    eval_string(outcome.eval, outcome.depth),
+                );
+            }
+            None => println!("{id} unsolved (no iteration completed)"),
+        }
+    }
+    println!("solved {solved} of {total}");
+    Ok(())
+}
+
+// Aggregates a file of game records into an opening tree, printed as JSON.
+#[cfg(feature = "book")]
+fn opening_tree(args: &[String]) -> Result<(), String> {
+    let path = args.first().ok_or_else(|| USAGE.to_string())?;
+    let plies = flag_value(args, "--plies", DEFAULT_TREE_PLIES)?;
+    let contents = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    let tree = opening_tree_from_records(&contents, plies).map_err(|e| format!("{path}: {e}"))?;
+    println!("{}", serde_json::to_string(&tree.export()).map_err(|e| e.to_string())?);
+    Ok(())
+}
+
+// Builds an opening book from a file of game records, learning the games in order,
+// and saves it to the file given by `--out` in the format of `Book::to_bytes`.
+#[cfg(feature = "book")]
+fn book(args: &[String]) -> Result<(), String> {
+    let path = args.first().ok_or_else(|| USAGE.to_string())?;
+    let out = flag_value(args, "--out", String::new())?;
+    if out.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    let contents = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    let mut book = Book::new();
+    let mut games = 0;
+    for (line_number, record) in contents.lines().enumerate() {
+        if record.trim().is_empty() {
+            continue;
+        }
+        let (game, result) = parse_game_record(record)
+            .map_err(|e| format!("{path}: line {} {e}", line_number + 1))?;
+        book.learn(&game, result);
+        games += 1;
+    }
+    fs::write(&out, book.to_bytes()).map_err(|e| format!("{out}: {e}"))?;
+    println!("games {games} positions {}", book.len());
+    Ok(())
+}
+
+// Generates a tablebase and saves it to the file given by `--out`
+// in the format of `Tablebase::to_bytes`.
+fn build_tablebase(args: &[String]) -> Result<(), String> {
+    let out = flag_value(args, "--out", String::new())?;
+    if out.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    let max_empty = flag_value(args, "--max-empty", DEFAULT_MAX_EMPTY)?;
+    let games = flag_value(args, "--games", DEFAULT_TABLEBASE_GAMES)?;
+    let seed = flag_value(args, "--seed", 1)?;
+    if max_empty > 81 {
+        return Err("max empty invalid".to_string());
+    }
+    let timer = Timer::start();
+    let tablebase = tablebase::generate(max_empty, games, seed);
+    fs::write(&out, tablebase.to_bytes()).map_err(|e| format!("{out}: {e}"))?;
+    println!("positions {} time {}", tablebase.len(), timer.elapsed_ms());
+    Ok(())
+}
+
+// Learns lookup tables from self-play games and saves them to the file given by `--out`
+// in the format of `win_tables::to_bytes`.
+fn build_win_tables(args: &[String]) -> Result<(), String> {
+    let out = flag_value(args, "--out", String::new())?;
+    if out.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    let games = flag_value(args, "--games", DEFAULT_WIN_TABLE_GAMES)?;
+    let depth = flag_value(args, "--depth", DEFAULT_WIN_TABLE_DEPTH)?.clamp(1, MAX_PLY);
+    let seed = flag_value(args, "--seed", 1)?;
+    let settings = MatchSettings {
+        players: [PlayerSettings::new(depth, DEFAULT_HASH_MB); 2],
+        rules: Some(AdjudicationRules::default()),
+        solver_plies: DEFAULT_SOLVER_PLIES,
+    };
+    let timer = Timer::start();
+    let stats = win_tables::generate(
+        games,
+        &settings,
+        DEFAULT_WIN_TABLE_OPENING_PLIES,
+        seed,
+        &load_tables(),
+    );
+    fs::write(&out, win_tables::to_bytes(&stats.tables())).map_err(|e| format!("{out}: {e}"))?;
+    println!("games {} time {}", stats.games(), timer.elapsed_ms());
+    Ok(())
+}
+
+/**
+ * Measures the speed of the engine on a fixed set of positions, with a search
+ * of each (using a fresh transposition table) and a perft, which only exercises
+ * move generation and move application. The node counts double as a quick check
+ * that a change meant only to speed the engine up has not changed its behaviour.
+ * With `--verify`, every n-th pruning decision is checked by a full-depth search
+ * (see `Verification`), and the decisions it contradicts are listed for each position.
+ * The checking searches are counted in the node counts, so the speed is then not comparable.
+ * With `--best-first`, each position is also searched by the experimental best-first
+ * search (see `best_first`), growing a tree of as many nodes as the alpha-beta search visited.
+ */
+fn bench(args: &[String]) -> Result<(), String> {
+    let depth = flag_value(args, "--depth", DEFAULT_BENCH_DEPTH)?.min(MAX_PLY);
+    let shallow_ordering = args.iter().any(|arg| arg == "--shallow-ordering");
+    let best_first = args.iter().any(|arg| arg == "--best-first");
+    let verify_interval: Option<u64> = match args.iter().any(|arg| arg == "--verify") {
+        true => Some(flag_value(args, "--verify", 0)?),
+        false => None,
+    };
+    let tables = load_tables();
+    let (mut search_nodes, mut search_ms) = (0, 0);
+    let (mut perft_nodes, mut perft_ms) = (0, 0);
+    for (index, choices) in BENCH_CHOICES.iter().enumerate() {
+        let (board, side) = board_from_choices(choices);
+
+        let timer = Timer::start();
+        let mut tt = TranspositionTable::new(DEFAULT_HASH_MB);
+        let mut ctx = SearchContext::new(&tables, depth);
+        ctx.tt = Some(&mut tt);
+        ctx.shallow_ordering = shallow_ordering;
+        ctx.verification = verify_interval.map(Verification::new);
+        let result = iterative_deepening(board, side, depth, &mut ctx);
+        let elapsed = timer.elapsed_ms();
+        search_nodes += ctx.nodes;
+        search_ms += elapsed;
+
+        let timer = Timer::start();
+        let leaves = perft(board, side, BENCH_PERFT_DEPTH);
+        perft_nodes += leaves;
+        perft_ms += timer.elapsed_ms();
+
+        println!(
+            "position {} nodes {} time {} bestmove {} perft {}",
+            index + 1,
+            ctx.nodes,
+            elapsed,
+            result.map_or_else(|| "none".to_string(), |result| move_string(result.pv[0])),
+            leaves,
+        );
+        if let Some(verification) = &ctx.verification {
+            println!(
+                "verify decisions {} checks {} discrepancies {}",
+                verification.decisions,
+                verification.checks,
+                verification.discrepancies.len(),
+            );
+            for discrepancy in &verification.discrepancies {
+                println!("discrepancy {discrepancy:?}");
+            }
+        }
+        if best_first {
+            let timer = Timer::start();
+            let max_nodes = (ctx.nodes as usize).min(DEFAULT_MAX_NODES);
+            let result = BestFirstSearch::new(&tables).search(board, side, max_nodes);
+            if let Some(result) = result {
+                println!(
+                    "bestfirst nodes {} expansions {} time {} bestmove {} eval {} depth {}",
+                    result.nodes,
+                    result.expansions,
+                    timer.elapsed_ms(),
+                    move_string(result.pv[0]),
+                    eval_string(result.eval, result.pv.len()),
+                    result.pv.len(),
+                );
+            }
+        }
+    }
+    println!(
+        "search nodes {search_nodes} time {search_ms} nps {}",
+        nodes_per_second(search_nodes, search_ms),
+    );
+    println!(
+        "perft nodes {perft_nodes} time {perft_ms} nps {}",
+        nodes_per_second(perft_nodes, perft_ms),
+    );
+    Ok(())
+}
+
+/**
+ * Analyzes each line of the input, printing a JSON object for it with its line number.
+ * A board string is searched as a position, as by `analyze_batch`, and a transcript
+ * of moves from the empty board is analyzed move by move, as by `analyze_game`.
+ * The time limit applies to each line. A position stopped by it reports its deepest
+ * completed iteration, and is searched without a depth limit unless one is given,
+ * whereas a game stopped by it is reported as an error.
+ */
+fn analyze(args: &[String]) -> Result<(), String> {
+    let path = args.first().filter(|arg| !arg.starts_with("--"));
+    let time_ms: Option<u64> = match args.iter().any(|arg| arg == "--time") {
+        true => Some(flag_value(args, "--time", 0)?),
+        false => None,
+    };
+    let game_depth = flag_value(args, "--depth", DEFAULT_ANALYZE_DEPTH)?.min(MAX_PLY);
+    let position_depth = match args.iter().any(|arg| arg == "--depth") {
+        false if time_ms.is_some() => MAX_PLY,
+        _ => game_depth,
+    };
+    let hash_mb = flag_value(args, "--hash", DEFAULT_HASH_MB)?;
+
+    let lines = read_lines(path)?;
+
+    let tables = load_tables();
+    let mut tt = TranspositionTable::new(hash_mb);
+    let perspective = ScorePerspective::default();
+    for (line_number, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let timer = Timer::start();
+        let out_of_time = || time_ms.is_some_and(|limit| timer.elapsed_ms() >= limit);
+        let stop_condition: Option<&dyn Fn() -> bool> = Some(&out_of_time);
+        let board = board_from_string(line.trim())
+            .map(correct_zone)
+            .filter(|&board| check_structure(board).is_ok());
+        let mut output = match (board, Game::from_transcript(line)) {
+            (Some(board), _) => {
+                tt.clear();
+                let analysis = analyze_position(
+                    board,
+                    position_depth,
+                    &tables,
+                    EvalParams::DEFAULT,
+                    &mut tt,
+                    perspective,
+                    stop_condition,
+                );
+                match analysis {
+                    Some(analysis) => serde_json::to_value(analysis).map_err(|e| e.to_string())?,
+                    None => serde_json::json!({ "error": "search aborted" }),
+                }
+            }
+            (None, Ok(game)) => {
+                let analysis = analyze_game(
+                    &game,
+                    game_depth,
+                    &tables,
+                    EvalParams::DEFAULT,
+                    hash_mb,
+                    perspective,
+                    stop_condition,
+                );
+                match analysis {
+                    Some(analysis) => serde_json::json!({ "moves": analysis }),
+                    None => serde_json::json!({ "error": "search aborted" }),
+                }
+            }
+            (None, Err(_)) => serde_json::json!({ "error": "board or transcript invalid" }),
+        };
+        output["line"] = serde_json::json!(line_number + 1);
+        println!("{output}");
+    }
+    Ok(())
+}
+
+// Draws each board or game transcript of the input, with a blank line between them.
+#[cfg(feature = "render")]
+fn show(args: &[String]) -> Result<(), String> {
+    use std::io::IsTerminal;
+
+    let path = args.first().filter(|arg| !arg.starts_with("--"));
+    let colour = io::stdout().is_terminal() && !args.iter().any(|arg| arg == "--plain");
+    for (line_number, line) in read_lines(path)?.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let board = board_from_string(line.trim())
+            .map(correct_zone)
+            .filter(|&board| check_structure(board).is_ok());
+        let (board, last_move) = match (board, Game::from_transcript(line)) {
+            (Some(board), _) => (board, None),
+            (None, Ok(game)) => (game.board(), game.moves().last().copied()),
+            (None, Err(_)) => {
+                return Err(format!("line {}: board or transcript invalid", line_number + 1))
+            }
+        };
+        let style = match colour {
+            true => BoardStyle::Ansi { last_move },
+            false => BoardStyle::Plain,
+        };
+        println!("{}\n", print_board(board, style));
+    }
+    Ok(())
+}
+
+// Plays a match between two engines, printing a record of each game
+// (with how it ended) and the overall score of engine A.
+fn play_match(args: &[String]) -> Result<(), String> {
+    let games = flag_value(args, "--games", DEFAULT_GAMES)?;
+    let depth_a = flag_value(args, "--depth", DEFAULT_MATCH_DEPTH)?;
+    let depth_b = flag_value(args, "--depth-b", depth_a)?;
+    let hash_mb = flag_value(args, "--hash", DEFAULT_HASH_MB)?;
+    let seed = flag_value(args, "--seed", 1)?;
+    let solver_plies = flag_value(args, "--solver", DEFAULT_SOLVER_PLIES)?;
+    let adjudicate = !args.iter().any(|arg| arg == "--no-adjudication");
+
+    let openings = match args.iter().position(|arg| arg == "--openings") {
+        Some(i) => {
+            let path = args.get(i + 1).ok_or_else(|| USAGE.to_string())?;
+            let contents = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+            let openings = contents
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(line_number, line)| {
+                    Game::from_transcript(line)
+                        .map_err(|e| format!("{path}:{}: {e}", line_number + 1))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if openings.is_empty() {
+                return Err(format!("{path}: no openings"));
+            }
+            openings
+        }
+        None => Vec::new(),
+    };
+    let mut records = match args.iter().position(|arg| arg == "--records") {
+        Some(i) => {
+            let path = args.get(i + 1).ok_or_else(|| USAGE.to_string())?;
+            Some((path, String::new()))
+        }
+        None => None,
+    };
+
+    let mut training = match args.iter().position(|arg| arg == "--training") {
+        Some(i) => {
+            let path = args.get(i + 1).ok_or_else(|| USAGE.to_string())?;
+            Some((path, String::new()))
+        }
+        None => None,
+    };
+    let augment = args.iter().any(|arg| arg == "--augment");
+
+    let tables = load_tables();
+    let mut rng = Rng::new(seed);
+    let player_a = PlayerSettings::new(depth_a, hash_mb);
+    let player_b = PlayerSettings::new(depth_b, hash_mb);
+    let (mut wins, mut draws, mut losses) = (0, 0, 0);
+    let mut opening = Game::new();
+    for index in 0..games {
+        // Each opening is played twice, with the engines swapping sides.
+        if index % 2 == 0 {
+            opening = if openings.is_empty() {
+                random_opening(&mut rng, DEFAULT_OPENING_PLIES)
+            } else {
+                openings[(index / 2) % openings.len()].clone()
+            };
+        }
+        let a_is_x = index % 2 == 0;
+        let settings = MatchSettings {
+            players: if a_is_x { [player_a, player_b] } else { [player_b, player_a] },
+            rules: if adjudicate { Some(AdjudicationRules::default()) } else { None },
+            solver_plies,
+        };
+        let outcome = play_game(&opening, &settings, &tables);
+        match (outcome.result, a_is_x) {
+            (GameResult::Draw, _) => draws += 1,
+            (GameResult::XWins, true) | (GameResult::OWins, false) => wins += 1,
+            _ => losses += 1,
+        }
+        println!(
+            "game {} a {} {} {} {}",
+            index + 1,
+            if a_is_x { "x" } else { "o" },
+            outcome.result.notation(),
+            outcome.termination.name(),
+            outcome.game.transcript(),
+        );
+        log::log(&LogRecord::Game {
+            game: index + 1,
+            result: outcome.result.notation(),
+            termination: outcome.termination.name(),
+            moves: outcome.game.transcript(),
+        });
+        if let Some((_, text)) = &mut training {
+            for sample in training_samples(&outcome, augment) {
+                text.push_str(&training_line(&sample));
+                text.push('\n');
+            }
+        }
+        if let Some((_, text)) = &mut records {
+            let mut record = GameRecord::new(outcome.game, Some(outcome.result));
+            for (name, player) in ["X", "O"].iter().zip(settings.players) {
+                let engine = if (*name == "X") == a_is_x { "a" } else { "b" };
+                record.set_header(name, &format!("{engine} depth {}", player.depth));
+            }
+            record.set_header("Round", &(index + 1).to_string());
+            record.set_header("Termination", outcome.termination.name());
+            text.push_str(&record_string(&record));
+            text.push('\n');
+        }
+    }
+    for (path, text) in records.into_iter().chain(training) {
+        fs::write(path, text).map_err(|e| format!("{path}: {e}"))?;
+    }
+    println!("a wins {wins} draws {draws} losses {losses}");
+    Ok(())
+}
+
+// Tunes parameters by SPSA, printing the parameters tuned after each iteration.
+fn tune(args: &[String]) -> Result<(), String> {
+    let clock = match args.iter().any(|arg| arg == "--time") {
+        true => Some(TimeControl {
+            remaining_ms: flag_value(args, "--time", 0)?,
+            increment_ms: flag_value(args, "--increment", 0)?,
+            move_overhead_ms: 0,
+            params: TimeParams::DEFAULT,
+        }),
+        false => None,
+    };
+    // Without a clock, only the evaluation weights are tuned by default.
+    let defaults = match clock {
+        Some(_) => TunedParams {
+            eval: EvalParams::DEFAULT,
+            time: TimeParams::DEFAULT,
+        }
+        .values(),
+        None => EvalParams::DEFAULT.values().into_iter().map(|(n, v)| (n, v as i64)).collect(),
+    };
+    let names: Vec<&str> = match args.iter().position(|arg| arg == "--params") {
+        Some(i) => args.get(i + 1).ok_or_else(|| USAGE.to_string())?.split(',').collect(),
+        None => defaults.iter().map(|&(name, _)| name).collect(),
+    };
+    let settings = SpsaSettings {
+        iterations: flag_value(args, "--iterations", DEFAULT_TUNE_ITERATIONS)?,
+        pairs: flag_value(args, "--pairs", DEFAULT_TUNE_PAIRS)?,
+        opening_plies: DEFAULT_OPENING_PLIES,
+        player: PlayerSettings {
+            clock,
+            ..PlayerSettings::new(
+                flag_value(args, "--depth", DEFAULT_TUNE_DEPTH)?,
+                flag_value(args, "--hash", DEFAULT_HASH_MB)?,
+            )
+        },
+        rules: Some(AdjudicationRules::default()),
+        solver_plies: DEFAULT_SOLVER_PLIES,
+        step: DEFAULT_TUNE_STEP,
+        learning_rate: DEFAULT_TUNE_LEARNING_RATE,
+    };
+    let mut rng = Rng::new(flag_value(args, "--seed", 1)?);
+
+    // The parameters tuned, as `<name> <value>` pairs.
+    let weights = |params: &TunedParams| -> String {
+        let values = params.values();
+        let tuned = values.iter().filter(|(name, _)| names.contains(name));
+        tuned.map(|(name, value)| format!("{name} {value}")).collect::<Vec<_>>().join(" ")
+    };
+    let tuned = spsa_tune(&settings, &names, &mut rng, &mut |iteration| {
+        println!(
+            "iteration {} score {:.3} {}",
+            iteration.iteration,
+            iteration.score,
+            weights(&iteration.params),
+        );
+    })
+    .map_err(|e| e.to_string())?;
+    println!("tuned {}", weights(&tuned));
+    Ok(())
+}
+
+// Measures the rating of each depth, printing each as it is found.
+fn calibrate(args: &[String]) -> Result<(), String> {
+    let max_depth = flag_value(args, "--max-depth", DEFAULT_CALIBRATION_MAX_DEPTH)?;
+    let pairs = flag_value(args, "--pairs", DEFAULT_CALIBRATION_PAIRS)?;
+    let hash_mb = flag_value(args, "--hash", DEFAULT_HASH_MB)?;
+    let mut rng = Rng::new(flag_value(args, "--seed", 1)?);
+
+    let tables = load_tables();
+    let depths: Vec<usize> = (1..=max_depth.min(MAX_PLY)).collect();
+    let ratings = calibrate_depths(
+        &depths,
+        PlayerSettings::new(1, hash_mb),
+        &tables,
+        (pairs, DEFAULT_OPENING_PLIES),
+        (Some(AdjudicationRules::default()), DEFAULT_SOLVER_PLIES),
+        &mut rng,
+        &mut |rating| match rating.score {
+            Some(score) => println!(
+                "depth {} score {score:.3} rating {:.0}",
+                rating.depth, rating.rating
+            ),
+            None => println!("depth {} rating {:.0}", rating.depth, rating.rating),
+        },
+    );
+    let entries: Vec<String> = ratings
+        .iter()
+        .map(|rating| format!("    ({}, {:.0}),", rating.depth, rating.rating))
+        .collect();
+    println!("[\n{}\n]", entries.join("\n"));
+    Ok(())
+}
+
+// Compares the moves and scores of two engines on the same positions.
+fn regress(args: &[String]) -> Result<(), String> {
+    let path = args.first().filter(|arg| !arg.starts_with("--"));
+    let count = flag_value(args, "--positions", DEFAULT_REGRESSION_POSITIONS)?;
+    let seed = flag_value(args, "--seed", 1)?;
+    let depth = flag_value(args, "--depth", DEFAULT_REGRESSION_DEPTH)?.min(MAX_PLY);
+    let hash_mb = flag_value(args, "--hash", DEFAULT_HASH_MB)?;
+    let tolerance = flag_value(args, "--tolerance", 0)?;
+    let file_flag = |flag: &str| -> Result<Option<&String>, String> {
+        match args.iter().position(|arg| arg == flag) {
+            Some(i) => args.get(i + 1).map(Some).ok_or_else(|| USAGE.to_string()),
+            None => Ok(None),
+        }
+    };
+    let mut config_a = RegressionConfig::new(depth, hash_mb);
+    let mut config_b = config_a;
+    config_a.set(file_flag("--a")?.map_or("", String::as_str))?;
+    config_b.set(file_flag("--b")?.map_or("", String::as_str))?;
+
+    let positions = match path {
+        Some(path) => {
+            let contents = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+            let mut positions = Vec::new();
+            for (line_number, line) in contents.lines().enumerate() {
+                match parse_test_position(line) {
+                    Some(Ok(position)) => positions.push((position.board, position.side)),
+                    Some(Err(e)) => return Err(format!("{path}:{}: {e}", line_number + 1)),
+                    None => {}
+                }
+            }
+            positions
+        }
+        None => random_positions(count, seed),
+    };
+
+    let results_a = run_positions(&positions, &config_a);
+    if let Some(path) = file_flag("--save")? {
+        let lines: Vec<String> = results_a.iter().map(result_line).collect();
+        fs::write(path, lines.join("\n") + "\n").map_err(|e| format!("{path}: {e}"))?;
+        println!("saved {} results", results_a.len());
+        return Ok(());
+    }
+    let results_b = match file_flag("--compare")? {
+        Some(path) => {
+            let contents = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+            contents
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(line_number, line)| {
+                    parse_result_line(line).map_err(|e| format!("{path}:{}: {e}", line_number + 1))
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        }
+        None => run_positions(&positions, &config_b),
+    };
+
+    let divergences = compare(&results_a, &results_b, tolerance)?;
+    let best = |best: Option<(Move, Eval)>| match best {
+        Some((mv, eval)) => format!("{} {eval}", move_string(mv)),
+        None => "none".to_string(),
+    };
+    for divergence in &divergences {
+        println!(
+            "divergence {} {} {} a {} b {}",
+            divergence.index + 1,
+            board_string(divergence.a.board),
+            if divergence.a.side { "o" } else { "x" },
+            best(divergence.a.best),
+            best(divergence.b.best),
+        );
+    }
+    let moves = divergences.iter().filter(|d| d.move_differs).count();
+    println!(
+        "positions {} divergences {} moves {moves}",
+        positions.len(),
+        divergences.len()
+    );
+    match divergences.is_empty() {
+        true => Ok(()),
+        false => Err("regression found".to_string()),
+    }
+}
+
+// Cross-checks the move generators, printing any positions where they disagree.
+#[cfg(feature = "dev-tools")]
+fn movegen_check(args: &[String]) -> Result<(), String> {
+    let depth = flag_value(args, "--depth", DEFAULT_MOVEGEN_CHECK_DEPTH)?;
+    let positions = flag_value(args, "--positions", DEFAULT_MOVEGEN_CHECK_POSITIONS)?;
+    let seed = flag_value(args, "--seed", 1)?;
+    let mut passed = true;
+    for (name, report) in [("tree", check_tree(depth)), ("random", check_random(positions, seed))] {
+        let MovegenReport {
+            positions,
+            moves,
+            mismatch_count,
+            ..
+        } = report;
+        println!("{name} positions {positions} moves {moves} mismatches {mismatch_count}");
+        for mismatch in &report.mismatches {
+            let moves = |moves: &[u64]| {
+                moves.iter().map(|&mv| move_string(mv)).collect::<Vec<_>>().join(",")
+            };
+            println!(
+                "mismatch {}{} expected {} actual {} mask {:021x}",
+                board_string(mismatch.board),
+                if mismatch.order_only() { " order" } else { "" },
+                moves(&mismatch.expected),
+                moves(&mismatch.actual),
+                mismatch.mask,
+            );
+        }
+        passed &= report.passed();
+    }
+    match passed {
+        true => Ok(()),
+        false => Err("move generators disagree".to_string()),
+    }
+}
+
+// Plays over standard input and output in the given protocol. Log lines,
+// such as those which the CodinGame arena shows alongside the game, go to standard error.
+fn play(args: &[String]) -> Result<(), String> {
+    let mut protocol: Box<dyn Protocol> = match args.first().map(String::as_str) {
+        Some("text") => Box::new(TextProtocol {
+            time_ms: flag_value(args, "--time", DEFAULT_TIME_MS)?,
+        }),
+        Some("codingame") => Box::new(CodinGame {
+            first_time_ms: flag_value(args, "--first-time", codingame::DEFAULT_FIRST_TIME_MS)?,
+            time_ms: flag_value(args, "--time", codingame::DEFAULT_TIME_MS)?,
+        }),
+        _ => return Err(USAGE.to_string()),
+    };
+    let hash_mb = flag_value(args, "--hash", DEFAULT_HASH_MB)?;
+
+    let tables = load_tables();
+    let mut tt = TranspositionTable::new(hash_mb);
+    let mut lines = io::stdin().lock().lines().map_while(Result::ok);
+    protocol::run(
+        protocol.as_mut(),
+        &mut lines,
+        &mut io::stdout(),
+        &mut io::stderr(),
+        &tables,
+        &mut tt,
+    )
+}
+
+// Converts a file of match records to NDJSON, analysing each game if a depth is given.
+fn ndjson(args: &[String]) -> Result<(), String> {
+    let path = args.first().filter(|arg| !arg.starts_with("--"));
+    let depth: Option<usize> = match args.iter().any(|arg| arg == "--depth") {
+        true => Some(flag_value(args, "--depth", 0)?.min(MAX_PLY)),
+        false => None,
+    };
+    let hash_mb = flag_value(args, "--hash", DEFAULT_HASH_MB)?;
+    let records = parse_records(&read_lines(path)?.join("\n"))?;
+
+    let tables = load_tables();
+    let stdout = io::stdout();
+    let mut output = stdout.lock();
+    for record in records {
+        let analysis = depth.map(|depth| {
+            let perspective = ScorePerspective::default();
+            let params = EvalParams::DEFAULT;
+            let analysis =
+                analyze_game(&record.game, depth, &tables, params, hash_mb, perspective, None);
+            serde_json::json!(analysis)
+        });
+        write_entries(&[GameEntry { record, analysis }], &mut output)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+// Converts a game in another notation to a transcript.
+fn import(args: &[String]) -> Result<(), String> {
+    let path = args.first().filter(|arg| !arg.starts_with("--"));
+    let notation = match flag_value(args, "--notation", "auto".to_string())?.as_str() {
+        "auto" => None,
+        name => Some(Notation::from_name(name).ok_or("notation unknown")?),
+    };
+    let (_, game) = import_game(&read_lines(path)?.join("\n"), notation)?;
+    println!("{}", game.transcript());
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use crate::engine::{line_presence, zone_cells, Board, Eval, Move, ZONE_ANY};
+
+// No position has more legal moves than there are cells.
+pub const MAX_MOVES: usize = 81;
+
+/**
+ * A list of moves held in a fixed-size buffer, each with a score used to order them.
+ * Unlike the iterator returned by `generate_moves`, the moves can be reordered
+ * before they are searched. Nothing is allocated, so a list can live on the stack,
+ * though the search keeps one per ply to avoid setting up a buffer at every node.
+ */
+#[derive(Clone, Copy)]
+pub struct MoveList {
+    // Each move with its ordering score. Higher scores are searched first.
+    // Moves are stored as bytes to keep the buffer small, as it is set up at every node.
+    entries: [(u8, Eval); MAX_MOVES],
+    len: usize,
+}
+
+impl MoveList {
+    pub fn new() -> Self {
+        MoveList {
+            entries: [(0, 0); MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    // Appends a move with a score of zero.
+    #[inline]
+    pub fn push(&mut self, mv: Move) {
+        self.entries[self.len] = (mv as u8, 0);
+        self.len += 1;
+    }
+
+    #[inline]
+    pub fn get(&self, index: usize) -> Move {
+        self.entries[index].0 as Move
+    }
+
+    #[inline]
+    pub fn set_score(&mut self, index: usize, score: Eval) {
+        self.entries[index].1 = score;
+    }
+
+    // The ordering scores, in the order of the moves, to be set by the caller before sorting.
+    pub fn scores_mut(&mut self) -> impl Iterator<Item = &mut Eval> + '_ {
+        self.entries[..self.len].iter_mut().map(|(_, score)| score)
+    }
+
+    pub fn moves(&self) -> impl Iterator<Item = Move> + '_ {
+        self.entries[..self.len].iter().map(|&(mv, _)| mv as Move)
+    }
+
+    pub fn contains(&self, mv: Move) -> bool {
+        self.moves().any(|m| m == mv)
+    }
+
+    /**
+     * Moves `mv` to the front of the list, keeping the order of the other moves,
+     * and returns whether it was in the list. This puts the best move of an earlier
+     * search first without checking its legality separately.
+     */
+    pub fn move_to_front(&mut self, mv: Move) -> bool {
+        let position = self.moves().position(|m| m == mv);
+        match position {
+            Some(index) => {
+                self.entries[..=index].rotate_right(1);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Sorts the moves from `start` onwards by descending score. The sort is stable,
+    // so moves with equal scores keep the order in which they were generated.
+    pub fn sort_from(&mut self, start: usize) {
+        let start = start.min(self.len);
+        self.entries[start..self.len].sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    }
+}
+
+impl Default for MoveList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/**
+ * Writes the legal moves of a position into `list`, replacing its contents.
+ * The moves are the same, and in the same order, as those of `generate_moves`,
+ * but each playable zone is scanned by its empty cells rather than by filtering
+ * every cell, and the result can be reordered by the search.
+ */
+pub fn generate_moves_into(board: Board, list: &mut MoveList) {
+    list.clear();
+    let share = board.2;
+    if line_presence(share >> 36) || line_presence(share >> 45) {
+        return;
+    }
+    let large = (share >> 36) | (share >> 45);
+    let zone = (share >> 54) & 0b1111;
+    let zones = if zone == ZONE_ANY { 0..9 } else { zone..zone + 1 };
+    for zone in zones {
+        // The zone field of a valid board never points to a won zone,
+        // so skipping won zones only matters with a free choice of zone.
+        if (large >> zone) & 1 == 1 {
+            continue;
+        }
+        let (x_cells, o_cells) = zone_cells(board, zone);
+        let mut empty = !(x_cells | o_cells) & 0b111111111;
+        while empty != 0 {
+            list.push(9 * zone + empty.trailing_zeros() as u64);
+            empty &= empty - 1;
+        }
+    }
+}
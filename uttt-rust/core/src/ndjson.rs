@@ -0,0 +1,95 @@
+use std::io::{self, BufRead, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    record::{record_from_value, record_json, GameRecord},
+    versioning::Format,
+};
+
+/*
+ * Newline-delimited JSON (NDJSON) of games with their analysis, for moving them between
+ * the browser, server jobs and offline tools. Each line is a JSON object such as
+ *
+ *     {"format":"uttt-game","version":1,"record":{"moves":"c/c c/nw"},"analysis":[...]}
+ *
+ * where `record` is a match record as given by `record_json`, and `analysis`, which may be
+ * absent, holds the results of analysing the game (such as those of `analyze_game`) as
+ * they are. Every line carries its own version, so that a file is read (and may be written)
+ * a line at a time, and files joined from several sources remain readable.
+ */
+
+// The value of the `format` field of every line.
+pub const FORMAT_NAME: &str = "uttt-game";
+
+// A game with its analysis, as held by a line.
+#[derive(Clone, Debug)]
+pub struct GameEntry {
+    pub record: GameRecord,
+    pub analysis: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonLine {
+    format: String,
+    version: u8,
+    record: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    analysis: Option<serde_json::Value>,
+}
+
+// Writes an entry as a line in the current version, without the line break.
+pub fn entry_line(entry: &GameEntry) -> String {
+    let line = JsonLine {
+        format: FORMAT_NAME.to_string(),
+        version: Format::Ndjson.current_version(),
+        record: record_json(&entry.record),
+        analysis: entry.analysis.clone(),
+    };
+    serde_json::to_string(&line).unwrap()
+}
+
+pub fn parse_entry_line(line: &str) -> Result<GameEntry, &'static str> {
+    let json: JsonLine = serde_json::from_str(line).map_err(|_| "line invalid")?;
+    if json.format != FORMAT_NAME {
+        return Err("format unknown");
+    }
+    if json.version == 0 {
+        return Err("version invalid");
+    }
+    // No version has been superseded yet, so there is nothing to migrate.
+    if json.version > Format::Ndjson.current_version() {
+        return Err("version unsupported");
+    }
+    Ok(GameEntry {
+        record: record_from_value(json.record)?,
+        analysis: json.analysis,
+    })
+}
+
+// Writes entries a line at a time.
+pub fn write_entries<'a>(
+    entries: impl IntoIterator<Item = &'a GameEntry>,
+    output: &mut dyn Write,
+) -> io::Result<()> {
+    for entry in entries {
+        writeln!(output, "{}", entry_line(entry))?;
+    }
+    Ok(())
+}
+
+/**
+ * Reads entries a line at a time, skipping blank lines. Each error names the line
+ * it occurred on, and reading may continue past it to the following lines.
+ */
+pub fn read_entries<R: BufRead>(input: R) -> impl Iterator<Item = Result<GameEntry, String>> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|(line_number, line)| {
+            let line = line.map_err(|e| e.to_string())?;
+            parse_entry_line(&line).map_err(|e| format!("line {} {e}", line_number + 1))
+        })
+}
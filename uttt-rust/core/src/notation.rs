@@ -0,0 +1,138 @@
+use crate::{codingame::move_from_row_column, engine::Move, game::Game, move_from_string};
+
+/*
+ * Conversion of move lists written by other Ultimate Tic-Tac-Toe implementations.
+ * Besides the engine's own `<zone>/<cell>` notation, these are supported:
+ *
+ *     tuple   (1,1,1,1) (1,1,0,0)   `(bigRow,bigCol,smallRow,smallCol)`, from 0
+ *     rowcol  4 4 3 3               `row col` pairs of the 9x9 grid, from 0
+ *
+ * Numbers may be separated by whitespace, commas, semicolons or brackets, so that
+ * logs can be pasted as they are. A `-1 -1` pair, which some referees give for the
+ * missing move before the first, is skipped.
+ */
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Notation {
+    Internal,
+    Tuple,
+    RowColumn,
+}
+
+pub const NOTATIONS: [Notation; 3] = [Notation::Internal, Notation::Tuple, Notation::RowColumn];
+
+impl Notation {
+    pub fn name(self) -> &'static str {
+        match self {
+            Notation::Internal => "internal",
+            Notation::Tuple => "tuple",
+            Notation::RowColumn => "rowcol",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        NOTATIONS.iter().copied().find(|notation| notation.name() == name)
+    }
+}
+
+// The characters which may separate the numbers of a move list.
+fn is_separator(c: char) -> bool {
+    c.is_whitespace() || ",;()[]".contains(c)
+}
+
+// The integers of a move list.
+fn numbers(text: &str) -> Result<Vec<i64>, &'static str> {
+    if !text.chars().all(|c| is_separator(c) || c.is_ascii_digit() || c == '-') {
+        return Err("move invalid");
+    }
+    text.split(is_separator)
+        .filter(|token| !token.is_empty())
+        .map(|token| token.parse().map_err(|_| "number invalid"))
+        .collect()
+}
+
+// A coordinate counting from 0, if it is within the 9x9 grid.
+fn coordinate(value: i64) -> Option<u64> {
+    if (0..9).contains(&value) {
+        Some(value as u64)
+    } else {
+        None
+    }
+}
+
+// Reads the moves of a move list in a notation, without checking they are legal.
+pub fn parse_moves(text: &str, notation: Notation) -> Result<Vec<Move>, &'static str> {
+    match notation {
+        Notation::Internal => text
+            .split_whitespace()
+            .map(|token| move_from_string(token).ok_or("move invalid"))
+            .collect(),
+        Notation::Tuple => {
+            let numbers = numbers(text)?;
+            if numbers.len() % 4 != 0 {
+                return Err("move list incomplete");
+            }
+            numbers
+                .chunks_exact(4)
+                .map(|tuple| {
+                    let coordinates: Option<Vec<u64>> =
+                        tuple.iter().map(|&n| coordinate(n).filter(|&n| n < 3)).collect();
+                    match coordinates.as_deref() {
+                        Some(&[big_row, big_col, small_row, small_col]) => {
+                            Ok(9 * (3 * big_row + big_col) + 3 * small_row + small_col)
+                        }
+                        _ => Err("move invalid"),
+                    }
+                })
+                .collect()
+        }
+        Notation::RowColumn => {
+            let numbers = numbers(text)?;
+            if numbers.len() % 2 != 0 {
+                return Err("move list incomplete");
+            }
+            numbers
+                .chunks_exact(2)
+                .filter(|pair| pair != &[-1, -1])
+                .map(|pair| match (coordinate(pair[0]), coordinate(pair[1])) {
+                    (Some(row), Some(column)) => {
+                        move_from_row_column(row, column).ok_or("move invalid")
+                    }
+                    _ => Err("move invalid"),
+                })
+                .collect()
+        }
+    }
+}
+
+/**
+ * Replays a move list as a game from the empty board. Without a notation, each one
+ * is tried in turn, and the first in which the list reads as a legal game is used,
+ * trying tuples before `row col` pairs if the list has brackets. Returns the notation
+ * used with the game, or the error of the given notation (or `notation unknown`).
+ */
+pub fn import_game(
+    text: &str,
+    notation: Option<Notation>,
+) -> Result<(Notation, Game), &'static str> {
+    let replay = |notation| -> Result<(Notation, Game), &'static str> {
+        let mut game = Game::new();
+        for mv in parse_moves(text, notation)? {
+            game.play(mv)?;
+        }
+        Ok((notation, game))
+    };
+    match notation {
+        Some(notation) => replay(notation),
+        None => {
+            let candidates = match text.contains('(') {
+                true => [Notation::Internal, Notation::Tuple, Notation::RowColumn],
+                false => [Notation::Internal, Notation::RowColumn, Notation::Tuple],
+            };
+            candidates
+                .iter()
+                .find_map(|&notation| replay(notation).ok())
+                .ok_or("notation unknown")
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::sync::mpsc::Sender;
+
+use crate::{
+    engine::{Eval, Move, NULL_MOVE},
+    search::{SearchObserver, SearchResult},
+};
+
+/*
+ * An adapter for native applications which run the search on a thread of its own,
+ * passing what a `SearchObserver` is told to another thread as messages over a channel.
+ * A user interface thread can then poll the channel for progress without sharing
+ * any state with the search.
+ */
+
+// What an observer is told during a search, as a message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SearchEvent {
+    RootMove {
+        depth: usize,
+        mv: Move,
+        number: usize,
+    },
+    DepthComplete {
+        depth: usize,
+        seldepth: usize,
+        eval: Eval,
+        pv: Vec<Move>,
+        nodes: u64,
+    },
+    // The search has ended, with the best move found, if any.
+    Finish {
+        best_move: Option<Move>,
+        eval: Option<Eval>,
+        nodes: u64,
+    },
+}
+
+pub struct ChannelObserver {
+    sender: Sender<SearchEvent>,
+    // Whether to send an event for every root move, which is many more messages.
+    pub root_moves: bool,
+}
+
+impl ChannelObserver {
+    pub fn new(sender: Sender<SearchEvent>) -> Self {
+        ChannelObserver {
+            sender,
+            root_moves: false,
+        }
+    }
+
+    // Sends an event, ignoring a receiver that has gone away,
+    // as the search need not stop because nobody is watching.
+    fn send(&self, event: SearchEvent) {
+        let _ = self.sender.send(event);
+    }
+}
+
+impl SearchObserver for ChannelObserver {
+    fn on_root_move(&self, depth: usize, mv: Move, number: usize) {
+        if self.root_moves {
+            self.send(SearchEvent::RootMove { depth, mv, number });
+        }
+    }
+
+    fn on_depth_complete(&self, result: &SearchResult, nodes: u64) {
+        self.send(SearchEvent::DepthComplete {
+            depth: result.depth,
+            seldepth: result.seldepth,
+            eval: result.eval,
+            pv: result.pv.iter().copied().take_while(|&mv| mv != NULL_MOVE).collect(),
+            nodes,
+        });
+    }
+
+    fn on_finish(&self, result: Option<&SearchResult>, nodes: u64) {
+        self.send(SearchEvent::Finish {
+            best_move: result.map(|result| result.pv[0]).filter(|&mv| mv != NULL_MOVE),
+            eval: result.map(|result| result.eval),
+            nodes,
+        });
+    }
+}
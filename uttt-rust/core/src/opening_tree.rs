@@ -0,0 +1,164 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+use crate::{
+    board_string,
+    engine::{mark_counts, Board, Move},
+    game::{parse_game_record, Game, GameResult},
+    move_string,
+    symmetry::canonical_move,
+    zobrist::hash_game,
+};
+
+// Results of the games in which a move was played, from X's point of view.
+#[derive(Serialize, Clone, Copy, Debug, Default)]
+pub struct ResultCounts {
+    pub games: u64,
+    pub x_wins: u64,
+    pub o_wins: u64,
+    pub draws: u64,
+}
+
+impl ResultCounts {
+    pub(crate) fn add(&mut self, result: GameResult) {
+        self.games += 1;
+        match result {
+            GameResult::XWins => self.x_wins += 1,
+            GameResult::OWins => self.o_wins += 1,
+            GameResult::Draw => self.draws += 1,
+        }
+    }
+
+    fn wins(&self, side: bool) -> u64 {
+        if side {
+            self.o_wins
+        } else {
+            self.x_wins
+        }
+    }
+
+    // The average result for `side` (`false` for X), counting a draw as half a win.
+    pub fn score(&self, side: bool) -> f64 {
+        (self.wins(side) as f64 + self.draws as f64 / 2.0) / self.games as f64
+    }
+
+    // The proportion of games won by `side` (`false` for X).
+    pub fn win_rate(&self, side: bool) -> f64 {
+        self.wins(side) as f64 / self.games as f64
+    }
+}
+
+/**
+ * Move statistics aggregated from many games, keyed by position.
+ * Positions are merged with their symmetric variants by storing them
+ * in canonical form, along with the moves transformed to match,
+ * so that symmetric lines are counted together.
+ */
+pub struct OpeningTree {
+    // Only the first `max_plies` moves of each game are counted.
+    max_plies: usize,
+    positions: BTreeMap<Board, BTreeMap<Move, ResultCounts>>,
+}
+
+/**
+ * Builds an opening tree from game records, one per line, as accepted by
+ * `parse_game_record`. Blank lines are skipped, as are games already counted,
+ * in the same or a symmetric form (see `hash_game`), so that records imported
+ * twice are not counted twice. Returns an error naming the line of the first
+ * invalid record.
+ */
+pub fn opening_tree_from_records(records: &str, max_plies: usize) -> Result<OpeningTree, String> {
+    let mut tree = OpeningTree::new(max_plies);
+    let mut seen = HashSet::new();
+    for (line_number, record) in records.lines().enumerate() {
+        if record.trim().is_empty() {
+            continue;
+        }
+        let (game, result) =
+            parse_game_record(record).map_err(|e| format!("line {} {e}", line_number + 1))?;
+        if seen.insert(hash_game(game.moves())) {
+            tree.add_game(&game, result);
+        }
+    }
+    Ok(tree)
+}
+
+impl OpeningTree {
+    pub fn new(max_plies: usize) -> Self {
+        OpeningTree {
+            max_plies,
+            positions: BTreeMap::new(),
+        }
+    }
+
+    pub fn add_game(&mut self, game: &Game, result: GameResult) {
+        for (ply, &mv) in game.moves().iter().enumerate().take(self.max_plies) {
+            if let Some(board) = game.board_at(ply) {
+                let (canonical, canonical_mv) = canonical_move(board, mv);
+                self.positions
+                    .entry(canonical)
+                    .or_default()
+                    .entry(canonical_mv)
+                    .or_default()
+                    .add(result);
+            }
+        }
+    }
+
+    // The number of distinct positions (up to symmetry) in the tree.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    // Lists every position with its moves, most played first,
+    // in a form suitable for serialising as JSON.
+    pub fn export(&self) -> Vec<PositionStats> {
+        self.positions
+            .iter()
+            .map(|(&board, moves)| {
+                let (x_count, o_count) = mark_counts(board);
+                let side = x_count > o_count;
+                let mut moves: Vec<MoveStats> = moves
+                    .iter()
+                    .map(|(&mv, counts)| MoveStats {
+                        mv: move_string(mv),
+                        score: counts.score(side),
+                        counts: *counts,
+                    })
+                    .collect();
+                moves.sort_by_key(|stats| std::cmp::Reverse(stats.counts.games));
+                PositionStats {
+                    board: board_string(board),
+                    side: if side { "o" } else { "x" },
+                    games: moves.iter().map(|stats| stats.counts.games).sum(),
+                    moves,
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct MoveStats {
+    #[serde(rename = "move")]
+    pub mv: String,
+    #[serde(flatten)]
+    pub counts: ResultCounts,
+    // The average result for the side that played the move.
+    pub score: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PositionStats {
+    // The canonical form of the position, as a board string.
+    pub board: String,
+    // The side to move, `x` or `o`.
+    pub side: &'static str,
+    pub games: u64,
+    pub moves: Vec<MoveStats>,
+}
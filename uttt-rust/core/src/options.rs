@@ -0,0 +1,198 @@
+use crate::{
+    adjudication::AdjudicationRules,
+    analysis::ONLY_MOVE_MARGIN,
+    engine::{generate_moves, Board, Eval, EvalParams, Move},
+    humanlike::safest_moves,
+    search::EvalNoise,
+    timeman::{DEFAULT_MOVE_OVERHEAD_MS, DEFAULT_TARGET_LATENCY_MS},
+    tt::{DEFAULT_HASH_MB, MAX_HASH_MB, MIN_HASH_MB},
+};
+
+// The player from whose point of view scores are reported.
+// Internally, scores are always relative to the side to move,
+// which suits engine logic but not an evaluation bar.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScorePerspective {
+    // Positive scores are good for the side to move.
+    #[default]
+    SideToMove,
+    // Positive scores are good for player X, regardless of the side to move.
+    X,
+}
+
+impl ScorePerspective {
+    // Converts a score relative to the side to move into this perspective,
+    // where `x_to_move` indicates whether X is the side to move.
+    pub fn normalise(self, eval: Eval, x_to_move: bool) -> Eval {
+        match self {
+            ScorePerspective::X if !x_to_move => -eval,
+            _ => eval,
+        }
+    }
+}
+
+// Which lookup tables searches evaluate positions with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TableSource {
+    // The tables built from the heuristic weights (see `init_with`).
+    #[default]
+    Heuristic,
+    // The tables learnt from the results of self-play games (see `win_tables`),
+    // where they have been loaded, and the heuristic tables otherwise.
+    Learned,
+}
+
+/**
+ * Engine options that persist between searches,
+ * set by name and string value in the manner of UCI's `setoption`.
+ */
+#[derive(Clone, Debug)]
+pub struct EngineOptions {
+    // Whether searches collect and report statistics in a `stats` field.
+    pub collect_stats: bool,
+    // Which player reported scores are relative to.
+    pub perspective: ScorePerspective,
+    // Size of the transposition table in megabytes.
+    pub hash_mb: usize,
+    // Whether boards whose zone field points to a won or full zone are rejected,
+    // rather than corrected to allow moves in any zone.
+    pub strict: bool,
+    // When games are resigned or adjudicated drawn.
+    pub adjudication: AdjudicationRules,
+    // Milliseconds taken from the time allotted to each move on a clock,
+    // to allow for the latency between the engine and the clock.
+    pub move_overhead_ms: u64,
+    // How much every alternative to a move must lose for `only_move` to report it.
+    pub only_move_margin: Eval,
+    // Whether a won game is drawn out by playing the slowest winning move
+    // rather than the quickest ("troll mode").
+    pub troll: bool,
+    // The evaluation terms applied by searches on top of the lookup tables.
+    pub eval_params: EvalParams,
+    // The standard deviation of the noise added to evaluations to weaken play
+    // (see `EvalNoise`), zero for none, and the seed it is drawn with.
+    pub noise_std_dev: Eval,
+    pub noise_seed: u64,
+    // Whether weakened play may give away a zone or the game with a move
+    // when another move would not (see `humanlike::move_safety`).
+    pub allow_blunders: bool,
+    // The most taken off the score of a move whose book games were heavily drawn,
+    // when playing for a win (see `Book::draw_penalties`), zero for none.
+    pub contempt: Eval,
+    // The response time aimed for by searches to depth `auto`, in milliseconds
+    // (see `SpeedProfile::depth_for_latency`).
+    pub target_latency_ms: u64,
+    // Which lookup tables searches evaluate positions with.
+    pub tables: TableSource,
+}
+
+impl Default for EngineOptions {
+    fn default() -> Self {
+        EngineOptions {
+            collect_stats: false,
+            perspective: ScorePerspective::default(),
+            hash_mb: DEFAULT_HASH_MB,
+            strict: false,
+            adjudication: AdjudicationRules::default(),
+            move_overhead_ms: DEFAULT_MOVE_OVERHEAD_MS,
+            only_move_margin: ONLY_MOVE_MARGIN,
+            troll: false,
+            eval_params: EvalParams::DEFAULT,
+            noise_std_dev: 0,
+            noise_seed: 0,
+            allow_blunders: false,
+            contempt: 0,
+            target_latency_ms: DEFAULT_TARGET_LATENCY_MS,
+            tables: TableSource::default(),
+        }
+    }
+}
+
+// Parses the boolean option values accepted by `set`.
+fn parse_bool(value: &str) -> Result<bool, &'static str> {
+    match value {
+        "true" | "on" => Ok(true),
+        "false" | "off" => Ok(false),
+        _ => Err("value invalid"),
+    }
+}
+
+// Parses non-negative numeric option values.
+fn parse_number<T>(value: &str) -> Result<T, &'static str>
+where
+    T: std::str::FromStr + Default + PartialOrd,
+{
+    match value.parse::<T>() {
+        Ok(number) if number >= T::default() => Ok(number),
+        _ => Err("value invalid"),
+    }
+}
+
+impl EngineOptions {
+    // Sets the option called `name` from its string value,
+    // returning a short error description if either is not recognised.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), &'static str> {
+        match name {
+            "stats" => self.collect_stats = parse_bool(value)?,
+            "perspective" => {
+                self.perspective = match value {
+                    "side" => ScorePerspective::SideToMove,
+                    "x" => ScorePerspective::X,
+                    _ => return Err("value invalid"),
+                }
+            }
+            "hash" => {
+                self.hash_mb = match value.parse::<usize>() {
+                    Ok(mb) if (MIN_HASH_MB..=MAX_HASH_MB).contains(&mb) => mb,
+                    _ => return Err("value invalid"),
+                }
+            }
+            "strict" => self.strict = parse_bool(value)?,
+            "resignscore" => self.adjudication.resign_score = parse_number(value)?,
+            "resignmoves" => self.adjudication.resign_moves = parse_number(value)?,
+            "drawscore" => self.adjudication.draw_score = parse_number(value)?,
+            "drawmoves" => self.adjudication.draw_moves = parse_number(value)?,
+            "moveoverhead" => self.move_overhead_ms = parse_number(value)?,
+            "onlymovemargin" => self.only_move_margin = parse_number(value)?,
+            "troll" => self.troll = parse_bool(value)?,
+            "tempo" => self.eval_params.tempo = parse_number(value)?,
+            "evalnoise" => self.noise_std_dev = parse_number(value)?,
+            "noiseseed" => self.noise_seed = parse_number(value)?,
+            "blunders" => self.allow_blunders = parse_bool(value)?,
+            "contempt" => self.contempt = parse_number(value)?,
+            "latency" => self.target_latency_ms = parse_number(value)?,
+            "tables" => {
+                self.tables = match value {
+                    "heuristic" => TableSource::Heuristic,
+                    "learned" => TableSource::Learned,
+                    _ => return Err("value invalid"),
+                }
+            }
+            _ => return Err("option unknown"),
+        }
+        Ok(())
+    }
+
+    // The noise searches add to evaluations, if any.
+    pub fn eval_noise(&self) -> Option<EvalNoise> {
+        match self.noise_std_dev {
+            0 => None,
+            std_dev => Some(EvalNoise {
+                std_dev,
+                seed: self.noise_seed,
+            }),
+        }
+    }
+
+    // The root moves a search weakened by noise is limited to, being those which give
+    // nothing away at once, unless blunders are allowed. `None` leaves every move.
+    pub fn safe_root_moves(&self, board: Board, side: bool) -> Option<Vec<Move>> {
+        match (self.eval_noise(), self.allow_blunders) {
+            (Some(_), false) => {
+                let moves: Vec<Move> = generate_moves(board).collect();
+                Some(safest_moves(board, side, &moves)).filter(|safe| !safe.is_empty())
+            }
+            _ => None,
+        }
+    }
+}
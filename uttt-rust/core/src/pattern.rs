@@ -0,0 +1,149 @@
+use crate::{
+    codingame::move_from_row_column,
+    engine::{Board, Move},
+    symmetry::{transform_move, TRANSFORM_COUNT},
+};
+
+/*
+ * Partial patterns of marks, for finding positions with a particular structure.
+ * A pattern is written as the 9 rows of the 9x9 grid from the top, separated by `/`
+ * as in board strings, with a character for each cell: `x` or `o` for a mark of
+ * that side, `.` for an empty cell, `*` for a mark of either side, and `?` for
+ * any cell. For example, X holding the centre cell with the cell above it empty is
+ *
+ *     ?????????/?????????/?????????/????.????/????x????/?????????/?????????/?????????/?????????
+ *
+ * Patterns are matched by masking the bitboards, so a match costs a few operations
+ * whatever the pattern.
+ */
+
+// Cells as in the bitboards: zones 0 to 6 in the low 63 bits of the first `u64`,
+// and zones 7 and 8 in the low 18 bits of the second.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Cells(u64, u64);
+
+impl Cells {
+    fn add(&mut self, mv: Move) {
+        if mv > 62 {
+            self.1 |= 1 << (mv - 63);
+        } else {
+            self.0 |= 1 << mv;
+        }
+    }
+
+    fn or(self, other: Cells) -> Cells {
+        Cells(self.0 | other.0, self.1 | other.1)
+    }
+
+    // Whether every cell of `other` is one of these.
+    fn contains(self, other: Cells) -> bool {
+        self.0 & other.0 == other.0 && self.1 & other.1 == other.1
+    }
+
+    fn is_disjoint(self, other: Cells) -> bool {
+        self.0 & other.0 == 0 && self.1 & other.1 == 0
+    }
+}
+
+// The marks of X and O respectively.
+fn marks(board: Board) -> (Cells, Cells) {
+    let (us, them, share) = board;
+    (Cells(us, share & 0x3ffff), Cells(them, (share >> 18) & 0x3ffff))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Constraint {
+    X,
+    O,
+    Empty,
+    Occupied,
+    Any,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    // The constraint on each cell, indexed by move.
+    cells: [Constraint; 81],
+    x: Cells,
+    o: Cells,
+    empty: Cells,
+    occupied: Cells,
+}
+
+impl Pattern {
+    fn from_cells(cells: [Constraint; 81]) -> Self {
+        let mut pattern = Pattern {
+            cells,
+            x: Cells::default(),
+            o: Cells::default(),
+            empty: Cells::default(),
+            occupied: Cells::default(),
+        };
+        for (mv, constraint) in cells.iter().enumerate() {
+            let mask = match constraint {
+                Constraint::X => &mut pattern.x,
+                Constraint::O => &mut pattern.o,
+                Constraint::Empty => &mut pattern.empty,
+                Constraint::Occupied => &mut pattern.occupied,
+                Constraint::Any => continue,
+            };
+            mask.add(mv as Move);
+        }
+        pattern
+    }
+
+    pub fn from_string(pattern: &str) -> Result<Self, &'static str> {
+        let rows: Vec<&str> = pattern.trim().split('/').collect();
+        if rows.len() != 9 {
+            return Err("pattern invalid");
+        }
+        let mut cells = [Constraint::Any; 81];
+        for (row, text) in rows.iter().enumerate() {
+            if text.chars().count() != 9 {
+                return Err("pattern invalid");
+            }
+            for (column, c) in text.chars().enumerate() {
+                let mv = move_from_row_column(row as u64, column as u64).unwrap();
+                cells[mv as usize] = match c {
+                    'x' => Constraint::X,
+                    'o' => Constraint::O,
+                    '.' => Constraint::Empty,
+                    '*' => Constraint::Occupied,
+                    '?' => Constraint::Any,
+                    _ => return Err("pattern invalid"),
+                };
+            }
+        }
+        Ok(Pattern::from_cells(cells))
+    }
+
+    // The pattern after one of the transforms of `symmetry`.
+    pub fn transformed(&self, transform: usize) -> Self {
+        let mut cells = [Constraint::Any; 81];
+        for (mv, &constraint) in self.cells.iter().enumerate() {
+            cells[transform_move(mv as Move, transform) as usize] = constraint;
+        }
+        Pattern::from_cells(cells)
+    }
+
+    // The pattern in each of its symmetric variants, without repeats.
+    pub fn variants(&self) -> Vec<Self> {
+        let mut variants: Vec<Pattern> = Vec::with_capacity(TRANSFORM_COUNT);
+        for transform in 0..TRANSFORM_COUNT {
+            let variant = self.transformed(transform);
+            if !variants.contains(&variant) {
+                variants.push(variant);
+            }
+        }
+        variants
+    }
+
+    pub fn matches(&self, board: Board) -> bool {
+        let (x, o) = marks(board);
+        let occupied = x.or(o);
+        x.contains(self.x)
+            && o.contains(self.o)
+            && occupied.contains(self.occupied)
+            && occupied.is_disjoint(self.empty)
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{
+    engine::{play_move, Board, Eval, EvalParams, Move},
+    search::{iterative_deepening, root_move_scores, SearchContext},
+    tt::TranspositionTable,
+};
+
+// Depth of the search ranking the opponent's replies by how likely they are.
+const RANKING_DEPTH: usize = 2;
+
+// Depth to which the position after each reply is searched at most.
+// Searches to this depth finish quickly, and their results are enough
+// for the engine's next search to reach its usual depth almost at once.
+pub const PRECOMPUTE_MAX_DEPTH: usize = 8;
+
+// How far the precomputation got before it finished or was stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrecomputeSummary {
+    // The number of legal replies.
+    pub replies: usize,
+    // The deepest depth at which every reply was searched, and the number
+    // of replies searched one ply deeper than that before stopping.
+    pub depth: usize,
+    pub partial: usize,
+    pub nodes: u64,
+}
+
+/**
+ * Searches the positions after each likely reply of the opponent, `side` (`false` for X),
+ * while they are thinking, so that the results are in the transposition table
+ * when the engine is asked to respond. The replies are ranked by a shallow search
+ * and then searched in that order, one depth at a time, so that the likeliest replies
+ * are searched deepest if the opponent moves before all are done. The evaluation terms
+ * are those of `eval_params`, which should be those the engine will respond with.
+ * Returns when every reply has been searched to `PRECOMPUTE_MAX_DEPTH`,
+ * or when the stop condition is met.
+ */
+pub fn precompute_replies(
+    board: Board,
+    side: bool,
+    tables: &(Vec<Eval>, Vec<Eval>),
+    eval_params: EvalParams,
+    tt: &mut TranspositionTable,
+    stop_condition: Option<&dyn Fn() -> bool>,
+) -> PrecomputeSummary {
+    tt.new_search();
+    let mut ctx = SearchContext::new(tables, RANKING_DEPTH);
+    ctx.eval_params = eval_params;
+    ctx.tt = Some(tt);
+    ctx.stop_condition = stop_condition;
+    let mut summary = PrecomputeSummary {
+        replies: 0,
+        depth: 0,
+        partial: 0,
+        nodes: 0,
+    };
+    let replies: Vec<Move> = match root_move_scores(board, side, RANKING_DEPTH, &mut ctx) {
+        Some(scores) => scores.into_iter().map(|(mv, _)| mv).collect(),
+        None => {
+            summary.nodes = ctx.nodes;
+            return summary;
+        }
+    };
+    summary.replies = replies.len();
+    'deepening: for depth in 1..=PRECOMPUTE_MAX_DEPTH {
+        summary.partial = 0;
+        for &reply in &replies {
+            // Earlier iterations are in the table, so only the deepest costs much.
+            iterative_deepening(play_move(board, reply, side), !side, depth, &mut ctx);
+            if ctx.stopped {
+                break 'deepening;
+            }
+            summary.partial += 1;
+        }
+        summary.depth = depth;
+        summary.partial = 0;
+    }
+    summary.nodes = ctx.nodes;
+    summary
+}
@@ -0,0 +1,240 @@
+use std::io::Write;
+
+use crate::{
+    board_from_string, eval_string, move_from_string, move_string,
+    engine::{
+        check_structure, correct_zone, generate_moves, mark_counts, play_move, Board, Eval, Move,
+        EMPTY_BOARD, MAX_PLY,
+    },
+    log::{self, position_hash, LogRecord},
+    search::{iterative_deepening, SearchContext, SearchResult},
+    timer::Timer,
+    tt::TranspositionTable,
+};
+
+/*
+ * Adapters between the engine and the protocols of the platforms it plays on.
+ * An adapter only parses requests and formats responses: the loop in `run`,
+ * which is shared by every adapter, keeps track of the position and searches each move.
+ * Adding a platform is then a matter of implementing `Protocol` for it.
+ */
+
+// How a request gives the position to move in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Position {
+    // The opponent's last move, if there is one, to play on the position so far.
+    OpponentMove(Option<Move>),
+    // A whole position, replacing the position so far, with the engine's side to move.
+    Board(Board, bool),
+}
+
+// A request for the engine to move.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Request {
+    pub position: Position,
+    // The moves the platform accepts, if it lists them. Its list is authoritative,
+    // should the two ever differ.
+    pub valid_moves: Option<Vec<Move>>,
+    // The time to search for, if the request sets it, in milliseconds.
+    pub time_ms: Option<u64>,
+}
+
+pub trait Protocol {
+    // Reads the next request from the lines of input, or `None` once the input has ended.
+    fn read_request(
+        &mut self,
+        lines: &mut dyn Iterator<Item = String>,
+    ) -> Result<Option<Request>, String>;
+
+    // Formats the response giving the chosen move, and the search which chose it, if any.
+    fn format_move(&self, mv: Move, search: Option<&SearchOutcome>) -> String;
+
+    // The time to search for when the request does not set it, given the number
+    // of moves played so far, in milliseconds.
+    fn time_limit_ms(&self, ply: usize) -> u64;
+
+    // The move to play when the search does not complete an iteration in time.
+    fn timeout_move(&self, board: Board, valid_moves: Option<&[Move]>) -> Option<Move> {
+        match valid_moves {
+            Some(moves) => moves.first().copied(),
+            None => generate_moves(board).next(),
+        }
+    }
+
+    // A line describing the search to write to the log, if the platform shows one.
+    fn log_line(&self, _search: &SearchOutcome) -> Option<String> {
+        None
+    }
+}
+
+// A completed search, as reported in responses.
+pub struct SearchOutcome {
+    pub result: SearchResult,
+    pub nodes: u64,
+    pub time_ms: u64,
+}
+
+/**
+ * Plays through the requests of a protocol, writing a response to `output` for each,
+ * and any log lines to `log`. Moves received are checked against the position so far,
+ * and an illegal move, or a request with no legal move to respond with, ends the loop.
+ */
+pub fn run(
+    protocol: &mut dyn Protocol,
+    lines: &mut dyn Iterator<Item = String>,
+    output: &mut dyn Write,
+    log: &mut dyn Write,
+    tables: &(Vec<Eval>, Vec<Eval>),
+    tt: &mut TranspositionTable,
+) -> Result<(), String> {
+    let (mut board, mut side) = (EMPTY_BOARD, false);
+    while let Some(request) = protocol.read_request(lines)? {
+        let timer = Timer::start();
+        match request.position {
+            Position::OpponentMove(Some(mv)) => {
+                if !generate_moves(board).any(|m| m == mv) {
+                    return Err(format!("opponent {}: move illegal", move_string(mv)));
+                }
+                board = play_move(board, mv, side);
+                side = !side;
+            }
+            Position::OpponentMove(None) => {}
+            Position::Board(b, s) => {
+                board = b;
+                side = s;
+            }
+        }
+        let (x, o) = mark_counts(board);
+        let limit = request
+            .time_ms
+            .unwrap_or_else(|| protocol.time_limit_ms((x + o) as usize));
+        let out_of_time = || timer.elapsed_ms() >= limit;
+
+        tt.new_search();
+        let mut ctx = SearchContext::new(tables, MAX_PLY);
+        ctx.tt = Some(tt);
+        ctx.stop_condition = Some(&out_of_time);
+        let search = iterative_deepening(board, side, MAX_PLY, &mut ctx).map(|result| {
+            SearchOutcome {
+                result,
+                nodes: ctx.nodes,
+                time_ms: timer.elapsed_ms(),
+            }
+        });
+
+        let valid_moves = request.valid_moves.as_deref();
+        let is_valid = |mv: &Move| match valid_moves {
+            Some(moves) => moves.contains(mv),
+            None => generate_moves(board).any(|m| m == *mv),
+        };
+        let mv = match search.as_ref().map(|s| s.result.pv[0]).filter(is_valid) {
+            Some(mv) => mv,
+            None => protocol
+                .timeout_move(board, valid_moves)
+                .ok_or("no legal moves")?,
+        };
+        if let Some(line) = search.as_ref().and_then(|s| protocol.log_line(s)) {
+            writeln!(log, "{line}").map_err(|e| e.to_string())?;
+        }
+        if let (true, Some(search)) = (log::enabled(), &search) {
+            log::log(&LogRecord::Move {
+                hash: position_hash(board, side),
+                ply: (x + o) as usize,
+                side: if side { "o" } else { "x" },
+                mv: move_string(mv),
+                score: search.result.eval,
+                depth: search.result.depth,
+            });
+        }
+        writeln!(output, "{}", protocol.format_move(mv, search.as_ref()))
+            .and_then(|_| output.flush())
+            .map_err(|e| e.to_string())?;
+        board = play_move(board, mv, side);
+        side = !side;
+    }
+    Ok(())
+}
+
+/**
+ * The engine's own line-based protocol. `go <x|o> <board string>` sets the position,
+ * with the given side to move, and `move <move>` plays the opponent's move on the position
+ * so far (after the engine's own response). Either may end with `movetime <ms>`,
+ * and each is answered with `[info depth <d> score <s> nodes <n> time <ms>] bestmove <move>`.
+ * `quit`, or the end of the input, ends the game.
+ */
+pub struct TextProtocol {
+    // The time to search each move for, unless a request sets it.
+    pub time_ms: u64,
+}
+
+// Splits an optional trailing `movetime <ms>` from the fields of a request.
+fn split_movetime<'a>(fields: &'a [&'a str]) -> Result<(&'a [&'a str], Option<u64>), String> {
+    match fields {
+        [rest @ .., "movetime", ms] => {
+            let ms = ms.parse().map_err(|_| "movetime invalid".to_string())?;
+            Ok((rest, Some(ms)))
+        }
+        _ => Ok((fields, None)),
+    }
+}
+
+impl Protocol for TextProtocol {
+    fn read_request(
+        &mut self,
+        lines: &mut dyn Iterator<Item = String>,
+    ) -> Result<Option<Request>, String> {
+        for line in lines {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (position, time_ms) = match fields.split_first() {
+                None => continue,
+                Some((&"quit", _)) => return Ok(None),
+                Some((&"go", args)) => {
+                    let (args, time_ms) = split_movetime(args)?;
+                    let side = match args.first() {
+                        Some(&"x") => false,
+                        Some(&"o") => true,
+                        _ => return Err("side invalid".to_string()),
+                    };
+                    let board = board_from_string(&args[1..].join(" "))
+                        .map(correct_zone)
+                        .filter(|&board| check_structure(board).is_ok())
+                        .ok_or_else(|| "board invalid".to_string())?;
+                    (Position::Board(board, side), time_ms)
+                }
+                Some((&"move", args)) => {
+                    let (args, time_ms) = split_movetime(args)?;
+                    let mv = match args {
+                        [mv] => move_from_string(mv).ok_or_else(|| "move invalid".to_string())?,
+                        _ => return Err("move invalid".to_string()),
+                    };
+                    (Position::OpponentMove(Some(mv)), time_ms)
+                }
+                Some((command, _)) => return Err(format!("unknown command {command}")),
+            };
+            return Ok(Some(Request {
+                position,
+                valid_moves: None,
+                time_ms,
+            }));
+        }
+        Ok(None)
+    }
+
+    fn format_move(&self, mv: Move, search: Option<&SearchOutcome>) -> String {
+        match search {
+            Some(s) => format!(
+                "info depth {} score {} nodes {} time {} bestmove {}",
+                s.result.depth,
+                eval_string(s.result.eval, s.result.depth),
+                s.nodes,
+                s.time_ms,
+                move_string(mv),
+            ),
+            None => format!("bestmove {}", move_string(mv)),
+        }
+    }
+
+    fn time_limit_ms(&self, _ply: usize) -> u64 {
+        self.time_ms
+    }
+}
@@ -0,0 +1,184 @@
+/*
+ * A Python module exposing the rules and the engine, so that experiments (such as
+ * reinforcement learning) can use exactly the same move generation, evaluation and search
+ * as the website. It is built with the `python` feature, for instance with
+ * `maturin develop --features python`, and imported as `uttt_core`.
+ *
+ * Moves are numbered `9 * zone + cell`, and scores are relative to the side to move.
+ */
+
+// The conversions are generated by the `pyo3` macros for functions returning `PyResult`.
+#![allow(clippy::useless_conversion)]
+
+use std::sync::LazyLock;
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::{
+    board_from_string, board_string, load_tables, move_from_string, move_string,
+    engine::{self, check_structure, correct_zone, Eval, Move, EMPTY_BOARD, MAX_PLY, NULL_MOVE},
+    game::board_result,
+    search::{iterative_deepening, SearchContext},
+    tt::{TranspositionTable, DEFAULT_HASH_MB, MAX_HASH_MB, MIN_HASH_MB},
+};
+
+static TABLES: LazyLock<(Vec<Eval>, Vec<Eval>)> = LazyLock::new(load_tables);
+
+/**
+ * A position together with the side to move. Boards are immutable:
+ * playing a move returns a new board.
+ */
+#[pyclass(frozen, eq, hash)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Board {
+    board: engine::Board,
+    // The engine's side to move, `false` being X.
+    side: bool,
+}
+
+#[pymethods]
+impl Board {
+    // The empty board, with X to move.
+    #[new]
+    fn new() -> Self {
+        Board {
+            board: EMPTY_BOARD,
+            side: false,
+        }
+    }
+
+    // Parses a board string, raising `ValueError` if it is invalid. A zone field
+    // pointing to a won or full zone is treated as allowing any zone.
+    #[staticmethod]
+    #[pyo3(signature = (board, x_to_move = true))]
+    fn from_string(board: &str, x_to_move: bool) -> PyResult<Self> {
+        let b = board_from_string(board)
+            .map(correct_zone)
+            .filter(|&b| check_structure(b).is_ok())
+            .ok_or_else(|| PyValueError::new_err("board invalid"))?;
+        Ok(Board {
+            board: b,
+            side: !x_to_move,
+        })
+    }
+
+    #[getter]
+    fn x_to_move(&self) -> bool {
+        !self.side
+    }
+
+    fn legal_moves(&self) -> Vec<Move> {
+        generate_moves(self)
+    }
+
+    // Plays a move for the side to move, raising `ValueError` if it is illegal.
+    #[pyo3(name = "play")]
+    fn play_py(&self, mv: Move) -> PyResult<Self> {
+        play_move(self, mv)
+    }
+
+    // The result of the game as `1-0`, `0-1` or `1/2-1/2`, or `None` if it is in progress.
+    fn result(&self) -> Option<&'static str> {
+        board_result(self.board).map(|r| r.notation())
+    }
+
+    fn __str__(&self) -> String {
+        board_string(self.board)
+    }
+
+    fn __repr__(&self) -> String {
+        let side = if self.side { "False" } else { "True" };
+        format!("Board.from_string('{}', x_to_move={})", board_string(self.board), side)
+    }
+}
+
+/**
+ * The outcome of a completed search. `eval` is relative to the side to move,
+ * and `pv` starts with the best move.
+ */
+#[pyclass(frozen, get_all)]
+pub struct SearchResult {
+    best_move: Option<Move>,
+    eval: Eval,
+    depth: usize,
+    pv: Vec<Move>,
+    nodes: u64,
+}
+
+// The legal moves of the side to move, which are none once the game is over.
+#[pyfunction]
+fn generate_moves(board: &Board) -> Vec<Move> {
+    engine::generate_moves(board.board).collect()
+}
+
+// Plays a move for the side to move, raising `ValueError` if it is illegal.
+#[pyfunction]
+fn play_move(board: &Board, mv: Move) -> PyResult<Board> {
+    if !engine::generate_moves(board.board).any(|m| m == mv) {
+        return Err(PyValueError::new_err("move illegal"));
+    }
+    Ok(Board {
+        board: engine::play_move(board.board, mv, board.side),
+        side: !board.side,
+    })
+}
+
+// The static evaluation of a board, relative to the side to move.
+#[pyfunction]
+fn evaluate(board: &Board) -> Eval {
+    engine::evaluate(board.board, board.side, &*TABLES)
+}
+
+// Searches a board to `depth` plies with a fresh transposition table of `hash_mb` megabytes.
+// The best move is `None` if the game is already over. The GIL is released during the search.
+#[pyfunction]
+#[pyo3(signature = (board, depth, hash_mb = DEFAULT_HASH_MB))]
+fn search(py: Python<'_>, board: &Board, depth: usize, hash_mb: usize) -> PyResult<SearchResult> {
+    if depth == 0 || depth > MAX_PLY {
+        return Err(PyValueError::new_err("depth invalid"));
+    }
+    let Board { board, side } = *board;
+    let result = py.allow_threads(|| {
+        let mut tt = TranspositionTable::new(hash_mb.clamp(MIN_HASH_MB, MAX_HASH_MB));
+        let mut ctx = SearchContext::new(&*TABLES, depth);
+        ctx.tt = Some(&mut tt);
+        iterative_deepening(board, side, depth, &mut ctx).map(|result| (result, ctx.nodes))
+    });
+    // Without a stop condition, the search always completes.
+    let (result, nodes) = result.unwrap();
+    let pv: Vec<Move> = result.pv.iter().copied().take_while(|&m| m != NULL_MOVE).collect();
+    Ok(SearchResult {
+        best_move: pv.first().copied(),
+        eval: result.eval,
+        depth: result.depth,
+        pv,
+        nodes,
+    })
+}
+
+// The name of a move, such as `ne/sw`.
+#[pyfunction(name = "move_string")]
+fn move_string_py(mv: Move) -> String {
+    move_string(mv)
+}
+
+// Parses the name of a move, raising `ValueError` if it is invalid.
+#[pyfunction(name = "move_from_string")]
+fn move_from_string_py(name: &str) -> PyResult<Move> {
+    move_from_string(name).ok_or_else(|| PyValueError::new_err("move invalid"))
+}
+
+#[pymodule]
+fn uttt_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Board>()?;
+    m.add_class::<SearchResult>()?;
+    m.add_function(wrap_pyfunction!(generate_moves, m)?)?;
+    m.add_function(wrap_pyfunction!(play_move, m)?)?;
+    m.add_function(wrap_pyfunction!(evaluate, m)?)?;
+    m.add_function(wrap_pyfunction!(search, m)?)?;
+    m.add_function(wrap_pyfunction!(move_string_py, m)?)?;
+    m.add_function(wrap_pyfunction!(move_from_string_py, m)?)?;
+    m.add("OUTCOME_WIN", engine::OUTCOME_WIN)?;
+    m.add("OUTCOME_LOSS", engine::OUTCOME_LOSS)?;
+    Ok(())
+}
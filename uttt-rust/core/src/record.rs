@@ -0,0 +1,408 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    engine::Move,
+    game::{Game, GameResult},
+    move_from_string, move_string,
+    versioning::{migrate_text, tag_text, Format, VersionError},
+};
+
+/*
+ * A text format for exchanging games, modelled on PGN, which records the players,
+ * date, result, time control and variant of a game in headers, and its moves with
+ * optional comments and numeric annotation glyphs (NAGs), such as
+ *
+ *     v1
+ *     [X "depth 8"]
+ *     [O "depth 6"]
+ *     [Date "2024.05.01"]
+ *     [Result "1-0"]
+ *     [TimeControl "300+5"]
+ *     [Variant "standard"]
+ *
+ *     1. c/c {Takes the centre.} 1... c/nw $2 2. nw/c c/ne 1-0
+ *
+ * Move numbers count moves of both sides, as in chess. A comment in braces follows
+ * the move it describes, and a comment before the first move describes the whole game.
+ * When parsing, move numbers are optional, and the suffixes `!`, `?`, `!!`, `??`,
+ * `!?` and `?!` of a move are read as the equivalent NAGs.
+ */
+
+// The headers written first, in this order, even when they are unknown.
+pub const ROSTER: [&str; 6] = ["X", "O", "Date", "Result", "TimeControl", "Variant"];
+
+// The value of a roster header which has not been set.
+fn unknown_value(name: &str) -> &'static str {
+    match name {
+        "Date" => "????.??.??",
+        "TimeControl" => "-",
+        "Variant" => "standard",
+        _ => "?",
+    }
+}
+
+// The NAGs of the move suffixes, which are also the symbols of `analysis`.
+const NAG_SYMBOLS: [(u8, &str); 6] =
+    [(1, "!"), (2, "?"), (3, "!!"), (4, "??"), (5, "!?"), (6, "?!")];
+
+pub fn nag_from_symbol(symbol: &str) -> Option<u8> {
+    NAG_SYMBOLS.iter().find(|(_, s)| *s == symbol).map(|&(nag, _)| nag)
+}
+
+pub fn nag_symbol(nag: u8) -> Option<&'static str> {
+    NAG_SYMBOLS.iter().find(|&&(n, _)| n == nag).map(|&(_, symbol)| symbol)
+}
+
+// The comment and NAGs of a move, either of which may be absent.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Annotation {
+    pub nags: Vec<u8>,
+    pub comment: Option<String>,
+}
+
+/**
+ * A game with its headers and annotations. The `Result` header is kept as `result`,
+ * which is `None` for a game in progress (written as `*`).
+ */
+#[derive(Clone, Debug)]
+pub struct GameRecord {
+    // Headers other than `Result`, in the order they were read or set.
+    pub headers: Vec<(String, String)>,
+    pub game: Game,
+    // The annotation of each move, which has the same length as the moves of `game`.
+    pub annotations: Vec<Annotation>,
+    // A comment on the whole game, written before the first move.
+    pub comment: Option<String>,
+    pub result: Option<GameResult>,
+}
+
+impl GameRecord {
+    pub fn new(game: Game, result: Option<GameResult>) -> Self {
+        GameRecord {
+            headers: Vec::new(),
+            annotations: vec![Annotation::default(); game.moves().len()],
+            game,
+            comment: None,
+            result,
+        }
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    // Sets a header, replacing any with the same name.
+    pub fn set_header(&mut self, name: &str, value: &str) {
+        match self.headers.iter_mut().find(|(n, _)| n == name) {
+            Some(header) => header.1 = value.to_string(),
+            None => self.headers.push((name.to_string(), value.to_string())),
+        }
+    }
+}
+
+fn result_string(result: Option<GameResult>) -> &'static str {
+    result.map_or("*", GameResult::notation)
+}
+
+fn parse_result(token: &str) -> Option<Option<GameResult>> {
+    match token {
+        "*" => Some(None),
+        _ => GameResult::from_notation(token).map(Some),
+    }
+}
+
+// Formats a header, escaping quotes and backslashes in its value.
+fn header_string(name: &str, value: &str) -> String {
+    let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("[{name} \"{value}\"]")
+}
+
+// Braces cannot be escaped within a comment, so closing braces are dropped.
+fn comment_string(comment: &str) -> String {
+    format!("{{{}}}", comment.replace('}', ""))
+}
+
+// Lines of move text are wrapped at this width, as in PGN.
+const LINE_WIDTH: usize = 80;
+
+// Writes a record in the current version of the format, tagged with that version.
+pub fn record_string(record: &GameRecord) -> String {
+    let mut lines = Vec::new();
+    for name in ROSTER {
+        let value = match name {
+            "Result" => result_string(record.result),
+            _ => record.header(name).unwrap_or_else(|| unknown_value(name)),
+        };
+        lines.push(header_string(name, value));
+    }
+    for (name, value) in &record.headers {
+        if !ROSTER.contains(&name.as_str()) {
+            lines.push(header_string(name, value));
+        }
+    }
+
+    let mut tokens = Vec::new();
+    if let Some(comment) = &record.comment {
+        tokens.push(comment_string(comment));
+    }
+    // After an annotation, the move of O is numbered again, as `1...`.
+    let mut renumber = false;
+    for (ply, &mv) in record.game.moves().iter().enumerate() {
+        // Move numbers are kept on the same line as their move.
+        let number = ply / 2 + 1;
+        tokens.push(match ply % 2 {
+            0 => format!("{number}. {}", move_string(mv)),
+            _ if renumber => format!("{number}... {}", move_string(mv)),
+            _ => move_string(mv),
+        });
+        let annotation = record.annotations.get(ply).cloned().unwrap_or_default();
+        tokens.extend(annotation.nags.iter().map(|nag| format!("${nag}")));
+        if let Some(comment) = &annotation.comment {
+            tokens.push(comment_string(comment));
+        }
+        renumber = !annotation.nags.is_empty() || annotation.comment.is_some();
+    }
+    tokens.push(result_string(record.result).to_string());
+
+    let mut movetext = vec![String::new()];
+    for token in tokens {
+        let line = movetext.last_mut().unwrap();
+        if line.is_empty() {
+            line.push_str(&token);
+        } else if line.len() + 1 + token.len() <= LINE_WIDTH {
+            line.push(' ');
+            line.push_str(&token);
+        } else {
+            movetext.push(token);
+        }
+    }
+    let body = format!("{}\n\n{}\n", lines.join("\n"), movetext.join("\n"));
+    format!("{}\n{body}", tag_text(Format::MatchRecord, "").trim_end())
+}
+
+// Parses a header line of `[<name> "<value>"]`.
+fn parse_header(line: &str) -> Result<(String, String), &'static str> {
+    let inner = line
+        .strip_prefix('[')
+        .and_then(|line| line.strip_suffix(']'))
+        .ok_or("header invalid")?;
+    let (name, value) = inner.split_once(char::is_whitespace).ok_or("header invalid")?;
+    let value = value
+        .trim()
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .ok_or("header invalid")?;
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.push(chars.next().ok_or("header invalid")?),
+            '"' => return Err("header invalid"),
+            c => unescaped.push(c),
+        }
+    }
+    Ok((name.to_string(), unescaped))
+}
+
+// Splits a move token into the move and any suffix, such as `!?`.
+fn split_suffix(token: &str) -> (&str, &str) {
+    let end = token.trim_end_matches(['!', '?']).len();
+    token.split_at(end)
+}
+
+// Appends to a comment, as a move may be followed by several.
+fn add_comment(comment: &mut Option<String>, text: &str) {
+    let text = text.trim();
+    *comment = Some(match comment.take() {
+        Some(existing) => format!("{existing} {text}"),
+        None => text.to_string(),
+    });
+}
+
+/**
+ * Parses a record, which may begin with a version tag (see `versioning`).
+ * The result may be given by the `Result` header, after the moves, or both,
+ * in which case they must agree. Every move must be legal.
+ */
+pub fn parse_record(text: &str) -> Result<GameRecord, &'static str> {
+    let text = migrate_text(Format::MatchRecord, text).map_err(VersionError::description)?;
+    let mut record = GameRecord::new(Game::new(), None);
+    let mut header_result = None;
+    let mut lines = text.lines().peekable();
+    while let Some(line) = lines.peek().map(|line| line.trim()) {
+        if line.starts_with('[') {
+            let (name, value) = parse_header(line)?;
+            if name == "Result" {
+                header_result = Some(parse_result(&value).ok_or("result invalid")?);
+            } else {
+                record.set_header(&name, &value);
+            }
+        } else if !line.is_empty() {
+            break;
+        }
+        lines.next();
+    }
+
+    let movetext: Vec<&str> = lines.collect();
+    let movetext = movetext.join("\n");
+    let mut rest = movetext.as_str();
+    let mut moves_result = None;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        if moves_result.is_some() {
+            return Err("text after result");
+        }
+        if let Some(comment) = rest.strip_prefix('{') {
+            let (comment, after) = comment.split_once('}').ok_or("comment unterminated")?;
+            match record.annotations.last_mut() {
+                Some(annotation) => add_comment(&mut annotation.comment, comment),
+                None => add_comment(&mut record.comment, comment),
+            }
+            rest = after;
+            continue;
+        }
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c == '{')
+            .unwrap_or(rest.len());
+        let (token, after) = rest.split_at(end);
+        rest = after;
+        if let Some(nag) = token.strip_prefix('$') {
+            let nag = nag.parse().map_err(|_| "annotation invalid")?;
+            let annotation = record.annotations.last_mut().ok_or("annotation invalid")?;
+            annotation.nags.push(nag);
+            continue;
+        }
+        if let Some(result) = parse_result(token) {
+            moves_result = Some(result);
+            continue;
+        }
+        // Move numbers may be written apart from their move, or joined to it as in `1.c/c`.
+        let token = token.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+        if token.is_empty() {
+            continue;
+        }
+        let (mv, suffix) = split_suffix(token);
+        let mv: Move = move_from_string(mv).ok_or("move invalid")?;
+        record.game.play(mv)?;
+        let mut annotation = Annotation::default();
+        if !suffix.is_empty() {
+            annotation.nags.push(nag_from_symbol(suffix).ok_or("annotation invalid")?);
+        }
+        record.annotations.push(annotation);
+    }
+
+    record.result = match (header_result, moves_result) {
+        (Some(a), Some(b)) if a != b => return Err("result inconsistent"),
+        (Some(result), _) | (None, Some(result)) => result,
+        (None, None) => None,
+    };
+    Ok(record)
+}
+
+// Whether a line begins a record: a version tag, such as `v1`.
+fn is_version_tag(line: &str) -> bool {
+    line.strip_prefix('v')
+        .is_some_and(|version| !version.is_empty() && version.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/**
+ * Parses every record of a file, where each record begins with a version tag,
+ * or with headers following the moves of the record before it.
+ * Returns an error naming the first line of the first invalid record.
+ */
+pub fn parse_records(text: &str) -> Result<Vec<GameRecord>, String> {
+    // The first line of each record, and whether moves have been read since.
+    let mut starts = Vec::new();
+    let mut in_moves = true;
+    for (line_number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if is_version_tag(line) || (line.starts_with('[') && in_moves) {
+            starts.push(line_number);
+            in_moves = false;
+        } else if !line.is_empty() && !line.starts_with('[') {
+            in_moves = true;
+        }
+    }
+    let lines: Vec<&str> = text.lines().collect();
+    let mut records = Vec::new();
+    for (i, &start) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).copied().unwrap_or(lines.len());
+        let record = parse_record(&lines[start..end].join("\n"))
+            .map_err(|e| format!("line {} {e}", start + 1))?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+// A record as a JSON object (see `record_json`).
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonRecord {
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+    moves: String,
+    #[serde(default)]
+    annotations: Vec<Annotation>,
+    #[serde(default)]
+    comment: Option<String>,
+    #[serde(default)]
+    result: Option<String>,
+}
+
+/**
+ * Converts a record to a JSON object of its `headers` as `[name, value]` pairs,
+ * its `moves` as a transcript, the `annotations` of each move as `{nags, comment}`,
+ * the game's `comment`, and the `result` in the notation of `GameResult`, or null.
+ */
+pub fn record_json(record: &GameRecord) -> serde_json::Value {
+    serde_json::json!(JsonRecord {
+        headers: record.headers.clone(),
+        moves: record.game.transcript(),
+        annotations: record.annotations.clone(),
+        comment: record.comment.clone(),
+        result: record.result.map(|result| result.notation().to_string()),
+    })
+}
+
+/**
+ * Parses a record from a JSON object as given by `record_json`, where only `moves`
+ * is required. There may be fewer annotations than moves, but not more.
+ */
+pub fn record_from_json(json: &str) -> Result<GameRecord, &'static str> {
+    record_from_json_record(serde_json::from_str(json).map_err(|_| "record invalid")?)
+}
+
+// Parses a record from a JSON value, as `record_from_json` does from text.
+pub fn record_from_value(json: serde_json::Value) -> Result<GameRecord, &'static str> {
+    record_from_json_record(serde_json::from_value(json).map_err(|_| "record invalid")?)
+}
+
+fn record_from_json_record(json: JsonRecord) -> Result<GameRecord, &'static str> {
+    let game = Game::from_transcript(&json.moves)?;
+    if json.annotations.len() > game.moves().len() {
+        return Err("annotations invalid");
+    }
+    let result = match json.result {
+        Some(result) => Some(GameResult::from_notation(&result).ok_or("result invalid")?),
+        None => None,
+    };
+    let mut record = GameRecord::new(game, result);
+    for (name, value) in &json.headers {
+        if name == "Result" {
+            return Err("header invalid");
+        }
+        record.set_header(name, value);
+    }
+    for (ply, annotation) in json.annotations.into_iter().enumerate() {
+        record.annotations[ply] = annotation;
+    }
+    record.comment = json.comment;
+    Ok(record)
+}
@@ -0,0 +1,193 @@
+use crate::{
+    board_from_string, board_string,
+    engine::{board_from_choices, generate_moves, init_with, Board, Eval, EvalParams, Move},
+    move_from_string, move_string,
+    rng::Rng,
+    search::{iterative_deepening, SearchContext},
+    tt::TranspositionTable,
+};
+
+/*
+ * Detection of unintended changes to the engine's play, such as a refactor of the board
+ * representation might make. The same positions are searched by two engines, which are
+ * either two configurations of this build, or this build and another whose results were
+ * saved to a file, and every position where they choose different moves, or where their
+ * scores differ by more than a tolerance, is reported.
+ *
+ * Searches are to a fixed depth, each with a fresh transposition table, so that
+ * an unchanged engine gives exactly the same results on every run and machine.
+ * Results are saved a line per position, as `<board> <x|o> <move> <eval>`.
+ */
+
+// How an engine searches the positions of a regression run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RegressionConfig {
+    pub depth: usize,
+    pub hash_mb: usize,
+    pub eval_params: EvalParams,
+    pub shallow_ordering: bool,
+}
+
+impl RegressionConfig {
+    pub fn new(depth: usize, hash_mb: usize) -> Self {
+        RegressionConfig {
+            depth,
+            hash_mb,
+            eval_params: EvalParams::DEFAULT,
+            shallow_ordering: false,
+        }
+    }
+
+    /**
+     * Changes settings given as comma-separated `<name>=<value>` pairs, where a name
+     * is that of an evaluation weight (see `EvalParams`) or `shallow_ordering`,
+     * whose value is `on` or `off`. Returns an error naming the first invalid pair.
+     */
+    pub fn set(&mut self, settings: &str) -> Result<(), String> {
+        for setting in settings.split(',').filter(|s| !s.is_empty()) {
+            let invalid = || format!("setting invalid: {setting}");
+            let (name, value) = setting.split_once('=').ok_or_else(invalid)?;
+            match name {
+                "shallow_ordering" => {
+                    self.shallow_ordering = match value {
+                        "on" => true,
+                        "off" => false,
+                        _ => return Err(invalid()),
+                    }
+                }
+                _ => {
+                    let value = value.parse().map_err(|_| invalid())?;
+                    self.eval_params.set(name, value).map_err(|_| invalid())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// The result of searching one position, which is `None` for a finished game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PositionResult {
+    pub board: Board,
+    pub side: bool,
+    pub best: Option<(Move, Eval)>,
+}
+
+// Positions from seeded random games, with the side to move, for when no suite is given.
+pub fn random_positions(count: usize, seed: u64) -> Vec<(Board, bool)> {
+    let mut rng = Rng::new(seed);
+    let mut positions = Vec::with_capacity(count);
+    while positions.len() < count {
+        let length = (rng.next_u64() % 50) as usize;
+        let choices: Vec<u8> = (0..length).map(|_| rng.next_u64() as u8).collect();
+        let (board, side) = board_from_choices(&choices);
+        if generate_moves(board).next().is_some() {
+            positions.push((board, side));
+        }
+    }
+    positions
+}
+
+// Searches every position with an engine configured by `config`.
+pub fn run_positions(
+    positions: &[(Board, bool)],
+    config: &RegressionConfig,
+) -> Vec<PositionResult> {
+    let tables = init_with(&config.eval_params);
+    positions
+        .iter()
+        .map(|&(board, side)| {
+            let mut tt = TranspositionTable::new(config.hash_mb);
+            let mut ctx = SearchContext::new(&tables, config.depth);
+            ctx.tt = Some(&mut tt);
+            ctx.eval_params = config.eval_params;
+            ctx.shallow_ordering = config.shallow_ordering;
+            let result = iterative_deepening(board, side, config.depth, &mut ctx);
+            PositionResult {
+                board,
+                side,
+                best: result.map(|result| (result.pv[0], result.eval)),
+            }
+        })
+        .collect()
+}
+
+// Writes a result as a line of a results file.
+pub fn result_line(result: &PositionResult) -> String {
+    let side = if result.side { "o" } else { "x" };
+    match result.best {
+        Some((mv, eval)) => {
+            format!("{} {side} {} {eval}", board_string(result.board), move_string(mv))
+        }
+        None => format!("{} {side} none 0", board_string(result.board)),
+    }
+}
+
+// Reads a line written by `result_line`.
+pub fn parse_result_line(line: &str) -> Result<PositionResult, &'static str> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    if tokens.len() != 5 {
+        return Err("result invalid");
+    }
+    let board = board_from_string(&format!("{} {}", tokens[0], tokens[1])).ok_or("board invalid")?;
+    let side = match tokens[2] {
+        "x" => false,
+        "o" => true,
+        _ => return Err("side invalid"),
+    };
+    let eval: Eval = tokens[4].parse().map_err(|_| "eval invalid")?;
+    let best = match tokens[3] {
+        "none" => None,
+        mv => Some((move_from_string(mv).ok_or("move invalid")?, eval)),
+    };
+    Ok(PositionResult { board, side, best })
+}
+
+// A position where the two engines disagree.
+#[derive(Clone, Copy, Debug)]
+pub struct Divergence {
+    // The position's index in the run.
+    pub index: usize,
+    pub a: PositionResult,
+    pub b: PositionResult,
+    // Whether the best moves differ, as opposed to the scores alone.
+    pub move_differs: bool,
+}
+
+/**
+ * Compares the results of two engines on the same positions, listing those where they
+ * choose different moves or their scores differ by more than `tolerance`. Returns
+ * an error if the runs are not of the same positions, in which case nothing can be said.
+ */
+pub fn compare(
+    a: &[PositionResult],
+    b: &[PositionResult],
+    tolerance: Eval,
+) -> Result<Vec<Divergence>, &'static str> {
+    if a.len() != b.len() || a.iter().zip(b).any(|(a, b)| (a.board, a.side) != (b.board, b.side)) {
+        return Err("positions differ");
+    }
+    let divergences = a
+        .iter()
+        .zip(b)
+        .enumerate()
+        .filter_map(|(index, (&a, &b))| {
+            let (move_differs, score_differs) = match (a.best, b.best) {
+                (Some((a_move, a_eval)), Some((b_move, b_eval))) => {
+                    (a_move != b_move, (a_eval - b_eval).abs() > tolerance)
+                }
+                (a_best, b_best) => (a_best != b_best, false),
+            };
+            match move_differs || score_differs {
+                true => Some(Divergence {
+                    index,
+                    a,
+                    b,
+                    move_differs,
+                }),
+                false => None,
+            }
+        })
+        .collect();
+    Ok(divergences)
+}
@@ -0,0 +1,226 @@
+use std::fmt::Write;
+
+use serde::Deserialize;
+
+use crate::{
+    engine::{Board, Move, ZONE_ANY},
+    move_from_string,
+};
+
+// Side length of the image in pixels if none is given.
+pub const DEFAULT_SVG_SIZE: u32 = 360;
+
+// Bounds on the side length, so that the marks remain legible
+// and the output stays a reasonable size.
+pub const MIN_SVG_SIZE: u32 = 90;
+pub const MAX_SVG_SIZE: u32 = 4096;
+
+const MARK_X_COLOUR: &str = "#d33";
+const MARK_O_COLOUR: &str = "#33d";
+const ZONE_HIGHLIGHT_COLOUR: &str = "#fff3b0";
+const LAST_MOVE_COLOUR: &str = "#bde0ff";
+const LINE_COLOUR: &str = "#222";
+
+/**
+ * How a board is drawn by `render_svg`.
+ * The last move, if any, is shaded, as are the zones the side to move may play in
+ * if `highlight_zone` is set.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SvgOptions {
+    pub size: u32,
+    pub last_move: Option<Move>,
+    pub highlight_zone: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            size: DEFAULT_SVG_SIZE,
+            last_move: None,
+            highlight_zone: true,
+        }
+    }
+}
+
+// The options as given in JSON, with the last move as a move string.
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct JsonOptions {
+    size: u32,
+    last_move: Option<String>,
+    highlight_zone: bool,
+}
+
+impl Default for JsonOptions {
+    fn default() -> Self {
+        let defaults = SvgOptions::default();
+        JsonOptions {
+            size: defaults.size,
+            last_move: None,
+            highlight_zone: defaults.highlight_zone,
+        }
+    }
+}
+
+impl SvgOptions {
+    /**
+     * Parses options from a JSON object such as
+     * `{"size": 480, "last_move": "c/c", "highlight_zone": false}`,
+     * where every field may be left out. An empty string gives the defaults.
+     */
+    pub fn from_json(json: &str) -> Result<Self, &'static str> {
+        if json.trim().is_empty() {
+            return Ok(SvgOptions::default());
+        }
+        let options: JsonOptions = serde_json::from_str(json).map_err(|_| "options invalid")?;
+        if !(MIN_SVG_SIZE..=MAX_SVG_SIZE).contains(&options.size) {
+            return Err("size invalid");
+        }
+        let last_move = match options.last_move {
+            Some(mv) => Some(move_from_string(&mv).ok_or("move invalid")?),
+            None => None,
+        };
+        Ok(SvgOptions {
+            size: options.size,
+            last_move,
+            highlight_zone: options.highlight_zone,
+        })
+    }
+}
+
+// The occupant of each cell and zone: `Some(false)` for X, `Some(true)` for O.
+fn cell_owner(board: Board, cell: u64) -> Option<bool> {
+    let (us, them, share) = board;
+    let (x, o) = if cell < 63 {
+        ((us >> cell) & 1, (them >> cell) & 1)
+    } else {
+        ((share >> (cell - 63)) & 1, (share >> (cell - 45)) & 1)
+    };
+    match (x, o) {
+        (1, _) => Some(false),
+        (_, 1) => Some(true),
+        _ => None,
+    }
+}
+
+fn zone_owner(board: Board, zone: u64) -> Option<bool> {
+    match ((board.2 >> (36 + zone)) & 1, (board.2 >> (45 + zone)) & 1) {
+        (1, _) => Some(false),
+        (_, 1) => Some(true),
+        _ => None,
+    }
+}
+
+// Draws a mark centred on (`x`, `y`) within a square of side `extent`.
+fn write_mark(svg: &mut String, o: bool, x: f64, y: f64, extent: f64, stroke: f64) {
+    let r = extent * 0.3;
+    if o {
+        let _ = write!(
+            svg,
+            "<circle cx='{x:.1}' cy='{y:.1}' r='{r:.1}' fill='none' \
+             stroke='{MARK_O_COLOUR}' stroke-width='{stroke:.1}'/>",
+        );
+    } else {
+        let _ = write!(
+            svg,
+            "<path d='M{:.1} {:.1}L{:.1} {:.1}M{:.1} {:.1}L{:.1} {:.1}' \
+             stroke='{MARK_X_COLOUR}' stroke-width='{stroke:.1}' stroke-linecap='round'/>",
+            x - r,
+            y - r,
+            x + r,
+            y + r,
+            x + r,
+            y - r,
+            x - r,
+            y + r,
+        );
+    }
+}
+
+/**
+ * Renders a board as a standalone SVG image, with no scripts or external resources,
+ * so that it can be saved or served as a preview of the position.
+ * Marks are drawn as red crosses and blue circles, and a won zone is covered
+ * by a large mark of its winner.
+ */
+pub fn render_svg(board: Board, options: &SvgOptions) -> String {
+    let size = options.size as f64;
+    let cell = size / 9.0;
+    let zone_field = (board.2 >> 54) & 0b1111;
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        "<svg xmlns='http://www.w3.org/2000/svg' width='{0}' height='{0}' \
+         viewBox='0 0 {0} {0}'>",
+        options.size,
+    );
+    let _ = write!(svg, "<rect width='{0}' height='{0}' fill='white'/>", options.size);
+
+    // Cells are numbered by zone, then by position within the zone,
+    // as in the move representation.
+    let origin = |mv: u64| {
+        let (zone, square) = (mv / 9, mv % 9);
+        let column = 3 * (zone % 3) + square % 3;
+        let row = 3 * (zone / 3) + square / 3;
+        (column as f64 * cell, row as f64 * cell)
+    };
+
+    if options.highlight_zone {
+        for zone in 0..9 {
+            let playable = zone_owner(board, zone).is_none()
+                && (0..9).any(|square| cell_owner(board, 9 * zone + square).is_none());
+            if playable && (zone_field == ZONE_ANY || zone_field == zone) {
+                let (x, y) = origin(9 * zone);
+                let _ = write!(
+                    svg,
+                    "<rect x='{x:.1}' y='{y:.1}' width='{0:.1}' height='{0:.1}' \
+                     fill='{ZONE_HIGHLIGHT_COLOUR}'/>",
+                    3.0 * cell,
+                );
+            }
+        }
+    }
+    if let Some(mv) = options.last_move {
+        let (x, y) = origin(mv);
+        let _ = write!(
+            svg,
+            "<rect x='{x:.1}' y='{y:.1}' width='{cell:.1}' height='{cell:.1}' \
+             fill='{LAST_MOVE_COLOUR}'/>",
+        );
+    }
+
+    // Thin lines between cells, and thick lines between zones.
+    for i in 1..9 {
+        let offset = i as f64 * cell;
+        let width = if i % 3 == 0 { size / 90.0 } else { size / 360.0 };
+        let _ = write!(
+            svg,
+            "<path d='M{offset:.1} 0V{size:.1}M0 {offset:.1}H{size:.1}' \
+             stroke='{LINE_COLOUR}' stroke-width='{width:.1}'/>",
+        );
+    }
+
+    for mv in 0..81 {
+        if let Some(o) = cell_owner(board, mv) {
+            let (x, y) = origin(mv);
+            write_mark(&mut svg, o, x + cell / 2.0, y + cell / 2.0, cell, size / 120.0);
+        }
+    }
+    for zone in 0..9 {
+        if let Some(o) = zone_owner(board, zone) {
+            let (x, y) = origin(9 * zone);
+            let _ = write!(
+                svg,
+                "<rect x='{x:.1}' y='{y:.1}' width='{0:.1}' height='{0:.1}' \
+                 fill='white' fill-opacity='0.7'/>",
+                3.0 * cell,
+            );
+            let centre = 1.5 * cell;
+            write_mark(&mut svg, o, x + centre, y + centre, 3.0 * cell, size / 40.0);
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
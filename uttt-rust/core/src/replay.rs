@@ -0,0 +1,155 @@
+use crate::{
+    analysis::{analyze_position, PositionAnalysis},
+    engine::{Board, Eval, EvalParams, Move},
+    game::Game,
+    options::ScorePerspective,
+    tt::TranspositionTable,
+};
+
+/**
+ * Navigation through a finished or ongoing game for review, positioned at a ply
+ * (the number of moves played to reach the current position). Moving between
+ * positions looks up the boards the game already keeps, rather than replaying the moves.
+ * Iterating steps forwards, yielding each move stepped over.
+ *
+ * The analysis of each position is cached once computed, so that returning
+ * to a position does not search it again.
+ */
+#[derive(Clone, Debug)]
+pub struct Replay {
+    game: Game,
+    ply: usize,
+    // The analysis of the position at each ply, with the perspective of its score
+    // and the depth that was requested, which the search may not reach if the game ends.
+    analyses: Vec<Option<(PositionAnalysis, ScorePerspective, usize)>>,
+}
+
+impl Replay {
+    // Starts a replay of a game at its initial position.
+    pub fn new(game: Game) -> Self {
+        let analyses = vec![None; game.moves().len() + 1];
+        Replay {
+            game,
+            ply: 0,
+            analyses,
+        }
+    }
+
+    pub fn from_transcript(transcript: &str) -> Result<Self, &'static str> {
+        Game::from_transcript(transcript).map(Replay::new)
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    // The number of moves in the game, which is the last ply.
+    pub fn len(&self) -> usize {
+        self.game.moves().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.game.moves().is_empty()
+    }
+
+    // Moves to the position at `ply`, returning whether it exists.
+    pub fn seek(&mut self, ply: usize) -> bool {
+        if ply > self.len() {
+            return false;
+        }
+        self.ply = ply;
+        true
+    }
+
+    // Steps back a move, returning the move undone,
+    // or `None` at the initial position.
+    pub fn prev(&mut self) -> Option<Move> {
+        let mv = self.current_move()?;
+        self.ply -= 1;
+        Some(mv)
+    }
+
+    pub fn board(&self) -> Board {
+        self.game.board_at(self.ply).unwrap()
+    }
+
+    // The side to move in the current position (`false` for X).
+    pub fn side_to_move(&self) -> bool {
+        self.ply % 2 == 1
+    }
+
+    // The move which led to the current position, or `None` at the initial position.
+    pub fn current_move(&self) -> Option<Move> {
+        self.ply.checked_sub(1).map(|ply| self.game.moves()[ply])
+    }
+
+    // The move played from the current position, or `None` at the end of the game.
+    pub fn next_move(&self) -> Option<Move> {
+        self.game.moves().get(self.ply).copied()
+    }
+
+    // The cached analysis of the current position, if any.
+    pub fn cached_analysis(&self) -> Option<&PositionAnalysis> {
+        self.analyses[self.ply].as_ref().map(|(analysis, _, _)| analysis)
+    }
+
+    // Caches an analysis of the current position made elsewhere, such as by a worker.
+    pub fn set_analysis(&mut self, analysis: PositionAnalysis, perspective: ScorePerspective) {
+        let depth = analysis.depth;
+        self.analyses[self.ply] = Some((analysis, perspective, depth));
+    }
+
+    /**
+     * Returns the analysis of the current position to at least `depth` plies,
+     * searching only if no such analysis is cached in the same perspective.
+     * The evaluation terms of a search are those of `eval_params`.
+     * Returns `None` if the search is stopped, keeping any earlier analysis.
+     */
+    pub fn analyze(
+        &mut self,
+        depth: usize,
+        tables: &(Vec<Eval>, Vec<Eval>),
+        eval_params: EvalParams,
+        tt: &mut TranspositionTable,
+        perspective: ScorePerspective,
+        stop_condition: Option<&dyn Fn() -> bool>,
+    ) -> Option<&PositionAnalysis> {
+        let cached = match &self.analyses[self.ply] {
+            Some((_, p, requested)) => *p == perspective && *requested >= depth,
+            None => false,
+        };
+        if !cached {
+            let analysis = analyze_position(
+                self.board(),
+                depth,
+                tables,
+                eval_params,
+                tt,
+                perspective,
+                stop_condition,
+            )?;
+            // A stopped search may have completed fewer iterations than requested.
+            if stop_condition.is_some_and(|stop| stop()) {
+                return None;
+            }
+            self.analyses[self.ply] = Some((analysis, perspective, depth));
+        }
+        self.cached_analysis()
+    }
+}
+
+impl Iterator for Replay {
+    type Item = Move;
+
+    // Steps forward a move, returning the move played,
+    // or `None` at the end of the game.
+    fn next(&mut self) -> Option<Move> {
+        let mv = self.next_move()?;
+        self.ply += 1;
+        Some(mv)
+    }
+}
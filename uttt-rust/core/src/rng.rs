@@ -0,0 +1,59 @@
+use crate::zobrist::splitmix64;
+
+/**
+ * A small pseudorandom number generator for choosing between moves.
+ * It need not be of cryptographic quality, but must be seedable so that
+ * games can be reproduced, and must not depend on `std` features
+ * unavailable in the browser.
+ */
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    // Seeds a generator from the clock, for when games need not be reproducible.
+    pub fn from_clock() -> Self {
+        #[cfg(target_arch = "wasm32")]
+        let seed = js_sys::Date::now().to_bits() ^ js_sys::Math::random().to_bits();
+        #[cfg(not(target_arch = "wasm32"))]
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        Rng::new(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        splitmix64(self.state)
+    }
+
+    // A uniformly distributed value in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Chooses an index with probability proportional to its weight,
+    // or `None` if there are no positive weights.
+    pub fn choose_weighted(&mut self, weights: &[f64]) -> Option<usize> {
+        let total: f64 = weights.iter().filter(|&&w| w > 0.0).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut target = self.next_f64() * total;
+        for (index, &weight) in weights.iter().enumerate() {
+            if weight > 0.0 {
+                if target < weight {
+                    return Some(index);
+                }
+                target -= weight;
+            }
+        }
+        // Rounding can leave a tiny remainder, in which case the last choice is taken.
+        weights.iter().rposition(|&w| w > 0.0)
+    }
+}
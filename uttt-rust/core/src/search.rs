@@ -0,0 +1,836 @@
+use crate::{
+    engine::*,
+    log::{self, position_hash, pv_strings, LogRecord},
+    movelist::{generate_moves_into, MoveList},
+    tablebase::Tablebase,
+    tt::{score_from_tt, score_to_tt, Bound, TranspositionTable, DECISIVE_MARGIN},
+    timer::Timer,
+    zobrist::{hash_position, splitmix64},
+};
+
+// A score beyond any reachable score, so that a search with the window
+// from `-INFINITY` to `INFINITY` always returns an exact score.
+pub const INFINITY: Eval = OUTCOME_WIN + 1;
+
+// Nodes at least this far from the leaves without a hash move run a shallower
+// internal search first, to find a good move to search first.
+const IID_MIN_DEPTH: usize = 4;
+const IID_REDUCTION: usize = 2;
+
+// Nodes at most this far from the leaves order their moves by cheap threat features
+// of the positions they lead to, when enabled (see `SearchContext`).
+pub const SHALLOW_ORDERING_MAX_DEPTH: usize = 2;
+const ZONE_WIN_ORDER_SCORE: Eval = 2;
+const FREE_MOVE_ORDER_PENALTY: Eval = 1;
+
+// Number of nodes searched between successive checks of the stop condition.
+// Checking an external signal on every node would dominate the search time.
+// Must be a power of two, as it is used as a bit mask.
+pub(crate) const STOP_CHECK_INTERVAL: u64 = 1024;
+
+/**
+ * Optional counters describing the shape of a search,
+ * used to measure the effect of move ordering changes.
+ */
+#[derive(Clone)]
+pub struct SearchStats {
+    // Number of beta cutoffs caused by the move at each index in the move list.
+    pub cutoffs: [u64; 81],
+    // Number of transposition table probes, and how many found an entry.
+    pub tt_probes: u64,
+    pub tt_hits: u64,
+    // Number of nodes scored by the tablebase (see `SearchContext::tablebase`).
+    pub tablebase_hits: u64,
+    // Number of nodes searched by each completed iteration of iterative deepening.
+    pub iteration_nodes: Vec<u64>,
+    // The share of the search taken by each root move, in the order first searched.
+    pub root_moves: Vec<RootMoveStats>,
+    // The index in `root_moves` of the root move being searched.
+    current_root: usize,
+}
+
+// Counters for the subtree of one root move, over all iterations of a search.
+#[derive(Clone, Copy, Debug)]
+pub struct RootMoveStats {
+    pub mv: Move,
+    pub nodes: u64,
+    // Number of beta cutoffs in the subtree, and the sum of the move indices causing them.
+    pub cutoffs: u64,
+    pub cutoff_index_total: u64,
+}
+
+impl RootMoveStats {
+    // The mean index in the move list of the moves causing cutoffs in the subtree,
+    // where 0 is the first move. The lower it is, the better the moves are ordered.
+    pub fn mean_cutoff_index(&self) -> Option<f64> {
+        if self.cutoffs > 0 {
+            Some(self.cutoff_index_total as f64 / self.cutoffs as f64)
+        } else {
+            None
+        }
+    }
+}
+
+impl SearchStats {
+    pub fn new() -> Self {
+        SearchStats {
+            cutoffs: [0; 81],
+            tt_probes: 0,
+            tt_hits: 0,
+            tablebase_hits: 0,
+            iteration_nodes: Vec::new(),
+            root_moves: Vec::new(),
+            current_root: 0,
+        }
+    }
+
+    // Makes `mv` the root move whose subtree is being counted.
+    fn enter_root_move(&mut self, mv: Move) {
+        self.current_root = match self.root_moves.iter().position(|stats| stats.mv == mv) {
+            Some(index) => index,
+            None => {
+                self.root_moves.push(RootMoveStats {
+                    mv,
+                    nodes: 0,
+                    cutoffs: 0,
+                    cutoff_index_total: 0,
+                });
+                self.root_moves.len() - 1
+            }
+        };
+    }
+
+    // The effective branching factor, which is the ratio of the number of nodes
+    // searched by the last iteration to that searched by the iteration before.
+    pub fn branching_factor(&self) -> Option<f64> {
+        match self.iteration_nodes[..] {
+            [.., previous, last] if previous > 0 => Some(last as f64 / previous as f64),
+            _ => None,
+        }
+    }
+
+    // The proportion of transposition table probes that found an entry.
+    pub fn tt_hit_rate(&self) -> Option<f64> {
+        if self.tt_probes > 0 {
+            Some(self.tt_hits as f64 / self.tt_probes as f64)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for SearchStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/**
+ * Optional checking of pruning decisions, used to validate pruning heuristics
+ * empirically before they are relied on. Every `interval`-th node cut short is
+ * searched again to full depth, with the transposition table detached and without
+ * further checks, and the cases where that search contradicts the pruned result are
+ * kept. The only pruning so far is by transposition table cutoffs, which are checked
+ * when the stored depth is exactly that of the node, as deeper entries may rightly
+ * differ. Pruning added later should be checked through `verify_pruning` alike,
+ * and switched off while the full-depth search runs.
+ */
+#[derive(Clone)]
+pub struct Verification {
+    pub interval: u64,
+    // Number of pruning decisions made, and how many of them were checked.
+    pub decisions: u64,
+    pub checks: u64,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+// A pruning decision contradicted by a full-depth search of the same node.
+#[derive(Clone, Debug)]
+pub struct Discrepancy {
+    // The pruning which cut the node short, such as `tt`.
+    pub kind: &'static str,
+    pub board: Board,
+    pub side: bool,
+    pub depth: usize,
+    pub alpha: Eval,
+    pub beta: Eval,
+    // The score returned in place of searching the node, and the score of searching it.
+    pub pruned: Eval,
+    pub full: Eval,
+}
+
+impl Verification {
+    pub fn new(interval: u64) -> Self {
+        Verification {
+            interval: interval.max(1),
+            decisions: 0,
+            checks: 0,
+            discrepancies: Vec::new(),
+        }
+    }
+}
+
+/**
+ * Random noise added to the evaluation of the leaves, to weaken the engine for lower
+ * difficulty levels. Searching less deeply only makes it miss tactics, while playing
+ * the rest of the game with the same uncanny judgement, whereas noise blurs its judgement
+ * of every position. The noise is normally distributed with a standard deviation of
+ * `std_dev` in the opening, shrinking in proportion to the empty cells left, so that
+ * the engine still plays won endings cleanly. Decisive scores are never changed.
+ * The noise of a position is derived from its hash and `seed`, so that it is the same
+ * wherever the position is reached, and a search with the same seed is reproducible.
+ * Noisy results are stored in the transposition table under keys of their own,
+ * so that they are not mistaken for those of searches without noise.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EvalNoise {
+    pub std_dev: Eval,
+    pub seed: u64,
+}
+
+impl EvalNoise {
+    // The noise added to the evaluation of a position with `side` to move.
+    pub fn sample(&self, board: Board, side: bool) -> Eval {
+        let (x_count, o_count) = mark_counts(board);
+        let empty = (81 - x_count - o_count) as f64;
+        let hash = splitmix64(hash_position(board, side) ^ self.seed);
+        // A standard normal sample from two uniform ones, by the Box-Muller transform.
+        let u1 = ((hash >> 11) + 1) as f64 / (1u64 << 53) as f64;
+        let u2 = (splitmix64(hash) >> 11) as f64 / (1u64 << 53) as f64;
+        let normal = (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+        (normal * self.std_dev as f64 * empty / 81.0).round() as Eval
+    }
+
+    // Combined with position hashes to key transposition table entries.
+    fn key_mask(&self) -> u64 {
+        splitmix64(self.seed ^ ((self.std_dev as u64) << 32))
+    }
+}
+
+/**
+ * Hooks through which an embedding application follows a search as it runs, for
+ * progress displays or telemetry, without changing the engine. Each method does nothing
+ * unless overridden. The observer is called on the searching thread, so it should return
+ * quickly. A closure taking the depth, move and 1-based move number observes root moves
+ * alone, which is all that the callbacks of the bindings used to report.
+ */
+pub trait SearchObserver {
+    // Called before each root move is searched, with the current depth,
+    // the move, and its 1-based position in the root move list.
+    fn on_root_move(&self, _depth: usize, _mv: Move, _number: usize) {}
+    // Called as each iteration of iterative deepening completes,
+    // with the nodes searched so far by the whole search.
+    fn on_depth_complete(&self, _result: &SearchResult, _nodes: u64) {}
+    // Called once the search ends, with the result of its deepest completed iteration,
+    // which is `None` if it was stopped before completing one.
+    fn on_finish(&self, _result: Option<&SearchResult>, _nodes: u64) {}
+}
+
+impl<F: Fn(usize, Move, usize)> SearchObserver for F {
+    fn on_root_move(&self, depth: usize, mv: Move, number: usize) {
+        self(depth, mv, number)
+    }
+}
+
+/**
+ * State shared by every node of a single search.
+ * The lookup tables and maximum depth used to be passed as separate arguments,
+ * but are now grouped alongside the bookkeeping needed to interrupt a search.
+ */
+pub struct SearchContext<'a> {
+    // Any backend of the lookup tables (see `EvalTables`), usually those of `init`.
+    pub tables: &'a dyn EvalTables,
+    pub max_depth: usize,
+    pub nodes: u64,
+    // The maximum ply reached in the current iteration.
+    pub seldepth: usize,
+    pub stopped: bool,
+    pub stats: Option<SearchStats>,
+    // Checks pruning decisions when present (see `Verification`).
+    pub verification: Option<Verification>,
+    // Results are shared across iterations and searches through this table.
+    pub tt: Option<&'a mut TranspositionTable>,
+    // Polled periodically during the search. Returning `true` aborts it.
+    pub stop_condition: Option<&'a dyn Fn() -> bool>,
+    // Told of the progress of the search (see `SearchObserver`).
+    pub observer: Option<&'a dyn SearchObserver>,
+    // Whether moves near the leaves are ordered by threat features (see `threat_score`).
+    // On the bench positions this searches about 10% fewer nodes, but takes about
+    // 25% longer, as scoring every move costs more than it saves, so it is off by default.
+    // Ordering by full static evaluation searched fewer nodes still, but was slower again.
+    pub shallow_ordering: bool,
+    // The evaluation terms applied at the leaves on top of `tables` (see `evaluate_with`).
+    pub eval_params: EvalParams,
+    // Noise added to the evaluation of the leaves when present (see `EvalNoise`).
+    pub eval_noise: Option<EvalNoise>,
+    // The only root moves searched, if not every legal move, such as the safe moves
+    // of a weakened search (see `humanlike::safest_moves`). It must hold a legal move.
+    pub allowed_root_moves: Option<Vec<Move>>,
+    // Amounts taken off the scores of root moves, such as for contempt
+    // (see `Book::draw_penalties`). Moves not listed are scored as usual.
+    pub root_penalties: Vec<(Move, Eval)>,
+    // Exact results of late positions, which replace searching them when present.
+    pub tablebase: Option<&'a Tablebase>,
+    // A move list for each ply, reused from node to node, as setting up
+    // a new buffer at every node costs more than generating the moves.
+    move_lists: Vec<MoveList>,
+    // The best move of the last completed iteration, searched first at the root.
+    previous_best: Move,
+}
+
+impl<'a> SearchContext<'a> {
+    pub fn new(tables: &'a dyn EvalTables, max_depth: usize) -> Self {
+        SearchContext {
+            tables,
+            max_depth,
+            nodes: 0,
+            seldepth: 0,
+            stopped: false,
+            stats: None,
+            verification: None,
+            tt: None,
+            stop_condition: None,
+            observer: None,
+            shallow_ordering: false,
+            eval_params: EvalParams::DEFAULT,
+            eval_noise: None,
+            allowed_root_moves: None,
+            root_penalties: Vec::new(),
+            tablebase: None,
+            move_lists: vec![MoveList::new(); MAX_PLY],
+            previous_best: NULL_MOVE,
+        }
+    }
+
+    // Counts a visited node, and checks whether the search should be aborted.
+    // Once a stop is requested, it stays in effect for the rest of the search.
+    #[inline]
+    fn visit(&mut self) -> bool {
+        self.nodes += 1;
+        if !self.stopped && self.nodes & (STOP_CHECK_INTERVAL - 1) == 0 {
+            if let Some(stop_condition) = self.stop_condition {
+                self.stopped = stop_condition();
+            }
+        }
+        self.stopped
+    }
+}
+
+/**
+ * Checks a decision to cut a node short, if verification is enabled and the decision
+ * is due to be checked, by searching the node to full depth with the same window,
+ * given as `(alpha, beta)`. `pruned` is the score returned in place of searching it,
+ * which claims what the full search's score would: a bound outside the window,
+ * or an exact score.
+ */
+pub fn verify_pruning(
+    kind: &'static str,
+    board: Board,
+    side: bool,
+    depth: usize,
+    (alpha, beta): (Eval, Eval),
+    pruned: Eval,
+    ctx: &mut SearchContext,
+) {
+    let verification = match &mut ctx.verification {
+        Some(verification) => verification,
+        None => return,
+    };
+    verification.decisions += 1;
+    if verification.decisions % verification.interval != 0 {
+        return;
+    }
+    let mut verification = ctx.verification.take().unwrap();
+    let tt = ctx.tt.take();
+    let (full, _) = alpha_beta(board, side, depth, alpha, beta, ctx);
+    ctx.tt = tt;
+    if !ctx.stopped {
+        verification.checks += 1;
+        let agrees = if pruned >= beta {
+            full >= beta
+        } else if pruned <= alpha {
+            full <= alpha
+        } else {
+            full == pruned
+        };
+        if !agrees {
+            verification.discrepancies.push(Discrepancy {
+                kind,
+                board,
+                side,
+                depth,
+                alpha,
+                beta,
+                pruned,
+                full,
+            });
+        }
+    }
+    ctx.verification = Some(verification);
+}
+
+// Scores a move for ordering by what it achieves: winning a zone is good,
+// and sending the opponent to a won or full zone, which frees them to play anywhere, is bad.
+#[inline]
+fn threat_score(before: Board, after: Board) -> Eval {
+    let won_zone = (after.2 >> 36) & DBLCHUNK != (before.2 >> 36) & DBLCHUNK;
+    let free_move = (after.2 >> 54) & 0b1111 == ZONE_ANY;
+    ZONE_WIN_ORDER_SCORE * won_zone as Eval - FREE_MOVE_ORDER_PENALTY * free_move as Eval
+}
+
+/**
+ * The main alpha-beta minimax function.
+ * Uses a negamax construct since the heuristic is symmetric.
+ * Returns evaluation and the principal variation.
+ * The search is fail-soft: a score of at least `beta` is a lower bound,
+ * a score of at most `alpha` is an upper bound, and any other score is exact.
+ * If the search is stopped, the returned values are meaningless
+ * and must be discarded by the caller, which should check `ctx.stopped`.
+ */
+pub fn alpha_beta(
+    board: Board,
+    side: bool,
+    depth: usize,
+    mut alpha: Eval, // The `alpha` variable will be updated throughout, and is cheaply copied.
+    beta: Eval,
+    ctx: &mut SearchContext,
+) -> (Eval, [u64; MAX_PLY]) {
+    // It is not always necessary to destructure the board,
+    // as only one branch of this function uses one of the components.
+    // The board is otherwise passed as is.
+
+    // Unwind as quickly as possible once the search has been stopped.
+    if ctx.visit() {
+        return (OUTCOME_DRAW, [NULL_MOVE; MAX_PLY]);
+    }
+
+    // The number of plies from the root to this node.
+    let ply = ctx.max_depth - depth;
+    ctx.seldepth = ctx.seldepth.max(ply);
+
+    // A position in the tablebase is scored exactly, whatever the window, as the score
+    // of a fail-soft search may lie anywhere. The root is searched all the same,
+    // as it needs a move. A score inside the window comes with the line to the end
+    // of the game as the PV, taken from the tablebase as far as it holds it.
+    if let (true, Some(tablebase)) = (ply > 0, ctx.tablebase) {
+        if let Some(entry) = tablebase.probe(board, side) {
+            if let Some(stats) = &mut ctx.stats {
+                stats.tablebase_hits += 1;
+            }
+            let score = entry.score(ply);
+            let mut pv = [NULL_MOVE; MAX_PLY];
+            if alpha < score && score < beta {
+                let (mut board, mut side) = (board, side);
+                for slot in &mut pv[ply..] {
+                    match tablebase.best_move(board, side) {
+                        Some(mv) => *slot = mv,
+                        None => break,
+                    }
+                    board = play_move(board, *slot, side);
+                    side = !side;
+                }
+            }
+            return (score, pv);
+        }
+    }
+
+    // Leaf node returns static evaluation and empty PV.
+    if depth == 0 {
+        let mut eval = evaluate_with(board, side, ctx.tables, &ctx.eval_params);
+        if let Some(noise) = &ctx.eval_noise {
+            // Noisy scores are kept clear of decisive ones.
+            if eval != OUTCOME_WIN && eval != OUTCOME_LOSS {
+                let limit = OUTCOME_WIN - DECISIVE_MARGIN - 1;
+                eval = (eval + noise.sample(board, side)).clamp(-limit, limit);
+            }
+        }
+        // In this branch, we also check whether the evaluation is conclusive or not.
+        // If it is conclusive, we adjust it based on the number of moves to win/loss.
+        let adjusted_eval = match eval {
+            OUTCOME_WIN => eval - ply as i32,
+            OUTCOME_LOSS => eval + ply as i32,
+            _ => eval,
+        };
+        return (adjusted_eval, [NULL_MOVE; MAX_PLY]);
+    }
+
+    // Probe the transposition table for a stored result of this position.
+    // A stored bound can only cut off this node if it falls outside the window,
+    // since an exact score inside the window would leave this node without a PV.
+    let key = match (&ctx.tt, &ctx.eval_noise) {
+        (None, _) => 0,
+        (Some(_), None) => hash_position(board, side),
+        (Some(_), Some(noise)) => hash_position(board, side) ^ noise.key_mask(),
+    };
+    let mut hash_move = NULL_MOVE;
+    if let Some(tt) = &ctx.tt {
+        let entry = tt.probe(key);
+        if let Some(stats) = &mut ctx.stats {
+            stats.tt_probes += 1;
+            stats.tt_hits += entry.is_some() as u64;
+        }
+        if let Some(entry) = entry {
+            hash_move = entry.best_move();
+            if ply > 0 && entry.depth as usize >= depth {
+                let score = score_from_tt(entry.score, ply);
+                if (score >= beta && entry.bound != Bound::Upper)
+                    || (score <= alpha && entry.bound != Bound::Lower)
+                {
+                    if ctx.verification.is_some() && entry.depth as usize == depth {
+                        verify_pruning("tt", board, side, depth, (alpha, beta), score, ctx);
+                    }
+                    return (score, [NULL_MOVE; MAX_PLY]);
+                }
+            }
+        }
+    }
+
+    // Root results shifted by penalties or restricted to some moves are kept out of the table
+    // (see `store` below), so the best move of the last iteration stands in for the hash move,
+    // which also spares running internal iterative deepening at the root on every iteration.
+    if ply == 0 && ctx.previous_best != NULL_MOVE {
+        hash_move = ctx.previous_best;
+    }
+
+    // Moves are only generated below the leaves, so the ply is always in range.
+    generate_moves_into(board, &mut ctx.move_lists[ply]);
+
+    // The hash move is searched first if it is legal in this position,
+    // which also guards against using the move of a colliding position.
+    let mut hash_move_legal = hash_move != NULL_MOVE && ctx.move_lists[ply].contains(hash_move);
+
+    // Internal iterative deepening: without a hash move, a reduced-depth search
+    // of this same node provides the best guess for the first move instead.
+    // The maximum depth is reduced alongside, so that the ply of this node
+    // (and hence the PV index and decisive score adjustment) stays the same.
+    if !hash_move_legal && depth >= IID_MIN_DEPTH {
+        ctx.max_depth -= IID_REDUCTION;
+        let (_, line) = alpha_beta(board, side, depth - IID_REDUCTION, alpha, beta, ctx);
+        ctx.max_depth += IID_REDUCTION;
+        if ctx.stopped {
+            return (OUTCOME_DRAW, [NULL_MOVE; MAX_PLY]);
+        }
+        // A move is only found if one raised alpha, or if the reduced search
+        // stored one in the transposition table.
+        hash_move = match (line[ply], &ctx.tt) {
+            (NULL_MOVE, Some(tt)) => tt.probe(key).map_or(NULL_MOVE, |entry| entry.best_move()),
+            (mv, _) => mv,
+        };
+        // The reduced search used the move list of this ply, so it is generated again.
+        generate_moves_into(board, &mut ctx.move_lists[ply]);
+        hash_move_legal = ctx.move_lists[ply].contains(hash_move);
+    }
+    if hash_move_legal {
+        ctx.move_lists[ply].move_to_front(hash_move);
+    }
+
+    // Near the leaves, the moves after the hash move are ordered best first
+    // by cheap threat features of the position each leads to.
+    if ctx.shallow_ordering && depth <= SHALLOW_ORDERING_MAX_DEPTH {
+        let move_list = &mut ctx.move_lists[ply];
+        for index in 0..move_list.len() {
+            let mv = move_list.get(index);
+            move_list.set_score(index, threat_score(board, play_move(board, mv, side)));
+        }
+        move_list.sort_from(hash_move_legal as usize);
+    }
+
+    // Branch immediately if there are no moves, as the game is then over.
+    let move_count = ctx.move_lists[ply].len();
+    if move_count > 0 {
+        // Remember the window on entry, to determine the bound of the result.
+        let alpha_orig = alpha;
+
+        // Initialise PV array that will be updated over iterations.
+        let mut pv = [NULL_MOVE; MAX_PLY];
+
+        // The best score found so far, which may be outside the window.
+        let mut best = Eval::MIN;
+
+        // Root scores shifted by contempt penalties, or found among only some of the moves,
+        // are not those of the position, so they are kept out of the table, where later
+        // searches would meet them.
+        let store =
+            ply > 0 || (ctx.root_penalties.is_empty() && ctx.allowed_root_moves.is_none());
+
+        // The position of each move in the move list is also used for statistics.
+        for index in 0..move_count {
+            // Deeper nodes use the lists of later plies, so this one is left intact.
+            let mv = ctx.move_lists[ply].get(index);
+            if ply == 0 {
+                if let Some(allowed) = &ctx.allowed_root_moves {
+                    if !allowed.contains(&mv) {
+                        continue;
+                    }
+                }
+                if let Some(observer) = ctx.observer {
+                    observer.on_root_move(depth, mv, index + 1);
+                }
+                if let Some(stats) = &mut ctx.stats {
+                    stats.enter_root_move(mv);
+                }
+            }
+            let nodes_before = ctx.nodes;
+
+            // A penalised root move is searched with the window shifted by its penalty,
+            // so that the bounds of the child's score hold for the penalised score.
+            let penalty = match ply {
+                0 => ctx.root_penalties.iter().find(|&&(m, _)| m == mv).map_or(0, |&(_, p)| p),
+                _ => 0,
+            };
+
+            // Recursive alpha-beta call
+            let (mut eval, mut line) = alpha_beta(
+                play_move(board, mv, side),
+                !side,
+                depth - 1,
+                -beta - penalty,
+                -alpha - penalty,
+                ctx,
+            );
+
+            // The child result is incomplete, so propagate the stop upwards.
+            if ctx.stopped {
+                return (OUTCOME_DRAW, [NULL_MOVE; MAX_PLY]);
+            }
+
+            if ply == 0 {
+                if let Some(stats) = &mut ctx.stats {
+                    stats.root_moves[stats.current_root].nodes += ctx.nodes - nodes_before;
+                }
+            }
+
+            // Take the negative of the evaluation to adjust for our current side.
+            eval = -eval - penalty;
+
+            // Record this move in the line.
+            line[ply] = mv;
+
+            if eval >= beta {
+                if let Some(stats) = &mut ctx.stats {
+                    stats.cutoffs[index] += 1;
+                    // Cutoffs at the root itself belong to no root move's subtree,
+                    // nor do those of searches started below the root.
+                    let root = stats.root_moves.get_mut(stats.current_root);
+                    if let (true, Some(root)) = (ply > 0, root) {
+                        root.cutoffs += 1;
+                        root.cutoff_index_total += index as u64;
+                    }
+                }
+                if let (true, Some(tt)) = (store, &mut ctx.tt) {
+                    tt.store(key, depth, score_to_tt(eval, ply), Bound::Lower, mv);
+                }
+                // Fail-soft beta cutoff, returning the score that caused it
+                // rather than `beta`, as it is a tighter lower bound.
+                return (eval, line);
+            }
+            if eval > best {
+                best = eval;
+                if eval > alpha {
+                    // New best move found. Update PV.
+                    alpha = eval;
+                    pv = line;
+                }
+            }
+        }
+
+        if let (true, Some(tt)) = (store, &mut ctx.tt) {
+            if best > alpha_orig {
+                tt.store(key, depth, score_to_tt(best, ply), Bound::Exact, pv[ply]);
+            } else {
+                tt.store(key, depth, score_to_tt(best, ply), Bound::Upper, NULL_MOVE);
+            }
+        }
+
+        // implicit return
+        // When every move fails low, `best` is an upper bound on the true score.
+        (best, pv)
+    } else {
+        // This position has no legal moves, and thus the game is over.
+
+        // We need only to check the evaluation of the large grid.
+        let eval = toggle_eval(side, ctx.tables.large(TableIndex::large_grid(board)));
+
+        // If the outcome is decisive (win or lose), we scale it inwards
+        // by the number of plies it will take to reach the conclusion.
+        let adjusted_eval = match eval {
+            OUTCOME_WIN => eval - ply as i32,
+            OUTCOME_LOSS => eval + ply as i32,
+            _ => OUTCOME_DRAW,
+        };
+
+        (adjusted_eval, [NULL_MOVE; MAX_PLY])
+        // The above implicit returns.
+    }
+}
+
+// The outcome of the deepest fully completed iteration of a search.
+pub struct SearchResult {
+    pub depth: usize,
+    pub seldepth: usize,
+    pub eval: Eval,
+    pub pv: [Move; MAX_PLY],
+    // How much the score has moved between the last few iterations (see `volatility`).
+    pub uncertainty: Eval,
+}
+
+// Number of most recent iterations whose scores determine the uncertainty.
+const UNCERTAINTY_ITERATIONS: usize = 4;
+
+// Scores are clamped to this magnitude when measuring their volatility,
+// so that a newly found forced win counts as a large change, but not a vast one.
+const UNCERTAINTY_CLAMP: Eval = 1000;
+
+/**
+ * An estimate of how unreliable the latest of a sequence of iteration scores is:
+ * the average absolute change between successive scores over the last few iterations.
+ * Scores that swing with the parity of the depth, or keep drifting as the
+ * search deepens, suggest that deeper searches may change the score further.
+ */
+pub fn volatility(evals: &[Eval]) -> Eval {
+    let recent = &evals[evals.len().saturating_sub(UNCERTAINTY_ITERATIONS)..];
+    if recent.len() < 2 {
+        return 0;
+    }
+    let clamped = |eval: Eval| eval.clamp(-UNCERTAINTY_CLAMP, UNCERTAINTY_CLAMP);
+    let total: Eval = recent
+        .windows(2)
+        .map(|pair| (clamped(pair[1]) - clamped(pair[0])).abs())
+        .sum();
+    total / (recent.len() - 1) as Eval
+}
+
+/**
+ * Searches to increasing depths up to `depth`, keeping the result of the
+ * deepest iteration that was not interrupted by the context's stop condition.
+ * This allows an aborted search to still return a usable partial result.
+ * Returns `None` only if the search was stopped before depth 1 completed.
+ */
+pub fn iterative_deepening(
+    board: Board,
+    side: bool,
+    depth: usize,
+    ctx: &mut SearchContext,
+) -> Option<SearchResult> {
+    let (timer, nodes_at_start) = (Timer::start(), ctx.nodes);
+    let mut result: Option<SearchResult> = None;
+    let mut evals = Vec::with_capacity(depth);
+    ctx.previous_best = NULL_MOVE;
+    for d in 1..=depth {
+        ctx.max_depth = d;
+        ctx.seldepth = 0;
+        let nodes_before = ctx.nodes;
+        let (eval, pv) = alpha_beta(board, side, d, -INFINITY, INFINITY, ctx);
+        if ctx.stopped {
+            break;
+        }
+        if let Some(stats) = &mut ctx.stats {
+            stats.iteration_nodes.push(ctx.nodes - nodes_before);
+        }
+        evals.push(eval);
+        ctx.previous_best = pv[0];
+        let completed = SearchResult {
+            depth: d,
+            seldepth: ctx.seldepth,
+            eval,
+            pv,
+            uncertainty: volatility(&evals),
+        };
+        if let Some(observer) = ctx.observer {
+            observer.on_depth_complete(&completed, ctx.nodes - nodes_at_start);
+        }
+        result = Some(completed);
+    }
+    ctx.previous_best = NULL_MOVE;
+    if let Some(observer) = ctx.observer {
+        observer.on_finish(result.as_ref(), ctx.nodes - nodes_at_start);
+    }
+    if let (true, Some(result)) = (log::enabled(), &result) {
+        log::log(&LogRecord::Search {
+            hash: position_hash(board, side),
+            depth: result.depth,
+            seldepth: result.seldepth,
+            score: result.eval,
+            nodes: ctx.nodes - nodes_at_start,
+            time_ms: timer.elapsed_ms(),
+            pv: pv_strings(&result.pv),
+        });
+    }
+    result
+}
+
+/**
+ * Searches every root move with a full window to `depth` plies in total,
+ * returning each move with its exact score relative to the side to move,
+ * best first (ties keep move generation order).
+ * This gives up pruning between root moves, so it is only used where the scores
+ * of moves other than the best are needed, such as for annotating a game.
+ * Returns `None` if the search is stopped, or if `depth` is zero.
+ */
+pub fn root_move_scores(
+    board: Board,
+    side: bool,
+    depth: usize,
+    ctx: &mut SearchContext,
+) -> Option<Vec<(Move, Eval)>> {
+    let lines = root_move_lines(board, side, depth, ctx)?;
+    Some(lines.into_iter().map(|(mv, eval, _)| (mv, eval)).collect())
+}
+
+// As `root_move_scores`, also giving the principal variation of each move, starting with it.
+pub fn root_move_lines(
+    board: Board,
+    side: bool,
+    depth: usize,
+    ctx: &mut SearchContext,
+) -> Option<Vec<(Move, Eval, [Move; MAX_PLY])>> {
+    if depth == 0 {
+        return None;
+    }
+    // With the maximum depth set to that of the root,
+    // decisive scores in the children are adjusted relative to the root.
+    ctx.max_depth = depth;
+    ctx.seldepth = 0;
+    let mut lines = Vec::new();
+    for mv in generate_moves(board) {
+        let (eval, mut line) = alpha_beta(
+            play_move(board, mv, side),
+            !side,
+            depth - 1,
+            -INFINITY,
+            INFINITY,
+            ctx,
+        );
+        if ctx.stopped {
+            return None;
+        }
+        line[0] = mv;
+        lines.push((mv, -eval, line));
+    }
+    lines.sort_by_key(|&(_, eval, _)| std::cmp::Reverse(eval));
+    Some(lines)
+}
+
+// Whether a score relative to the side to move is a forced win.
+pub fn is_forced_win(eval: Eval) -> bool {
+    eval >= OUTCOME_WIN - DECISIVE_MARGIN
+}
+
+/**
+ * The root move which wins by force but takes the longest to do so against the best
+ * defence, with its score, for drawing out a won game ("troll mode") instead of ending
+ * it as soon as possible. Every root move is searched with a full window, so this is
+ * only worth calling once a search has found a forced win. Returns `None` if no move
+ * wins by force within `depth`, or if the search is stopped.
+ */
+pub fn longest_win(
+    board: Board,
+    side: bool,
+    depth: usize,
+    ctx: &mut SearchContext,
+) -> Option<(Move, Eval)> {
+    root_move_scores(board, side, depth, ctx)?
+        .into_iter()
+        .filter(|&(_, eval)| is_forced_win(eval))
+        .min_by_key(|&(_, eval)| eval)
+}
@@ -0,0 +1,371 @@
+#[cfg(feature = "solver")]
+use crate::solver::MateSearch;
+use crate::{
+    adjudication::{Adjudication, AdjudicationRules, Adjudicator},
+    board_string,
+    engine::{generate_moves, Board, Eval, EvalParams, Move, MAX_PLY, OUTCOME_WIN},
+    game::{Game, GameResult},
+    log::{self, position_hash, LogRecord},
+    move_string,
+    rng::Rng,
+    search::{iterative_deepening, SearchContext},
+    symmetry::symmetric_variants,
+    timeman::{blitz_move, BlitzSource, TimeControl},
+    timer::Timer,
+    tt::TranspositionTable,
+    zobrist::hash_position,
+};
+
+// How a game between two engines came to an end.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Termination {
+    // A player won the large grid, or no moves were left.
+    Finished,
+    Resignation,
+    DrawAdjudication,
+    // The solver proved a forced win for the side to move.
+    ForcedWin,
+    // A player on a clock used up its remaining time.
+    TimeForfeit,
+}
+
+impl Termination {
+    pub fn name(self) -> &'static str {
+        match self {
+            Termination::Finished => "finished",
+            Termination::Resignation => "resignation",
+            Termination::DrawAdjudication => "draw-adjudication",
+            Termination::ForcedWin => "forced-win",
+            Termination::TimeForfeit => "time-forfeit",
+        }
+    }
+}
+
+// The settings of one engine in a game, each with its own transposition table.
+// The evaluation weights applied at the leaves are taken from `eval_params`, while those
+// built into the lookup tables come from the tables the game is played with.
+// With a `clock`, moves are searched for as long as it allows, as in `go_clock`,
+// instead of to `depth`, and a player whose time runs out loses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlayerSettings {
+    pub depth: usize,
+    pub hash_mb: usize,
+    pub eval_params: EvalParams,
+    pub clock: Option<TimeControl>,
+}
+
+impl PlayerSettings {
+    pub fn new(depth: usize, hash_mb: usize) -> Self {
+        PlayerSettings {
+            depth,
+            hash_mb,
+            eval_params: EvalParams::DEFAULT,
+            clock: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SelfPlayGame {
+    pub game: Game,
+    pub result: GameResult,
+    pub termination: Termination,
+    // The score of the search for each move played after the opening,
+    // relative to the side that played it.
+    pub evals: Vec<Eval>,
+}
+
+// The settings of a game between two engines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatchSettings {
+    // The settings of X and O respectively.
+    pub players: [PlayerSettings; 2],
+    // When games are resigned or drawn early, if at all.
+    pub rules: Option<AdjudicationRules>,
+    // Games are won as soon as the solver proves a forced win for the side to move
+    // within this many plies. Zero disables this, as does building without `solver`.
+    pub solver_plies: usize,
+}
+
+// The move, score and depth of a search.
+struct MoveChoice {
+    mv: Move,
+    eval: Eval,
+    depth: usize,
+}
+
+/**
+ * Chooses a move for a player on a clock, searching until the time for the move
+ * is used up, or cheaply in blitz mode (see `blitz_move`) when little time remains.
+ * A move chosen without a search is given the score stored for the position,
+ * if any, so that the adjudicator is not misled.
+ */
+fn clocked_move(
+    board: Board,
+    side: bool,
+    clock: TimeControl,
+    tables: &(Vec<Eval>, Vec<Eval>),
+    tt: &mut TranspositionTable,
+    eval_params: EvalParams,
+) -> MoveChoice {
+    let timer = Timer::start();
+    let move_time = clock.move_time_ms();
+    let is_over = || timer.elapsed_ms() >= move_time;
+    let mut ctx = SearchContext::new(tables, MAX_PLY);
+    ctx.tt = Some(tt);
+    ctx.eval_params = eval_params;
+    ctx.stop_condition = Some(&is_over);
+    if !clock.is_low() {
+        if let Some(result) = iterative_deepening(board, side, MAX_PLY, &mut ctx) {
+            return MoveChoice {
+                mv: result.pv[0],
+                eval: result.eval,
+                depth: result.depth,
+            };
+        }
+    }
+    let choice = blitz_move(board, side, None, &mut ctx).expect("the game is not over");
+    if let Some(result) = choice.result {
+        return MoveChoice {
+            mv: choice.mv,
+            eval: result.eval,
+            depth: result.depth,
+        };
+    }
+    let stored = ctx.tt.as_ref().and_then(|tt| tt.probe(hash_position(board, side)));
+    let eval = match (choice.source, stored) {
+        (BlitzSource::Win, _) => OUTCOME_WIN - 1,
+        (_, Some(entry)) => entry.score,
+        (_, None) => 0,
+    };
+    MoveChoice {
+        mv: choice.mv,
+        eval,
+        depth: 0,
+    }
+}
+
+/**
+ * Plays a game between two engines, continuing from the moves of `opening`.
+ * Each move is chosen by a search to the player's depth, or for as long as its clock
+ * allows (see `clocked_move`), and its score is passed
+ * to the adjudicator, if any, which may end the game early by resignation or as a draw.
+ * Before each move, the solver may end the game early once its outcome is proven,
+ * which saves playing out long won endings when many games are needed for tuning.
+ */
+pub fn play_game(
+    opening: &Game,
+    settings: &MatchSettings,
+    tables: &(Vec<Eval>, Vec<Eval>),
+) -> SelfPlayGame {
+    play_game_with_tables(opening, settings, [tables, tables])
+}
+
+// Plays a game as `play_game` does, with X and O each searching with their own tables.
+pub fn play_game_with_tables(
+    opening: &Game,
+    settings: &MatchSettings,
+    tables: [&(Vec<Eval>, Vec<Eval>); 2],
+) -> SelfPlayGame {
+    let mut game = opening.clone();
+    let mut tts = settings.players.map(|player| TranspositionTable::new(player.hash_mb));
+    let mut clocks = settings.players.map(|player| player.clock);
+    let mut adjudicator = settings.rules.map(Adjudicator::new);
+    let mut evals = Vec::new();
+    loop {
+        if let Some(result) = game.result() {
+            return SelfPlayGame {
+                game,
+                result,
+                termination: Termination::Finished,
+                evals,
+            };
+        }
+        let side = game.side_to_move();
+
+        #[cfg(feature = "solver")]
+        if settings.solver_plies > 0
+            && MateSearch::new()
+                .distance(game.board(), side, settings.solver_plies)
+                .is_some()
+        {
+            return SelfPlayGame {
+                game,
+                result: if side { GameResult::OWins } else { GameResult::XWins },
+                termination: Termination::ForcedWin,
+                evals,
+            };
+        }
+
+        let player = settings.players[side as usize];
+        let tt = &mut tts[side as usize];
+        tt.new_search();
+        let table = tables[side as usize];
+        let timer = Timer::start();
+        let choice = match clocks[side as usize] {
+            Some(clock) => clocked_move(game.board(), side, clock, table, tt, player.eval_params),
+            None => {
+                let mut ctx = SearchContext::new(table, player.depth);
+                ctx.tt = Some(tt);
+                ctx.eval_params = player.eval_params;
+                let result = iterative_deepening(game.board(), side, player.depth, &mut ctx)
+                    .expect("search without a stop condition completes");
+                MoveChoice {
+                    mv: result.pv[0],
+                    eval: result.eval,
+                    depth: result.depth,
+                }
+            }
+        };
+        if let Some(clock) = &mut clocks[side as usize] {
+            let elapsed = timer.elapsed_ms();
+            if elapsed >= clock.remaining_ms {
+                return SelfPlayGame {
+                    game,
+                    result: if side { GameResult::XWins } else { GameResult::OWins },
+                    termination: Termination::TimeForfeit,
+                    evals,
+                };
+            }
+            clock.remaining_ms = clock.remaining_ms - elapsed + clock.increment_ms;
+        }
+        if log::enabled() {
+            log::log(&LogRecord::Move {
+                hash: position_hash(game.board(), side),
+                ply: game.moves().len(),
+                side: if side { "o" } else { "x" },
+                mv: move_string(choice.mv),
+                score: choice.eval,
+                depth: choice.depth,
+            });
+        }
+        game.play(choice.mv).expect("search returns a legal move");
+        evals.push(choice.eval);
+
+        let adjudication = adjudicator
+            .as_mut()
+            .and_then(|adjudicator| adjudicator.record_game(&game, choice.eval));
+        if let Some(adjudication) = adjudication {
+            return SelfPlayGame {
+                game,
+                result: adjudication.result(),
+                termination: match adjudication {
+                    Adjudication::Resign(_) => Termination::Resignation,
+                    Adjudication::Draw => Termination::DrawAdjudication,
+                },
+                evals,
+            };
+        }
+    }
+}
+
+// Plays a random opening of `plies` moves, stopping early if the game ends.
+pub fn random_opening(rng: &mut Rng, plies: usize) -> Game {
+    let mut game = Game::new();
+    for _ in 0..plies {
+        let moves: Vec<_> = generate_moves(game.board()).collect();
+        if moves.is_empty() || game.result().is_some() {
+            break;
+        }
+        let mv = moves[(rng.next_u64() % moves.len() as u64) as usize];
+        game.play(mv).expect("generated moves are legal");
+    }
+    game
+}
+
+/**
+ * The score of engine A against engine B, counting a draw as half a win, over `pairs` pairs
+ * of games. Each pair is played from a random opening of `opening_plies` moves, with the
+ * engines swapping sides, and `rules` and `solver_plies` are as in `MatchSettings`.
+ * Each engine searches with its own tables.
+ */
+pub fn match_score(
+    players: [PlayerSettings; 2],
+    tables: [&(Vec<Eval>, Vec<Eval>); 2],
+    pairs: usize,
+    opening_plies: usize,
+    rules: Option<AdjudicationRules>,
+    solver_plies: usize,
+    rng: &mut Rng,
+) -> f64 {
+    let pairs = pairs.max(1);
+    let mut points = 0.0;
+    for _ in 0..pairs {
+        let opening = random_opening(rng, opening_plies);
+        // Engine A plays X in the first game of the pair, and O in the second.
+        for (x, o) in [(0, 1), (1, 0)] {
+            let settings = MatchSettings {
+                players: [players[x], players[o]],
+                rules,
+                solver_plies,
+            };
+            let game = play_game_with_tables(&opening, &settings, [tables[x], tables[o]]);
+            points += match (game.result, x == 0) {
+                (GameResult::Draw, _) => 0.5,
+                (GameResult::XWins, true) | (GameResult::OWins, false) => 1.0,
+                _ => 0.0,
+            };
+        }
+    }
+    points / (2 * pairs) as f64
+}
+
+// A position from a self-play game, labelled for training an evaluation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrainingSample {
+    pub board: Board,
+    // The side to move, `false` for X.
+    pub side: bool,
+    // The move played, and the score of the search that chose it, relative to the side to move.
+    pub mv: Move,
+    pub eval: Eval,
+    // The result of the game for the side to move: 1 for a win, 0 for a draw, -1 for a loss.
+    pub outcome: i8,
+}
+
+/**
+ * The positions of a game in which a move was searched, that is every position after
+ * the opening, each labelled with its search score and the result of the game.
+ * With `augment`, each is given in all eight of its symmetric variants, with the move
+ * transformed to match (see `symmetric_variants`), for eight times as many samples.
+ */
+pub fn training_samples(game: &SelfPlayGame, augment: bool) -> Vec<TrainingSample> {
+    let moves = game.game.moves();
+    let opening_plies = moves.len() - game.evals.len();
+    let mut samples = Vec::new();
+    for (ply, &eval) in (opening_plies..).zip(&game.evals) {
+        let side = ply % 2 == 1;
+        let outcome = match (game.result, side) {
+            (GameResult::Draw, _) => 0,
+            (GameResult::XWins, false) | (GameResult::OWins, true) => 1,
+            _ => -1,
+        };
+        let board = game.game.board_at(ply).unwrap();
+        let variants = match augment {
+            true => symmetric_variants(board, moves[ply]).to_vec(),
+            false => vec![(board, moves[ply])],
+        };
+        for (board, mv) in variants {
+            samples.push(TrainingSample {
+                board,
+                side,
+                mv,
+                eval,
+                outcome,
+            });
+        }
+    }
+    samples
+}
+
+// Writes a sample as a line of the form `<board> <x|o> <move> <eval> <outcome>`.
+pub fn training_line(sample: &TrainingSample) -> String {
+    format!(
+        "{} {} {} {} {}",
+        board_string(sample.board),
+        if sample.side { "o" } else { "x" },
+        move_string(sample.mv),
+        sample.eval,
+        sample.outcome,
+    )
+}
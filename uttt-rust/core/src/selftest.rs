@@ -0,0 +1,70 @@
+use crate::{
+    board_from_string,
+    engine::{perft, Eval},
+    search::{iterative_deepening, SearchContext},
+    tt::TranspositionTable,
+};
+
+// Positions used by the self-test, with the side to move (`true` for X)
+// and the perft and search depths to use.
+const SELFTEST_POSITIONS: [(&str, bool, usize, usize); 4] = [
+    ("9/9/9/9/9/9/9/9/9 any", true, 4, 6),
+    ("x8/9/9/9/1o7/9/9/9/9 c", true, 4, 6),
+    ("xxo6/9/9/1o7/9/9/9/9/9 nw", true, 4, 6),
+    ("xox1x1o2/1o1x5/o1x1o2x1/9/4x4/2o6/9/9/9 any", true, 3, 6),
+];
+
+// Size of the transposition table used by each search, in megabytes.
+const SELFTEST_HASH_MB: usize = 1;
+
+// Accumulates bytes into a 64-bit FNV-1a hash.
+struct Digest(u64);
+
+impl Digest {
+    fn new() -> Self {
+        Digest(0xcbf29ce484222325)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+/**
+ * A deterministic self-test, which runs a fixed set of perfts and searches
+ * and reduces all of their results (and the evaluation tables) to a digest.
+ * Builds that behave identically produce the same digest, so comparing the
+ * digest of a wasm build with that of a native build catches miscompiles
+ * and table generation drift.
+ * Each search uses its own empty transposition table, so that the result
+ * does not depend on anything searched beforehand.
+ */
+pub fn selftest(tables: &(Vec<Eval>, Vec<Eval>)) -> u64 {
+    let mut digest = Digest::new();
+
+    for eval in tables.0.iter().chain(tables.1.iter()) {
+        digest.update(&eval.to_le_bytes());
+    }
+
+    for (board_string, x_to_move, perft_depth, search_depth) in SELFTEST_POSITIONS {
+        let board = board_from_string(board_string).unwrap();
+        let side = !x_to_move;
+
+        digest.update(&perft(board, side, perft_depth).to_le_bytes());
+
+        let mut tt = TranspositionTable::new(SELFTEST_HASH_MB);
+        let mut ctx = SearchContext::new(tables, search_depth);
+        ctx.tt = Some(&mut tt);
+        let result = iterative_deepening(board, side, search_depth, &mut ctx).unwrap();
+        digest.update(&result.eval.to_le_bytes());
+        for mv in result.pv {
+            digest.update(&mv.to_le_bytes());
+        }
+        digest.update(&ctx.nodes.to_le_bytes());
+    }
+
+    digest.0
+}
@@ -0,0 +1,146 @@
+use crate::{
+    engine::{generate_moves, line_presence, play_move, Board, Move},
+    search::STOP_CHECK_INTERVAL,
+    tablebase::empty_cells,
+};
+
+/**
+ * Search for forced wins only, as used to verify puzzles.
+ * Unlike the main search, there is no evaluation: a line is only followed
+ * while the attacker can still complete the large grid within the remaining plies,
+ * so the tree is far smaller than that of a full-width search to the same depth.
+ * Distances are in plies, counting the winning move, so they are always odd.
+ */
+pub struct MateSearch<'a> {
+    pub nodes: u64,
+    pub stopped: bool,
+    // Polled periodically during the search. Returning `true` aborts it.
+    pub stop_condition: Option<&'a dyn Fn() -> bool>,
+}
+
+// The most empty cells of a position whose draw is proven when its score is reported.
+// Proofs take a few milliseconds at this size, but grow several times slower with each
+// further cell.
+pub const DRAW_PROOF_PLIES: usize = 14;
+
+// Whether `side` has completed a line of the large grid.
+#[inline]
+fn has_won(board: Board, side: bool) -> bool {
+    line_presence(board.2 >> if side { 45 } else { 36 })
+}
+
+impl<'a> MateSearch<'a> {
+    pub fn new() -> Self {
+        MateSearch {
+            nodes: 0,
+            stopped: false,
+            stop_condition: None,
+        }
+    }
+
+    // Counts a visited node, and checks whether the search should be aborted.
+    #[inline]
+    fn visit(&mut self) -> bool {
+        self.nodes += 1;
+        if !self.stopped && self.nodes & (STOP_CHECK_INTERVAL - 1) == 0 {
+            if let Some(stop_condition) = self.stop_condition {
+                self.stopped = stop_condition();
+            }
+        }
+        self.stopped
+    }
+
+    // Returns a move with which `side` (to move) wins within `plies` plies
+    // against any defence, if there is one.
+    fn winning_move(&mut self, board: Board, side: bool, plies: usize) -> Option<Move> {
+        if self.visit() {
+            return None;
+        }
+        let moves: Vec<Move> = generate_moves(board).collect();
+        if let Some(&mv) = moves.iter().find(|&&mv| has_won(play_move(board, mv, side), side)) {
+            return Some(mv);
+        }
+        if plies < 3 {
+            return None;
+        }
+        moves.into_iter().find(|&mv| {
+            let next = play_move(board, mv, side);
+            let mut replies = generate_moves(next).peekable();
+            // A position with no replies is drawn, not won.
+            replies.peek().is_some()
+                && replies.all(|reply| {
+                    self.winning_move(play_move(next, reply, !side), side, plies - 2)
+                        .is_some()
+                })
+        })
+    }
+
+    // Returns the shortest distance within which `side` (to move) can force a win,
+    // if it is at most `plies`.
+    pub fn distance(&mut self, board: Board, side: bool, plies: usize) -> Option<usize> {
+        (1..=plies)
+            .step_by(2)
+            .find(|&n| self.stopped || self.winning_move(board, side, n).is_some())
+            .filter(|_| !self.stopped)
+    }
+
+    /**
+     * Proves that a position with `side` (to move) is drawn, by showing that neither side
+     * can force a win before the game is over. A game cannot outlast the empty cells of its
+     * open zones, so only positions with at most `plies` of them are tried. Returns their
+     * number, the most plies the game can last, or `None` if the position is not drawn,
+     * has too many empty cells, or the search was stopped.
+     */
+    pub fn draw_distance(&mut self, board: Board, side: bool, plies: usize) -> Option<usize> {
+        let remaining = empty_cells(board) as usize;
+        if remaining > plies || has_won(board, side) || has_won(board, !side) {
+            return None;
+        }
+        let moves: Vec<Move> = generate_moves(board).collect();
+        // The side to move survives with a move after which the other side cannot force a win.
+        let survives = moves.is_empty()
+            || moves.iter().any(|&mv| {
+                self.winning_move(play_move(board, mv, side), !side, remaining - 1).is_none()
+            });
+        let drawn = survives && self.distance(board, side, remaining).is_none();
+        Some(remaining).filter(|_| drawn && !self.stopped)
+    }
+
+    /**
+     * Finds a forced win for `side` (to move) within `plies` plies.
+     * Returns the winning line, in which the attacker wins as quickly as possible
+     * and the defender delays the loss as long as possible,
+     * or `None` if there is no such win or the search was stopped.
+     */
+    pub fn solve(&mut self, board: Board, side: bool, plies: usize) -> Option<Vec<Move>> {
+        let mut distance = self.distance(board, side, plies)?;
+        let mut board = board;
+        let mut line = Vec::with_capacity(distance);
+        loop {
+            let mv = self.winning_move(board, side, distance)?;
+            line.push(mv);
+            board = play_move(board, mv, side);
+            if has_won(board, side) {
+                return Some(line);
+            }
+            // Every reply loses within the remaining plies, so pick the slowest.
+            let mut longest = None;
+            for reply in generate_moves(board) {
+                let reply_distance = self.distance(play_move(board, reply, !side), side, distance - 2)?;
+                if longest.is_none_or(|(_, d)| reply_distance > d) {
+                    longest = Some((reply, reply_distance));
+                }
+            }
+            let (reply, reply_distance) = longest?;
+            line.push(reply);
+            board = play_move(board, reply, !side);
+            distance = reply_distance;
+        }
+    }
+}
+
+impl Default for MateSearch<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
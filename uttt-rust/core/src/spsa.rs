@@ -0,0 +1,180 @@
+use crate::{
+    adjudication::AdjudicationRules,
+    engine::{init_with, Eval, EvalParams},
+    rng::Rng,
+    selfplay::{match_score, PlayerSettings},
+    timeman::{TimeControl, TimeParams},
+};
+
+/*
+ * Tuning of the engine's parameters by simultaneous perturbation stochastic approximation
+ * (SPSA), from matches between two copies of the engine. Each iteration moves every
+ * parameter being tuned a random step up or down at once, giving one engine the parameters
+ * plus the steps and the other the parameters minus them, and plays a few pairs of games
+ * between the two. The parameters are then moved towards the engine that scored better,
+ * in proportion to its margin. A handful of games says little, so each iteration is only
+ * a noisy estimate, but the steps and learning rate shrink as the iterations go on,
+ * and over some hundreds of them the parameters settle.
+ *
+ * The parameters are the evaluation weights of `EvalParams`, and the time management
+ * factors of `TimeParams`. Those weights built into the lookup tables are tuned alike,
+ * with new tables built for each iteration. The time factors only matter in games on
+ * a clock, so they can only be tuned when the players are given one. The search has no
+ * margins of its own to tune yet, as it prunes only by transposition table cutoffs.
+ */
+
+// The exponents by which the learning rate and the steps shrink, as recommended by Spall.
+const LEARNING_RATE_DECAY: f64 = 0.602;
+const STEP_DECAY: f64 = 0.101;
+
+pub struct SpsaSettings {
+    pub iterations: usize,
+    // The pairs of games played in each iteration. Each pair is played from a random
+    // opening of `opening_plies` moves, with the engines swapping sides.
+    pub pairs: usize,
+    pub opening_plies: usize,
+    // The depth, hash and clock of both engines, and the evaluation weights tuning
+    // starts from. The time factors start from those of the clock, if any.
+    pub player: PlayerSettings,
+    pub rules: Option<AdjudicationRules>,
+    pub solver_plies: usize,
+    // The first step of each parameter, as a multiple of its scale (see `scale`),
+    // and the first learning rate.
+    pub step: f64,
+    pub learning_rate: f64,
+}
+
+// The parameters which can be tuned, by name, from the evaluation weights and the time factors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TunedParams {
+    pub eval: EvalParams,
+    pub time: TimeParams,
+}
+
+impl TunedParams {
+    // The name and value of each parameter, the evaluation weights first.
+    pub fn values(&self) -> Vec<(&'static str, i64)> {
+        let eval = self.eval.values().into_iter().map(|(name, value)| (name, value as i64));
+        let time = self.time.values().into_iter().map(|(name, value)| (name, value as i64));
+        eval.chain(time).collect()
+    }
+
+    // Sets a parameter by name, returning an error if there is no parameter of that name.
+    // Time factors are kept from going negative.
+    pub fn set(&mut self, name: &str, value: i64) -> Result<(), &'static str> {
+        match self.time.values().iter().any(|&(n, _)| n == name) {
+            true => self.time.set(name, value.max(0) as u64),
+            false => self.eval.set(name, value as Eval),
+        }
+    }
+}
+
+// The outcome of one iteration of tuning.
+pub struct SpsaIteration {
+    pub iteration: usize,
+    // The score of the engine given the parameters plus the steps,
+    // counting a draw as half a win.
+    pub score: f64,
+    // The parameters after the iteration, rounded.
+    pub params: TunedParams,
+}
+
+// The size of changes to a parameter, in proportion to its starting value,
+// so that large and small weights move alike, but never less than a couple of units.
+fn scale(value: i64) -> f64 {
+    (value.abs() as f64 / 5.0).max(2.0)
+}
+
+// The score of the engine with parameters `plus` in games against that with `minus`.
+fn plus_score(
+    settings: &SpsaSettings,
+    plus: TunedParams,
+    minus: TunedParams,
+    rng: &mut Rng,
+) -> f64 {
+    let tables = [init_with(&plus.eval), init_with(&minus.eval)];
+    let players = [plus, minus].map(|params| PlayerSettings {
+        eval_params: params.eval,
+        clock: settings.player.clock.map(|clock| TimeControl {
+            params: params.time,
+            ..clock
+        }),
+        ..settings.player
+    });
+    match_score(
+        players,
+        [&tables[0], &tables[1]],
+        settings.pairs,
+        settings.opening_plies,
+        settings.rules,
+        settings.solver_plies,
+        rng,
+    )
+}
+
+/**
+ * Tunes the parameters with the given names, starting from those of `settings.player`,
+ * and returns the tuned parameters, or an error if a name is unknown, or names a time
+ * factor when the players have no clock. The other parameters are left as they are.
+ * `on_iteration` is called after every iteration, so that progress can be followed
+ * during a long run.
+ */
+pub fn tune(
+    settings: &SpsaSettings,
+    names: &[&str],
+    rng: &mut Rng,
+    on_iteration: &mut dyn FnMut(&SpsaIteration),
+) -> Result<TunedParams, &'static str> {
+    let start = TunedParams {
+        eval: settings.player.eval_params,
+        time: settings.player.clock.map_or(TimeParams::DEFAULT, |clock| clock.params),
+    };
+    let values = start.values();
+    let timed = |name: &str| TimeParams::DEFAULT.values().iter().any(|&(n, _)| n == name);
+    // The name, unrounded value and scale of each parameter tuned.
+    let mut params = names
+        .iter()
+        .map(|name| match values.iter().find(|(n, _)| n == name) {
+            Some(_) if settings.player.clock.is_none() && timed(name) => {
+                Err("time parameters need a clock")
+            }
+            Some(&(name, value)) => Ok((name, value as f64, scale(value))),
+            None => Err("parameter unknown"),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The learning rate shrinks more slowly over the first tenth of the iterations,
+    // so that the early, larger steps do not throw the parameters far off.
+    let offset = settings.iterations as f64 / 10.0;
+    let mut current = start;
+    for iteration in 0..settings.iterations {
+        let k = iteration as f64 + 1.0;
+        let step = settings.step / k.powf(STEP_DECAY);
+        let learning_rate = settings.learning_rate / (k + offset).powf(LEARNING_RATE_DECAY);
+
+        let (mut plus, mut minus) = (current, current);
+        let mut steps = Vec::with_capacity(params.len());
+        for &(name, value, scale) in &params {
+            let sign = if rng.next_u64() & 1 == 0 { 1.0 } else { -1.0 };
+            let size = sign * (step * scale).round().max(1.0);
+            plus.set(name, (value + size).round() as i64)?;
+            minus.set(name, (value - size).round() as i64)?;
+            steps.push(size);
+        }
+
+        // The difference in score between the two engines, from -1 to 1, estimates
+        // the gradient along the steps taken.
+        let score = plus_score(settings, plus, minus, rng);
+        let difference = 2.0 * score - 1.0;
+        for ((name, value, scale), size) in params.iter_mut().zip(&steps) {
+            *value += learning_rate * *scale * *scale * difference / (2.0 * size);
+            current.set(name, value.round() as i64)?;
+        }
+        on_iteration(&SpsaIteration {
+            iteration: iteration + 1,
+            score,
+            params: current,
+        });
+    }
+    Ok(current)
+}
@@ -0,0 +1,81 @@
+use crate::{
+    engine::Move,
+    game::Game,
+    versioning::{migrate_bytes, Format},
+};
+
+/*
+ * Games are shared in links as a short token in the URL-safe base64 alphabet
+ * (RFC 4648, without padding), so that it can appear in a query string or fragment
+ * without escaping. The token encodes bytes of which the first is the format version:
+ *
+ *     version 1: the moves of the game from the empty board, one byte each (0-80).
+ *
+ * The position and side to move follow from the moves, as every game starts
+ * from the empty board. Tokens of earlier versions are migrated as described
+ * in `versioning`, so that old links keep working.
+ */
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity((bytes.len() * 4).div_ceil(3));
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (i, &byte)| group | (byte as u32) << (16 - 8 * i));
+        // A chunk of n bytes needs n + 1 characters.
+        for i in 0..=chunk.len() {
+            encoded.push(BASE64_ALPHABET[(group >> (18 - 6 * i)) as usize & 0b111111] as char);
+        }
+    }
+    encoded
+}
+
+// Returns `None` for characters outside the alphabet, padding included,
+// and for lengths which no number of bytes encodes to.
+fn base64_decode(encoded: &str) -> Option<Vec<u8>> {
+    let values = encoded
+        .bytes()
+        .map(|c| BASE64_ALPHABET.iter().position(|&a| a == c).map(|v| v as u32))
+        .collect::<Option<Vec<_>>>()?;
+    if values.len() % 4 == 1 {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(values.len() * 3 / 4);
+    for chunk in values.chunks(4) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (i, &value)| group | value << (18 - 6 * i));
+        for i in 0..chunk.len() - 1 {
+            bytes.push((group >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+// Encodes a game as a URL-safe token, in the current version of the format.
+pub fn encode_state_url(game: &Game) -> String {
+    let mut bytes = vec![Format::StateUrl.current_version()];
+    bytes.extend(game.moves().iter().map(|&mv| mv as u8));
+    base64_encode(&bytes)
+}
+
+/**
+ * Decodes a token made by `encode_state_url` in any version of the format,
+ * replaying its moves. Returns a short error description if the token is malformed,
+ * its version is unknown (such as one made by a newer release), or a move is illegal.
+ */
+pub fn decode_state_url(token: &str) -> Result<Game, &'static str> {
+    let bytes = base64_decode(token.trim()).ok_or("token invalid")?;
+    let (&version, payload) = bytes.split_first().ok_or("token invalid")?;
+    let moves = migrate_bytes(Format::StateUrl, version, payload).map_err(|e| e.description())?;
+    let mut game = Game::new();
+    for &mv in moves.iter() {
+        game.play(mv as Move)?;
+    }
+    Ok(game)
+}
@@ -0,0 +1,154 @@
+use crate::engine::{Board, Move, NULL_MOVE, ZONE_ANY};
+
+/**
+ * The eight symmetries of the board (rotations and reflections).
+ * Each maps a position within a 3x3 grid, numbered row by row from the top left,
+ * to its position after the transform. The same mapping is applied both to the
+ * zones of the large grid and to the cells within each zone.
+ */
+pub const TRANSFORM_COUNT: usize = 8;
+const TRANSFORMS: [[u64; 9]; TRANSFORM_COUNT] = [
+    // Identity.
+    [0, 1, 2, 3, 4, 5, 6, 7, 8],
+    // Rotation by 90 degrees clockwise.
+    [2, 5, 8, 1, 4, 7, 0, 3, 6],
+    // Rotation by 180 degrees.
+    [8, 7, 6, 5, 4, 3, 2, 1, 0],
+    // Rotation by 90 degrees anticlockwise.
+    [6, 3, 0, 7, 4, 1, 8, 5, 2],
+    // Reflection in the vertical axis.
+    [2, 1, 0, 5, 4, 3, 8, 7, 6],
+    // Reflection in the horizontal axis.
+    [6, 7, 8, 3, 4, 5, 0, 1, 2],
+    // Reflection in the main diagonal.
+    [0, 3, 6, 1, 4, 7, 2, 5, 8],
+    // Reflection in the anti-diagonal.
+    [8, 5, 2, 7, 4, 1, 6, 3, 0],
+];
+
+// The transform undoing each transform. Rotations by 90 degrees
+// undo each other, and every other transform is its own inverse.
+const INVERSES: [usize; TRANSFORM_COUNT] = [0, 3, 2, 1, 4, 5, 6, 7];
+
+pub fn inverse(transform: usize) -> usize {
+    INVERSES[transform]
+}
+
+// Names for the transforms, in the order above.
+pub const TRANSFORM_NAMES: [&str; TRANSFORM_COUNT] = [
+    "identity",
+    "rotate-90",
+    "rotate-180",
+    "rotate-270",
+    "reflect-vertical",
+    "reflect-horizontal",
+    "reflect-diagonal",
+    "reflect-antidiagonal",
+];
+
+// Finds a transform by its name, or by its index from `0` to `7`.
+pub fn transform_from_name(name: &str) -> Option<usize> {
+    match name.parse::<usize>() {
+        Ok(transform) if transform < TRANSFORM_COUNT => Some(transform),
+        Ok(_) => None,
+        Err(_) => TRANSFORM_NAMES.iter().position(|&n| n == name),
+    }
+}
+
+// Applies a transform to a move. The null move is left unchanged.
+pub fn transform_move(mv: Move, transform: usize) -> Move {
+    if mv >= NULL_MOVE {
+        return mv;
+    }
+    let map = &TRANSFORMS[transform];
+    9 * map[(mv / 9) as usize] + map[(mv % 9) as usize]
+}
+
+// Applies a transform to a 3x3 grid, given as a 9-bit mask of its cells.
+pub fn transform_grid(grid: u64, transform: usize) -> u64 {
+    let map = &TRANSFORMS[transform];
+    (0..9).fold(0, |new_grid, cell| new_grid | (((grid >> cell) & 1) << map[cell]))
+}
+
+// Applies a transform to a board, moving every mark, won zone
+// and the zone field (unless it is `ZONE_ANY`) to its new position.
+pub fn transform_board(board: Board, transform: usize) -> Board {
+    let (us, them, share) = board;
+    let map = &TRANSFORMS[transform];
+    let (mut new_us, mut new_them, mut new_share) = (0u64, 0u64, 0u64);
+    for cell in 0..81 {
+        let (x, o) = if cell > 62 {
+            ((share >> (cell - 63)) & 1, (share >> (cell - 45)) & 1)
+        } else {
+            ((us >> cell) & 1, (them >> cell) & 1)
+        };
+        let new_cell = transform_move(cell, transform);
+        if new_cell > 62 {
+            new_share |= (x << (new_cell - 63)) | (o << (new_cell - 45));
+        } else {
+            new_us |= x << new_cell;
+            new_them |= o << new_cell;
+        }
+    }
+    for zone in 0..9 {
+        let new_zone = map[zone as usize];
+        new_share |= ((share >> (36 + zone)) & 1) << (36 + new_zone);
+        new_share |= ((share >> (45 + zone)) & 1) << (45 + new_zone);
+    }
+    let zone = (share >> 54) & 0b1111;
+    let new_zone = if zone < ZONE_ANY { map[zone as usize] } else { zone };
+    (new_us, new_them, new_share | (new_zone << 54))
+}
+
+/**
+ * Returns the canonical form of a board, the least of its symmetric variants,
+ * together with the transform that produces it. Symmetric positions share
+ * a canonical form, so it can be used to merge them, for example in opening trees.
+ * A move in the original board corresponds to `transform_move(mv, transform)`
+ * in the canonical board, and `inverse(transform)` maps it back.
+ */
+pub fn canonical_board(board: Board) -> (Board, usize) {
+    (0..TRANSFORM_COUNT)
+        .map(|transform| (transform_board(board, transform), transform))
+        .min()
+        .unwrap()
+}
+
+/**
+ * Returns the canonical form of a board together with a move in it,
+ * transformed to match. Where the board is itself symmetric, several transforms
+ * give the canonical board, and the least of the resulting moves is chosen,
+ * so that symmetric moves in a symmetric position are also merged.
+ */
+pub fn canonical_move(board: Board, mv: Move) -> (Board, Move) {
+    let (canonical, _) = canonical_board(board);
+    let canonical_mv = (0..TRANSFORM_COUNT)
+        .filter(|&transform| transform_board(board, transform) == canonical)
+        .map(|transform| transform_move(mv, transform))
+        .min()
+        .unwrap();
+    (canonical, canonical_mv)
+}
+
+// The least of the symmetric forms of a line of moves from the empty board,
+// so that symmetric lines (and games) are counted together.
+pub fn canonical_line(moves: &[Move]) -> Vec<Move> {
+    (0..TRANSFORM_COUNT)
+        .map(|transform| moves.iter().map(|&mv| transform_move(mv, transform)).collect())
+        .min()
+        .unwrap()
+}
+
+/**
+ * Returns every symmetric variant of a board together with a move in it, in the order
+ * of the transforms, for augmenting training data. Unlike `canonical_move`, variants
+ * which coincide in a symmetric position are all kept, so that each position
+ * contributes the same number of samples.
+ */
+pub fn symmetric_variants(board: Board, mv: Move) -> [(Board, Move); TRANSFORM_COUNT] {
+    let mut variants = [(board, mv); TRANSFORM_COUNT];
+    for (transform, variant) in variants.iter_mut().enumerate() {
+        *variant = (transform_board(board, transform), transform_move(mv, transform));
+    }
+    variants
+}
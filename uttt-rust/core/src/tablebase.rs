@@ -0,0 +1,283 @@
+use std::{collections::HashMap, convert::TryInto};
+
+use serde::Serialize;
+
+use crate::{
+    engine::{
+        generate_moves, line_presence, play_move, zone_cells, Board, Eval, Move, EMPTY_BOARD,
+        OUTCOME_DRAW, OUTCOME_LOSS, OUTCOME_WIN,
+    },
+    rng::Rng,
+    symmetry::canonical_board,
+    versioning::{migrate_bytes, Format, VersionError},
+    zobrist::{hash_position, Hash},
+};
+
+/*
+ * Exact results of late positions, solved offline (see `uttt-rust tablebase`) and loaded
+ * by the search, which then scores them exactly instead of evaluating or searching them.
+ * A tablebase covers positions with at most `max_empty` empty cells in zones that are
+ * still open, and holds every such position reached while solving the positions
+ * it was generated from, so it is not complete, and a probe may find nothing.
+ *
+ * Like the book, it is saved as a byte buffer, so that it can be shipped to the browser
+ * as a static asset and loaded only when needed. The format is the magic bytes `UTTB`,
+ * a version byte (see `versioning`), the `max_empty` of the tablebase (one byte),
+ * a little-endian `u32` position count, and then for each position: the hash of its
+ * canonical form with the side to move (`u64`, little-endian), its result for the side
+ * to move (one byte: 0 for a loss, 1 for a draw and 2 for a win) and its distance
+ * (one byte). Positions are written in order of their hash.
+ */
+const MAGIC: &[u8; 4] = b"UTTB";
+const HEADER_SIZE: usize = 10;
+const ENTRY_SIZE: usize = 10;
+
+// The most empty cells of positions covered by a tablebase generated by default.
+// Each further cell makes the tablebase several times larger and slower to generate:
+// from 100 games, 10 cells give about 220,000 positions, and 12 about 1,100,000.
+pub const DEFAULT_MAX_EMPTY: u32 = 10;
+
+// The result of a position for the side to move.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Wdl {
+    Loss,
+    Draw,
+    Win,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TablebaseEntry {
+    pub wdl: Wdl,
+    // Plies until the game is decided, with the winner winning as quickly as possible
+    // and the loser losing as slowly as possible. Always 0 for a draw.
+    pub distance: u8,
+}
+
+impl TablebaseEntry {
+    // The entry of a position whose game is over, with `side` to move.
+    fn finished(board: Board, side: bool) -> Self {
+        let lost = line_presence(board.2 >> if side { 36 } else { 45 });
+        TablebaseEntry {
+            wdl: if lost { Wdl::Loss } else { Wdl::Draw },
+            distance: 0,
+        }
+    }
+
+    // The entry of the position before a move leading to a position with this entry.
+    fn parent(self) -> Self {
+        match self.wdl {
+            Wdl::Loss => TablebaseEntry {
+                wdl: Wdl::Win,
+                distance: self.distance + 1,
+            },
+            Wdl::Draw => self,
+            Wdl::Win => TablebaseEntry {
+                wdl: Wdl::Loss,
+                distance: self.distance + 1,
+            },
+        }
+    }
+
+    // Orders entries from best to worst for the side to move.
+    fn rank(self) -> i32 {
+        match self.wdl {
+            Wdl::Win => 1000 - self.distance as i32,
+            Wdl::Draw => 0,
+            Wdl::Loss => self.distance as i32 - 1000,
+        }
+    }
+
+    /**
+     * The score of the entry to a search at `ply` plies from the root, which is that
+     * the search itself gives a game decided `distance` plies later, so that scores
+     * from the tablebase and from the search can be compared.
+     */
+    pub fn score(self, ply: usize) -> Eval {
+        let plies = (ply + self.distance as usize) as Eval;
+        match self.wdl {
+            Wdl::Win => OUTCOME_WIN - plies,
+            Wdl::Draw => OUTCOME_DRAW,
+            Wdl::Loss => OUTCOME_LOSS + plies,
+        }
+    }
+}
+
+// The number of empty cells in zones that are neither won nor full,
+// which decides whether a position is covered by a tablebase.
+pub fn empty_cells(board: Board) -> u32 {
+    let decided = (board.2 >> 36) | (board.2 >> 45);
+    (0..9)
+        .filter(|zone| (decided >> zone) & 1 == 0)
+        .map(|zone| {
+            let (x_cells, o_cells) = zone_cells(board, zone);
+            9 - (x_cells | o_cells).count_ones()
+        })
+        .sum()
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Tablebase {
+    max_empty: u32,
+    positions: HashMap<Hash, TablebaseEntry>,
+}
+
+impl Tablebase {
+    pub fn new(max_empty: u32) -> Self {
+        Tablebase {
+            max_empty,
+            positions: HashMap::new(),
+        }
+    }
+
+    pub fn max_empty(&self) -> u32 {
+        self.max_empty
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    fn key(board: Board, side: bool) -> Hash {
+        hash_position(canonical_board(board).0, side)
+    }
+
+    // Whether the tablebase may hold a position, checked before hashing it,
+    // as that costs more than counting its empty cells.
+    #[inline]
+    fn covers(&self, board: Board) -> bool {
+        !self.positions.is_empty() && empty_cells(board) <= self.max_empty
+    }
+
+    /**
+     * The exact result of a position with `side` to move, if the tablebase holds it.
+     * A finished game is not held, but its result is found all the same,
+     * as the search needs no tablebase for it.
+     */
+    pub fn probe(&self, board: Board, side: bool) -> Option<TablebaseEntry> {
+        if !self.covers(board) {
+            return None;
+        }
+        match generate_moves(board).next() {
+            None => Some(TablebaseEntry::finished(board, side)),
+            Some(_) => self.positions.get(&Self::key(board, side)).copied(),
+        }
+    }
+
+    // A move achieving the result of a position, if the tablebase holds it
+    // and the positions the move leads to.
+    pub fn best_move(&self, board: Board, side: bool) -> Option<Move> {
+        let entry = self.probe(board, side)?;
+        generate_moves(board).find(|&mv| {
+            self.probe(play_move(board, mv, side), !side)
+                .is_some_and(|child| child.parent() == entry)
+        })
+    }
+
+    /**
+     * Solves a position with `side` to move exactly, by searching every line to the end
+     * of the game, adding it and every position reached to the tablebase. The position
+     * must be covered by the tablebase, as otherwise the search may take very long.
+     */
+    pub fn solve(&mut self, board: Board, side: bool) -> TablebaseEntry {
+        debug_assert!(empty_cells(board) <= self.max_empty);
+        let key = Self::key(board, side);
+        if let Some(&entry) = self.positions.get(&key) {
+            return entry;
+        }
+        let moves: Vec<Move> = generate_moves(board).collect();
+        if moves.is_empty() {
+            return TablebaseEntry::finished(board, side);
+        }
+        let entry = moves
+            .into_iter()
+            .map(|mv| self.solve(play_move(board, mv, side), !side).parent())
+            .max_by_key(|entry| entry.rank())
+            .unwrap();
+        self.positions.insert(key, entry);
+        entry
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut keys: Vec<&Hash> = self.positions.keys().collect();
+        keys.sort_unstable();
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + keys.len() * ENTRY_SIZE);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(Format::Tablebase.current_version());
+        bytes.push(self.max_empty as u8);
+        bytes.extend_from_slice(&(keys.len() as u32).to_le_bytes());
+        for key in keys {
+            let entry = self.positions[key];
+            bytes.extend_from_slice(&key.to_le_bytes());
+            bytes.push(entry.wdl as u8);
+            bytes.push(entry.distance);
+        }
+        bytes
+    }
+
+    // Reads a tablebase saved by `to_bytes`, or returns an error if it is malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        if bytes.len() < HEADER_SIZE || &bytes[..4] != MAGIC {
+            return Err("tablebase invalid");
+        }
+        let payload = match migrate_bytes(Format::Tablebase, bytes[4], &bytes[5..]) {
+            Ok(payload) => payload,
+            Err(VersionError::Unsupported) => return Err("tablebase version unsupported"),
+            Err(VersionError::Malformed) => return Err("tablebase invalid"),
+        };
+        let count = u32::from_le_bytes(payload[1..5].try_into().unwrap()) as usize;
+        // Checked without overflowing, as a corrupt count could otherwise match the length.
+        let size = count.checked_mul(ENTRY_SIZE).and_then(|size| size.checked_add(5));
+        if size != Some(payload.len()) {
+            return Err("tablebase invalid");
+        }
+        let mut tablebase = Tablebase::new(payload[0] as u32);
+        for chunk in payload[5..].chunks_exact(ENTRY_SIZE) {
+            let key = Hash::from_le_bytes(chunk[..8].try_into().unwrap());
+            let distance = chunk[9];
+            // Wins take an odd number of plies and losses an even one, and only a finished
+            // game, which is never saved, is decided in none.
+            let wdl = match chunk[8] {
+                0 if distance > 0 && distance % 2 == 0 => Wdl::Loss,
+                1 if distance == 0 => Wdl::Draw,
+                2 if distance % 2 == 1 => Wdl::Win,
+                _ => return Err("tablebase invalid"),
+            };
+            let entry = TablebaseEntry { wdl, distance };
+            if tablebase.positions.insert(key, entry).is_some() {
+                return Err("tablebase invalid");
+            }
+        }
+        Ok(tablebase)
+    }
+}
+
+/**
+ * Generates a tablebase by playing random games from the empty board, and solving
+ * the first position of each with at most `max_empty` empty cells, until `games`
+ * games have been played. The same seed gives the same tablebase.
+ */
+pub fn generate(max_empty: u32, games: usize, seed: u64) -> Tablebase {
+    let mut rng = Rng::new(seed);
+    let mut tablebase = Tablebase::new(max_empty);
+    for _ in 0..games {
+        let (mut board, mut side) = (EMPTY_BOARD, false);
+        loop {
+            let moves: Vec<Move> = generate_moves(board).collect();
+            if moves.is_empty() {
+                break;
+            }
+            if empty_cells(board) <= max_empty {
+                tablebase.solve(board, side);
+                break;
+            }
+            board = play_move(board, moves[(rng.next_u64() % moves.len() as u64) as usize], side);
+            side = !side;
+        }
+    }
+    tablebase
+}
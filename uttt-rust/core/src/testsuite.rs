@@ -0,0 +1,124 @@
+use crate::{
+    board_from_string,
+    engine::{Board, Eval, Move, MAX_PLY},
+    move_from_string,
+    search::{iterative_deepening, SearchContext},
+    timer::Timer,
+    tt::TranspositionTable,
+};
+
+/**
+ * Test suites in an EPD-like format, for tracking tactical strength.
+ * Each line holds a board string, the side to move (`x` or `o`),
+ * and then operations separated by semicolons, for example:
+ *
+ * ```text
+ * xx1o5/9/9/9/9/9/9/9/9 nw x bm nw/ne; id "zone win";
+ * ```
+ *
+ * The `bm` operation lists the accepted best moves, and is required.
+ * The `id` operation names the position, and is optional.
+ * Empty lines and lines beginning with `#` are ignored.
+ */
+#[derive(Clone, Debug)]
+pub struct TestPosition {
+    pub id: String,
+    pub board: Board,
+    // The side to move, using the same convention as the search functions.
+    pub side: bool,
+    pub best_moves: Vec<Move>,
+}
+
+// Parses a single line of a test suite, returning `None` for blank lines and comments.
+pub fn parse_test_position(line: &str) -> Option<Result<TestPosition, String>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    Some(parse_fields(line))
+}
+
+fn parse_fields(line: &str) -> Result<TestPosition, String> {
+    let tokens: Vec<&str> = line.splitn(4, char::is_whitespace).collect();
+    if tokens.len() != 4 {
+        return Err("missing fields".to_string());
+    }
+    let board = board_from_string(&format!("{} {}", tokens[0], tokens[1]))
+        .ok_or_else(|| "board invalid".to_string())?;
+    let side = match tokens[2] {
+        "x" => false,
+        "o" => true,
+        _ => return Err("side invalid".to_string()),
+    };
+
+    let mut id = String::new();
+    let mut best_moves = Vec::new();
+    for operation in tokens[3].split(';').map(str::trim).filter(|op| !op.is_empty()) {
+        let (opcode, operands) = operation
+            .split_once(char::is_whitespace)
+            .unwrap_or((operation, ""));
+        match opcode {
+            "bm" => {
+                for operand in operands.split_whitespace() {
+                    best_moves.push(
+                        move_from_string(operand)
+                            .ok_or_else(|| format!("move invalid: {operand}"))?,
+                    );
+                }
+            }
+            "id" => id = operands.trim().trim_matches('"').to_string(),
+            _ => return Err(format!("operation unknown: {opcode}")),
+        }
+    }
+    if best_moves.is_empty() {
+        return Err("no best move given".to_string());
+    }
+
+    Ok(TestPosition {
+        id,
+        board,
+        side,
+        best_moves,
+    })
+}
+
+// The outcome of running the engine on a single test position.
+#[derive(Clone, Debug)]
+pub struct TestOutcome {
+    pub best_move: Move,
+    pub eval: Eval,
+    pub depth: usize,
+    pub solved: bool,
+}
+
+/**
+ * Searches a test position by iterative deepening, until either `max_depth`
+ * is completed or `time_ms` milliseconds have elapsed, and checks the best move
+ * of the deepest completed iteration. A fresh transposition table is used
+ * so that positions in a suite do not affect one another.
+ * Returns `None` if not even the first iteration completed.
+ */
+pub fn run_test_position(
+    position: &TestPosition,
+    tables: &(Vec<Eval>, Vec<Eval>),
+    time_ms: u64,
+    max_depth: usize,
+    hash_mb: usize,
+) -> Option<TestOutcome> {
+    let timer = Timer::start();
+    let out_of_time = || timer.elapsed_ms() >= time_ms;
+    let mut tt = TranspositionTable::new(hash_mb);
+    let max_depth = max_depth.min(MAX_PLY);
+    let mut ctx = SearchContext::new(tables, max_depth);
+    ctx.tt = Some(&mut tt);
+    ctx.stop_condition = Some(&out_of_time);
+
+    let result = iterative_deepening(position.board, position.side, max_depth, &mut ctx)?;
+    let best_move = result.pv[0];
+    Some(TestOutcome {
+        best_move,
+        eval: result.eval,
+        depth: result.depth,
+        solved: position.best_moves.contains(&best_move),
+    })
+}
@@ -0,0 +1,312 @@
+use crate::{
+    engine::{
+        board_from_choices, generate_moves, line_presence, play_move, Board, Eval, EvalParams, Move,
+        MAX_PLY,
+    },
+    search::{iterative_deepening, SearchContext, SearchResult, SearchStats},
+    timer::{nodes_per_second, Timer},
+    tt::{TranspositionTable, DEFAULT_HASH_MB},
+    zobrist::hash_position,
+};
+
+/*
+ * Time management for games played on a clock. Each move is given a share
+ * of the remaining time, and the search is stopped once it is used up.
+ * When little time remains, moves are chosen by a cheaper path (see `blitz_move`)
+ * instead, so that the engine never loses on time by starting searches it cannot finish.
+ */
+
+// The number of further moves the remaining time is planned to be shared between.
+// Games rarely last more than 60 plies, so each side has about 30 moves at most.
+const MOVES_TO_GO: u64 = 25;
+
+// The percentage of the increment spent on each move, on top of its share of the remaining time.
+const INCREMENT_PERCENT: u64 = 75;
+
+// Below this much remaining time, or with less than `BLITZ_MIN_THINK_MS` to think,
+// moves are chosen in blitz mode.
+pub const BLITZ_THRESHOLD_MS: u64 = 2000;
+pub const BLITZ_MIN_THINK_MS: u64 = 50;
+
+// Depth of the search in blitz mode, which takes a few milliseconds in any position.
+// A stored transposition table move is trusted if it was searched at least this deep.
+pub const BLITZ_DEPTH: usize = 4;
+
+// Time lost between the engine choosing a move and the clock being stopped,
+// in passing the move from the worker to the page (and over the network in online games).
+pub const DEFAULT_MOVE_OVERHEAD_MS: u64 = 30;
+
+// The response time aimed for by searches to depth `auto`, in milliseconds, by default.
+pub const DEFAULT_TARGET_LATENCY_MS: u64 = 1000;
+
+// How long the speed of a device is measured for by default, in milliseconds.
+pub const DEFAULT_SPEED_BUDGET_MS: u64 = 250;
+
+// The position the speed of a device is measured on, given as choices for
+// `board_from_choices`, an early middlegame position of typical branching.
+const SPEED_CHOICES: &[u8] = &[7, 7, 100, 2, 61, 5, 33, 9, 4, 12, 0, 18];
+
+// The least growth in nodes from one depth to the next assumed when extrapolating
+// beyond the depths measured, as the last few may happen to grow slowly.
+const MIN_DEPTH_GROWTH: f64 = 1.5;
+
+/**
+ * The speed of a device, measured by `measure_speed`, from which the depth of a search
+ * answering in a given time is chosen. Fixed depths are too slow on phones and needlessly
+ * weak on desktops, which this lets the frontend avoid without knowing the device.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpeedProfile {
+    pub nps: u64,
+    // The nodes searched by each completed iteration of the measuring search, from depth 1.
+    pub iteration_nodes: Vec<u64>,
+}
+
+impl SpeedProfile {
+    /**
+     * The deepest search expected to complete within `latency_ms`, and at least 1.
+     * Iterative deepening searches every depth up to it, so the nodes of all of them
+     * count. Depths beyond those measured are extrapolated from the growth of the last.
+     */
+    pub fn depth_for_latency(&self, latency_ms: u64) -> usize {
+        let budget = self.nps as f64 * latency_ms as f64 / 1000.0;
+        let measured = &self.iteration_nodes;
+        let growth = match measured.len() {
+            0..=2 => MIN_DEPTH_GROWTH,
+            n => (measured[n - 1] as f64 / measured[n - 3].max(1) as f64).sqrt(),
+        }
+        .max(MIN_DEPTH_GROWTH);
+        let (mut total, mut nodes) = (0.0, 1.0);
+        for depth in 1..=MAX_PLY {
+            nodes = match measured.get(depth - 1) {
+                Some(&measured) => measured as f64,
+                None => nodes * growth,
+            };
+            total += nodes;
+            if total > budget {
+                return (depth - 1).max(1);
+            }
+        }
+        MAX_PLY
+    }
+}
+
+/**
+ * Measures the speed of the device by searching a fixed position for `budget_ms`,
+ * with a fresh transposition table, recording the nodes of each iteration.
+ * The evaluation terms are those of `eval_params`, as they affect the speed.
+ */
+pub fn measure_speed(
+    tables: &(Vec<Eval>, Vec<Eval>),
+    eval_params: EvalParams,
+    budget_ms: u64,
+) -> SpeedProfile {
+    let (board, side) = board_from_choices(SPEED_CHOICES);
+    let timer = Timer::start();
+    let is_over = || timer.elapsed_ms() >= budget_ms;
+    let mut tt = TranspositionTable::new(DEFAULT_HASH_MB);
+    let mut ctx = SearchContext::new(tables, MAX_PLY);
+    ctx.eval_params = eval_params;
+    ctx.tt = Some(&mut tt);
+    ctx.stats = Some(SearchStats::new());
+    ctx.stop_condition = Some(&is_over);
+    iterative_deepening(board, side, MAX_PLY, &mut ctx);
+    SpeedProfile {
+        nps: nodes_per_second(ctx.nodes, timer.elapsed_ms()),
+        iteration_nodes: ctx.stats.take().map_or_else(Vec::new, |stats| stats.iteration_nodes),
+    }
+}
+
+/**
+ * The factors by which the time for each move is chosen, which can be tuned from games
+ * on a clock (see `spsa`). The defaults are those the engine plays with.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeParams {
+    pub moves_to_go: u64,
+    pub increment_percent: u64,
+    pub blitz_threshold_ms: u64,
+    pub blitz_min_think_ms: u64,
+}
+
+impl TimeParams {
+    pub const DEFAULT: TimeParams = TimeParams {
+        moves_to_go: MOVES_TO_GO,
+        increment_percent: INCREMENT_PERCENT,
+        blitz_threshold_ms: BLITZ_THRESHOLD_MS,
+        blitz_min_think_ms: BLITZ_MIN_THINK_MS,
+    };
+
+    // Each factor by name, in a fixed order, for tuners to work through.
+    fn fields_mut(&mut self) -> [(&'static str, &mut u64); 4] {
+        [
+            ("moves_to_go", &mut self.moves_to_go),
+            ("increment_percent", &mut self.increment_percent),
+            ("blitz_threshold_ms", &mut self.blitz_threshold_ms),
+            ("blitz_min_think_ms", &mut self.blitz_min_think_ms),
+        ]
+    }
+
+    // The name and value of each factor.
+    pub fn values(&self) -> Vec<(&'static str, u64)> {
+        let mut params = *self;
+        params.fields_mut().iter().map(|(name, value)| (*name, **value)).collect()
+    }
+
+    // Sets a factor by name, returning an error if there is no factor of that name.
+    pub fn set(&mut self, name: &str, value: u64) -> Result<(), &'static str> {
+        match self.fields_mut().iter_mut().find(|(n, _)| *n == name) {
+            Some((_, field)) => {
+                **field = value;
+                Ok(())
+            }
+            None => Err("parameter unknown"),
+        }
+    }
+}
+
+impl Default for TimeParams {
+    fn default() -> Self {
+        TimeParams::DEFAULT
+    }
+}
+
+// The state of the clock of the side to move, in milliseconds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeControl {
+    pub remaining_ms: u64,
+    // Added to the remaining time after each move.
+    pub increment_ms: u64,
+    // Taken from the time to spend on each move, to allow for latency.
+    pub move_overhead_ms: u64,
+    pub params: TimeParams,
+}
+
+impl TimeControl {
+    // The time to spend on the next move, less the move overhead,
+    // which never exceeds half of the remaining time.
+    pub fn think_time_ms(&self) -> u64 {
+        let share = self.remaining_ms / self.params.moves_to_go.max(1)
+            + self.increment_ms * self.params.increment_percent / 100;
+        share.min(self.remaining_ms / 2).saturating_sub(self.move_overhead_ms)
+    }
+
+    // Whether moves should be chosen in blitz mode.
+    pub fn is_low(&self) -> bool {
+        self.remaining_ms < self.params.blitz_threshold_ms
+            || self.think_time_ms() < self.params.blitz_min_think_ms
+    }
+
+    // The time after which the search for the next move is stopped. Even in blitz mode,
+    // a search is never allowed to use the whole clock.
+    pub fn move_time_ms(&self) -> u64 {
+        match self.is_low() {
+            true => (self.remaining_ms / 2).saturating_sub(self.move_overhead_ms),
+            false => self.think_time_ms(),
+        }
+    }
+}
+
+// Where a move chosen in blitz mode came from, in order of preference.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlitzSource {
+    // The move wins the game at once.
+    Win,
+    Book,
+    TranspositionTable,
+    Search,
+}
+
+impl BlitzSource {
+    pub fn name(self) -> &'static str {
+        match self {
+            BlitzSource::Win => "win",
+            BlitzSource::Book => "book",
+            BlitzSource::TranspositionTable => "tt",
+            BlitzSource::Search => "search",
+        }
+    }
+}
+
+pub struct BlitzMove {
+    pub mv: Move,
+    pub source: BlitzSource,
+    // The result of the shallow search, if one was made.
+    pub result: Option<SearchResult>,
+}
+
+// Whether a move by `side` (`false` for X) wins the game at once.
+fn wins_game(board: Board, mv: Move, side: bool) -> bool {
+    let shift = if side { 45 } else { 36 };
+    line_presence(play_move(board, mv, side).2 >> shift)
+}
+
+// Whether a legal move by `side` leaves the opponent no reply that wins the game at once,
+// which is checked before playing a move that was not searched in this position.
+pub fn is_tactically_safe(board: Board, mv: Move, side: bool) -> bool {
+    let after = play_move(board, mv, side);
+    !generate_moves(after).any(|reply| wins_game(after, reply, !side))
+}
+
+/**
+ * Chooses a move for `side` (`false` for X) cheaply, for when little time remains.
+ * In order, this is a move that wins at once, the given book move, or the move stored
+ * in the transposition table from a search of at least `BLITZ_DEPTH` plies, provided
+ * that the latter two are legal, tactically safe and among the allowed root moves of `ctx`,
+ * if it has any. Failing those, a search of `BLITZ_DEPTH` plies is made with `ctx`,
+ * whose stop condition should be set by the caller. Returns `None` if the position
+ * has no legal moves.
+ */
+pub fn blitz_move(
+    board: Board,
+    side: bool,
+    book_move: Option<Move>,
+    ctx: &mut SearchContext,
+) -> Option<BlitzMove> {
+    let moves: Vec<Move> = generate_moves(board).collect();
+    let first = *moves.first()?;
+    // Weakened play keeps to the moves which give nothing away (see `EngineOptions`).
+    let allowed: Vec<Move> = match &ctx.allowed_root_moves {
+        Some(allowed) => moves.iter().copied().filter(|mv| allowed.contains(mv)).collect(),
+        None => moves.clone(),
+    };
+    let choose = |mv, source| BlitzMove {
+        mv,
+        source,
+        result: None,
+    };
+    if let Some(&mv) = moves.iter().find(|&&mv| wins_game(board, mv, side)) {
+        return Some(choose(mv, BlitzSource::Win));
+    }
+    let playable = |mv: Move| allowed.contains(&mv) && is_tactically_safe(board, mv, side);
+    if let Some(mv) = book_move.filter(|&mv| playable(mv)) {
+        return Some(choose(mv, BlitzSource::Book));
+    }
+    let tt_move = ctx
+        .tt
+        .as_ref()
+        .and_then(|tt| tt.probe(hash_position(board, side)))
+        .filter(|entry| entry.depth as usize >= BLITZ_DEPTH)
+        .map(|entry| entry.best_move());
+    if let Some(mv) = tt_move.filter(|&mv| playable(mv)) {
+        return Some(choose(mv, BlitzSource::TranspositionTable));
+    }
+
+    ctx.max_depth = BLITZ_DEPTH;
+    let result = iterative_deepening(board, side, BLITZ_DEPTH, ctx);
+    // Without even a single completed iteration, any safe move will do.
+    let mv = match &result {
+        Some(result) => result.pv[0],
+        None => allowed
+            .iter()
+            .copied()
+            .find(|&mv| is_tactically_safe(board, mv, side))
+            .or_else(|| allowed.first().copied())
+            .unwrap_or(first),
+    };
+    Some(BlitzMove {
+        mv,
+        source: BlitzSource::Search,
+        result,
+    })
+}
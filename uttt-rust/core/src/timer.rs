@@ -0,0 +1,39 @@
+/**
+ * Wall-clock timing of searches.
+ * `std::time::Instant` is not available in the browser, so the wasm build
+ * uses the JavaScript clock instead, which has millisecond resolution.
+ */
+
+#[cfg(target_arch = "wasm32")]
+fn now_ms() -> f64 {
+    js_sys::Date::now()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn now_ms() -> f64 {
+    use std::{sync::LazyLock, time::Instant};
+    static EPOCH: LazyLock<Instant> = LazyLock::new(Instant::now);
+    EPOCH.elapsed().as_secs_f64() * 1000.0
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Timer {
+    start: f64,
+}
+
+impl Timer {
+    // Starts a new timer from the current time.
+    pub fn start() -> Self {
+        Timer { start: now_ms() }
+    }
+
+    // Milliseconds elapsed since the timer was started.
+    pub fn elapsed_ms(&self) -> u64 {
+        (now_ms() - self.start).max(0.0) as u64
+    }
+}
+
+// Nodes searched per second, given a node count and the elapsed milliseconds.
+pub fn nodes_per_second(nodes: u64, elapsed_ms: u64) -> u64 {
+    nodes * 1000 / elapsed_ms.max(1)
+}
@@ -0,0 +1,267 @@
+use std::convert::TryInto;
+
+use crate::{
+    engine::{Eval, Move, NULL_MOVE, OUTCOME_LOSS, OUTCOME_WIN},
+    versioning::{migrate_bytes, Format, VersionError},
+    zobrist::Hash,
+};
+
+// Size of the transposition table, in megabytes, used by default.
+pub const DEFAULT_HASH_MB: usize = 4;
+
+// Scores this close to a win or loss are decisive, and encode a distance
+// in plies which has to be adjusted when moving between nodes.
+pub(crate) const DECISIVE_MARGIN: Eval = 100;
+
+// Whether a stored score is exact, or only bounds the true score
+// because of an alpha-beta cutoff.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+    // The true score is at least the stored score (the search failed high).
+    Lower,
+    // The true score is at most the stored score (the search failed low).
+    Upper,
+}
+
+// Limits on the configurable size of the table, in megabytes.
+pub const MIN_HASH_MB: usize = 1;
+pub const MAX_HASH_MB: usize = 1024;
+
+/**
+ * A single stored search result.
+ * The fields are kept small so that an entry fits in 16 bytes.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct TTEntry {
+    pub key: Hash,
+    pub score: Eval,
+    pub best_move: u8,
+    pub depth: u8,
+    pub bound: Bound,
+    // The search that stored this entry, so that stale entries can be replaced.
+    pub generation: u8,
+}
+
+impl TTEntry {
+    pub fn best_move(&self) -> Move {
+        self.best_move as Move
+    }
+}
+
+// Each position maps to a bucket of two slots, using a two-tier replacement scheme.
+// The first slot keeps the deepest result from the current search,
+// while the second slot is always overwritten with the newest result.
+type Bucket = [Option<TTEntry>; 2];
+const DEPTH_PREFERRED: usize = 0;
+const ALWAYS_REPLACE: usize = 1;
+
+/**
+ * Transposition table mapping position hashes to previous search results,
+ * which are used both for cutoffs and to search the best move first.
+ */
+pub struct TranspositionTable {
+    buckets: Vec<Bucket>,
+    generation: u8,
+}
+
+impl TranspositionTable {
+    pub fn new(megabytes: usize) -> Self {
+        let count = (megabytes << 20) / std::mem::size_of::<Bucket>();
+        TranspositionTable {
+            buckets: vec![[None; 2]; count.max(1)],
+            generation: 0,
+        }
+    }
+
+    // Maps a hash onto a bucket index, using the high bits of the hash
+    // so that the table size need not be a power of two.
+    #[inline]
+    fn index(&self, key: Hash) -> usize {
+        ((key as u128 * self.buckets.len() as u128) >> 64) as usize
+    }
+
+    // Marks the start of a new search, so that entries from earlier searches
+    // are replaced in preference to those from the current search.
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    // Returns the stored entry for this position, if there is one.
+    pub fn probe(&self, key: Hash) -> Option<TTEntry> {
+        self.buckets[self.index(key)]
+            .iter()
+            .flatten()
+            .find(|entry| entry.key == key)
+            .copied()
+    }
+
+    pub fn store(&mut self, key: Hash, depth: usize, score: Eval, bound: Bound, best_move: Move) {
+        let generation = self.generation;
+        let index = self.index(key);
+        let bucket = &mut self.buckets[index];
+
+        // Keep the previous best move when this search did not find one,
+        // since it is still the best guess for move ordering.
+        let best_move = match bucket.iter().flatten().find(|entry| entry.key == key) {
+            Some(entry) if best_move == NULL_MOVE => entry.best_move,
+            _ => best_move as u8,
+        };
+        let new_entry = TTEntry {
+            key,
+            score,
+            best_move,
+            depth: depth as u8,
+            bound,
+            generation,
+        };
+
+        // The depth-preferred slot is only overwritten by a result of the same position,
+        // a result at least as deep, or any result if its entry is from an earlier search.
+        let replace_preferred = match bucket[DEPTH_PREFERRED] {
+            None => true,
+            Some(entry) => {
+                entry.key == key || entry.generation != generation || depth as u8 >= entry.depth
+            }
+        };
+        // Since the depth-preferred slot is probed first, a stale copy
+        // of the same position in the other slot is never used.
+        if replace_preferred {
+            bucket[DEPTH_PREFERRED] = Some(new_entry);
+        } else {
+            bucket[ALWAYS_REPLACE] = Some(new_entry);
+        }
+    }
+
+    // Empties the table and resets the generation count.
+    pub fn clear(&mut self) {
+        self.buckets.fill([None; 2]);
+        self.generation = 0;
+    }
+
+    // The number of entries the table can hold.
+    pub fn capacity(&self) -> usize {
+        self.buckets.len() * 2
+    }
+
+    // The size of the table in megabytes.
+    pub fn megabytes(&self) -> usize {
+        (self.buckets.len() * std::mem::size_of::<Bucket>()) >> 20
+    }
+
+    // Approximate occupancy in permille by entries from the current search,
+    // sampled from the first 500 buckets.
+    pub fn hashfull(&self) -> usize {
+        let sample = &self.buckets[..self.buckets.len().min(500)];
+        let used = sample
+            .iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.generation == self.generation)
+            .count();
+        used * 1000 / (sample.len() * 2)
+    }
+}
+
+/**
+ * Serialisation of the table contents, so that search results can be kept
+ * between sessions. The format is the magic bytes `UTTS`, a version byte
+ * (see `versioning`),
+ * a little-endian `u32` entry count, and then for each entry: the key (`u64`),
+ * score (`i32`), best move, depth and bound (one byte each), all little-endian.
+ * Generations are not stored, as imported entries belong to no particular search.
+ */
+const STATE_MAGIC: &[u8; 4] = b"UTTS";
+const STATE_HEADER_SIZE: usize = 9;
+const STATE_ENTRY_SIZE: usize = 15;
+
+impl TranspositionTable {
+    pub fn export(&self) -> Vec<u8> {
+        let entries: Vec<&TTEntry> = self.buckets.iter().flatten().flatten().collect();
+        let mut bytes = Vec::with_capacity(STATE_HEADER_SIZE + entries.len() * STATE_ENTRY_SIZE);
+        bytes.extend_from_slice(STATE_MAGIC);
+        bytes.push(Format::EngineState.current_version());
+        bytes.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        for entry in entries {
+            bytes.extend_from_slice(&entry.key.to_le_bytes());
+            bytes.extend_from_slice(&entry.score.to_le_bytes());
+            bytes.push(entry.best_move);
+            bytes.push(entry.depth);
+            bytes.push(match entry.bound {
+                Bound::Exact => 0,
+                Bound::Lower => 1,
+                Bound::Upper => 2,
+            });
+        }
+        bytes
+    }
+
+    // Merges previously exported entries into the table, using the usual
+    // replacement scheme. Returns the number of entries read, or an error
+    // without modifying the table if the data is malformed.
+    pub fn import(&mut self, bytes: &[u8]) -> Result<usize, &'static str> {
+        if bytes.len() < STATE_HEADER_SIZE || &bytes[..4] != STATE_MAGIC {
+            return Err("state invalid");
+        }
+        let payload = match migrate_bytes(Format::EngineState, bytes[4], &bytes[5..]) {
+            Ok(payload) => payload,
+            Err(VersionError::Unsupported) => return Err("state version unsupported"),
+            Err(VersionError::Malformed) => return Err("state invalid"),
+        };
+        let count = u32::from_le_bytes([payload[0], payload[1], payload[2], payload[3]]) as usize;
+        let body = &payload[STATE_HEADER_SIZE - 5..];
+        // The count is checked without overflowing, as the data may be corrupt, and the
+        // entries are allocated by the length of the body, which has been read in full.
+        if count.checked_mul(STATE_ENTRY_SIZE) != Some(body.len()) {
+            return Err("state invalid");
+        }
+
+        let mut entries = Vec::with_capacity(body.len() / STATE_ENTRY_SIZE);
+        for chunk in body.chunks_exact(STATE_ENTRY_SIZE) {
+            let bound = match chunk[14] {
+                0 => Bound::Exact,
+                1 => Bound::Lower,
+                2 => Bound::Upper,
+                _ => return Err("state invalid"),
+            };
+            if chunk[12] as Move > NULL_MOVE {
+                return Err("state invalid");
+            }
+            entries.push((
+                Hash::from_le_bytes(chunk[0..8].try_into().unwrap()),
+                Eval::from_le_bytes(chunk[8..12].try_into().unwrap()),
+                chunk[12] as Move,
+                chunk[13] as usize,
+                bound,
+            ));
+        }
+        for (key, score, best_move, depth, bound) in entries {
+            self.store(key, depth, score, bound, best_move);
+        }
+        Ok(count)
+    }
+}
+
+// Decisive scores are stored relative to the node rather than the root,
+// so that they remain correct when the position is reached at another ply.
+#[inline]
+pub fn score_to_tt(score: Eval, ply: usize) -> Eval {
+    if score >= OUTCOME_WIN - DECISIVE_MARGIN {
+        score + ply as Eval
+    } else if score <= OUTCOME_LOSS + DECISIVE_MARGIN {
+        score - ply as Eval
+    } else {
+        score
+    }
+}
+
+#[inline]
+pub fn score_from_tt(score: Eval, ply: usize) -> Eval {
+    if score >= OUTCOME_WIN - DECISIVE_MARGIN {
+        score - ply as Eval
+    } else if score <= OUTCOME_LOSS + DECISIVE_MARGIN {
+        score + ply as Eval
+    } else {
+        score
+    }
+}
@@ -0,0 +1,144 @@
+use std::borrow::Cow;
+
+/*
+ * Versions of the formats in which boards, games and engine data are saved,
+ * and the migration of saved data from older versions to the current one.
+ *
+ * Text formats (board strings, game records and match records) may begin with a tag `v<n>`
+ * followed by whitespace, such as `v1 1-0 c/c c/nw`. Untagged text is version 1,
+ * the format used before tags were introduced, so that everything saved earlier
+ * remains readable. Binary formats begin with a version byte, after any magic bytes.
+ *
+ * Every change to a format must take a new version number, with a migration step
+ * from the previous version to it. Saved data is migrated step by step to the current
+ * version before it is parsed, so that parsers only deal with the current version.
+ * No format has changed since version 1, so there are no steps yet.
+ */
+
+// A format in which data is saved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    BoardString,
+    GameRecord,
+    // The transposition table contents of `export_state`.
+    EngineState,
+    Book,
+    // The tokens of `encode_state_url`.
+    StateUrl,
+    // The annotated game records of `record`.
+    MatchRecord,
+    // The saved games of `gamedb`.
+    GameDb,
+    // The lines of `ndjson`, which carry their version in a field rather than a tag.
+    Ndjson,
+    // The games with analysis of `annotated`, which also carry their version in a field.
+    AnnotatedGame,
+    Tablebase,
+    // The learnt lookup tables of `win_tables`.
+    WinTables,
+}
+
+impl Format {
+    // The version in which the format is written.
+    pub fn current_version(self) -> u8 {
+        match self {
+            Format::BoardString => 1,
+            Format::GameRecord => 1,
+            Format::EngineState => 1,
+            Format::Book => 1,
+            Format::StateUrl => 1,
+            Format::MatchRecord => 1,
+            Format::GameDb => 1,
+            Format::Ndjson => 1,
+            Format::AnnotatedGame => 1,
+            Format::Tablebase => 1,
+            Format::WinTables => 1,
+        }
+    }
+}
+
+// Why saved data could not be read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VersionError {
+    // The version tag or header is malformed.
+    Malformed,
+    // The version is unknown, such as one written by a newer release.
+    Unsupported,
+}
+
+impl VersionError {
+    pub fn description(self) -> &'static str {
+        match self {
+            VersionError::Malformed => "version invalid",
+            VersionError::Unsupported => "version unsupported",
+        }
+    }
+}
+
+// Prefixes text with the tag of the current version of its format.
+pub fn tag_text(format: Format, text: &str) -> String {
+    format!("v{} {text}", format.current_version())
+}
+
+// Splits the version tag from text, returning version 1 for untagged text.
+fn split_tag(text: &str) -> Result<(u8, &str), VersionError> {
+    let text = text.trim_start();
+    match text.strip_prefix('v') {
+        Some(tagged) => {
+            let (version, rest) = tagged.split_once(char::is_whitespace).unwrap_or((tagged, ""));
+            match version.parse::<u8>() {
+                Ok(version) if version > 0 => Ok((version, rest)),
+                _ => Err(VersionError::Malformed),
+            }
+        }
+        None => Ok((1, text)),
+    }
+}
+
+// Converts text of a format from version `from` to version `from + 1`.
+fn migrate_text_step(_format: Format, _from: u8, _text: &str) -> Result<String, VersionError> {
+    Err(VersionError::Unsupported)
+}
+
+/**
+ * Strips any version tag from text of a format, and migrates it from that version
+ * to the current version, ready to be parsed.
+ */
+pub fn migrate_text(format: Format, text: &str) -> Result<Cow<'_, str>, VersionError> {
+    let (version, body) = split_tag(text)?;
+    if version > format.current_version() {
+        return Err(VersionError::Unsupported);
+    }
+    let mut text = Cow::Borrowed(body);
+    for from in version..format.current_version() {
+        text = Cow::Owned(migrate_text_step(format, from, &text)?);
+    }
+    Ok(text)
+}
+
+// Converts the payload of a binary format from version `from` to version `from + 1`.
+fn migrate_bytes_step(_format: Format, _from: u8, _bytes: &[u8]) -> Result<Vec<u8>, VersionError> {
+    Err(VersionError::Unsupported)
+}
+
+/**
+ * Migrates the payload of a binary format, which follows the version byte,
+ * from the given version to the current version, ready to be parsed.
+ */
+pub fn migrate_bytes(
+    format: Format,
+    version: u8,
+    payload: &[u8],
+) -> Result<Cow<'_, [u8]>, VersionError> {
+    if version == 0 {
+        return Err(VersionError::Malformed);
+    }
+    if version > format.current_version() {
+        return Err(VersionError::Unsupported);
+    }
+    let mut payload = Cow::Borrowed(payload);
+    for from in version..format.current_version() {
+        payload = Cow::Owned(migrate_bytes_step(format, from, &payload)?);
+    }
+    Ok(payload)
+}
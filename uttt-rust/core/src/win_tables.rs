@@ -0,0 +1,255 @@
+use crate::{
+    engine::{line_presence, zone_cells, Eval, OUTCOME_DRAW, OUTCOME_LOSS, OUTCOME_WIN},
+    game::{Game, GameResult},
+    rng::Rng,
+    selfplay::{play_game, random_opening, MatchSettings},
+    symmetry::{transform_grid, TRANSFORM_COUNT},
+    versioning::{migrate_bytes, Format, VersionError},
+};
+
+/*
+ * Lookup tables learnt from the results of self-play games, an alternative to those built
+ * from the heuristic weights by `init_with` which is far lighter than a learnt evaluation
+ * of whole positions. Every arrangement of a grid is scored by how the games it was seen
+ * in turned out: its evaluation is the log-odds of X's expected score from those games.
+ * Decided arrangements of the large grid are scored exactly, as in the heuristic tables,
+ * so the learnt tables take their place in `evaluate` (and beside its other terms) as they are.
+ *
+ * Each arrangement is counted in all eight of its symmetric variants, and again with the
+ * sides swapped and the result reversed, so that the tables score a grid for O as the
+ * negation of the same grid for X, which `evaluate` relies on.
+ *
+ * Like the tablebase, the tables are saved as a byte buffer, so that they can be learnt
+ * offline (see `uttt-rust win-tables`) and shipped to the browser as a static asset.
+ * The format is the magic bytes `UTWT`, a version byte (see `versioning`), and then the
+ * evaluation of each of the 19683 arrangements of a grid, first in the large grid and then
+ * in a small grid, as little-endian `i16`s. Arrangements are ordered as base 3 numbers
+ * whose `i`-th digit is cell `i`: 0 if it is empty, 1 if it is X's and 2 if it is O's.
+ * Decided arrangements, whose evaluations are never read, are written as 0.
+ */
+const MAGIC: &[u8; 4] = b"UTWT";
+const ARRANGEMENTS: usize = 19683;
+const TABLE_SIZE: usize = 262144;
+const GRID: u64 = 0b111111111;
+
+// Evaluation units per unit of log-odds, which puts learnt evaluations on about the scale
+// of the heuristic ones, so that the other terms of the evaluation keep their weight.
+pub const LOGIT_SCALE: f64 = 400.0;
+
+// Drawn positions added to those of every arrangement, so that an arrangement
+// seen only a few times is scored near zero rather than by a handful of results.
+pub const PRIOR_POSITIONS: f64 = 4.0;
+
+// The most a learnt evaluation may be, well within the range of the saved format.
+const MAX_EVAL: Eval = 30000;
+
+// The number of positions an arrangement was seen in, and X's points from their games,
+// counting a win as 2 and a draw as 1 so that they are whole numbers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Tally {
+    seen: u64,
+    x_points: u64,
+}
+
+impl Tally {
+    // The log-odds of X's expected score, as the difference of the logarithms of X's points
+    // and O's, which negates exactly when the sides are swapped.
+    fn eval(self) -> Eval {
+        let x_points = self.x_points as f64 + PRIOR_POSITIONS;
+        let o_points = (2 * self.seen - self.x_points) as f64 + PRIOR_POSITIONS;
+        let eval = (LOGIT_SCALE * (x_points.ln() - o_points.ln())).round() as Eval;
+        eval.clamp(-MAX_EVAL, MAX_EVAL)
+    }
+}
+
+// Every arrangement of a grid, as the cells of X and of O, in the order of the saved format.
+fn arrangements() -> impl Iterator<Item = (u64, u64)> {
+    (0..ARRANGEMENTS).map(|mut digits| {
+        let (mut x_cells, mut o_cells) = (0, 0);
+        for cell in 0..9 {
+            match digits % 3 {
+                1 => x_cells |= 1 << cell,
+                2 => o_cells |= 1 << cell,
+                _ => {}
+            }
+            digits /= 3;
+        }
+        (x_cells, o_cells)
+    })
+}
+
+// The exact evaluation of a grid that has been won or filled, or `None` if it is still open.
+fn decided_eval(x_cells: u64, o_cells: u64) -> Option<Eval> {
+    if line_presence(x_cells) {
+        Some(OUTCOME_WIN)
+    } else if line_presence(o_cells) {
+        Some(OUTCOME_LOSS)
+    } else if x_cells | o_cells == GRID {
+        Some(OUTCOME_DRAW)
+    } else {
+        None
+    }
+}
+
+/**
+ * The results of the games each arrangement of the large grid and of a small grid was
+ * seen in, indexed as the lookup tables are, from which the learnt tables are made.
+ */
+#[derive(Clone, Debug)]
+pub struct WinStats {
+    games: usize,
+    large: Vec<Tally>,
+    small: Vec<Tally>,
+}
+
+impl Default for WinStats {
+    fn default() -> Self {
+        WinStats::new()
+    }
+}
+
+impl WinStats {
+    pub fn new() -> Self {
+        WinStats {
+            games: 0,
+            large: vec![Tally::default(); TABLE_SIZE],
+            small: vec![Tally::default(); TABLE_SIZE],
+        }
+    }
+
+    // The number of games recorded.
+    pub fn games(&self) -> usize {
+        self.games
+    }
+
+    // Counts an arrangement in each of its symmetric variants, with either side as X.
+    fn count(tallies: &mut [Tally], x_cells: u64, o_cells: u64, x_points: u64) {
+        for transform in 0..TRANSFORM_COUNT {
+            let x = transform_grid(x_cells, transform);
+            let o = transform_grid(o_cells, transform);
+            for (x, o, points) in [(x, o, x_points), (o, x, 2 - x_points)] {
+                let tally = &mut tallies[((o << 9) | x) as usize];
+                tally.seen += 1;
+                tally.x_points += points;
+            }
+        }
+    }
+
+    /**
+     * Counts the grids of every position of a finished game with the given result:
+     * the large grid until it is decided, and each small grid while it is still in play.
+     */
+    pub fn record(&mut self, game: &Game, result: GameResult) {
+        let x_points = match result {
+            GameResult::XWins => 2,
+            GameResult::Draw => 1,
+            GameResult::OWins => 0,
+        };
+        for board in (0..=game.moves().len()).filter_map(|ply| game.board_at(ply)) {
+            let (x_zones, o_zones) = ((board.2 >> 36) & GRID, (board.2 >> 45) & GRID);
+            if decided_eval(x_zones, o_zones).is_some() {
+                continue;
+            }
+            Self::count(&mut self.large, x_zones, o_zones, x_points);
+            for zone in (0..9).filter(|zone| ((x_zones | o_zones) >> zone) & 1 == 0) {
+                let (x_cells, o_cells) = zone_cells(board, zone);
+                if x_cells | o_cells != GRID {
+                    Self::count(&mut self.small, x_cells, o_cells, x_points);
+                }
+            }
+        }
+        self.games += 1;
+    }
+
+    // The learnt lookup tables, in the form of those of `init`.
+    pub fn tables(&self) -> (Vec<Eval>, Vec<Eval>) {
+        let mut large = vec![0; TABLE_SIZE];
+        let mut small = vec![0; TABLE_SIZE];
+        for (x_cells, o_cells) in arrangements() {
+            let index = ((o_cells << 9) | x_cells) as usize;
+            match decided_eval(x_cells, o_cells) {
+                Some(eval) => large[index] = eval,
+                None => {
+                    large[index] = self.large[index].eval();
+                    small[index] = self.small[index].eval();
+                }
+            }
+        }
+        (large, small)
+    }
+}
+
+/**
+ * Saves lookup tables, learnt or not, in the format above. Evaluations beyond the range
+ * of an `i16` are clamped to it, which the tables of `init` never need.
+ */
+pub fn to_bytes(tables: &(Vec<Eval>, Vec<Eval>)) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(5 + 4 * ARRANGEMENTS);
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(Format::WinTables.current_version());
+    for table in [&tables.0, &tables.1] {
+        for (x_cells, o_cells) in arrangements() {
+            let eval = match decided_eval(x_cells, o_cells) {
+                Some(_) => 0,
+                None => table[((o_cells << 9) | x_cells) as usize],
+            };
+            let eval = eval.clamp(i16::MIN as Eval, i16::MAX as Eval) as i16;
+            bytes.extend_from_slice(&eval.to_le_bytes());
+        }
+    }
+    bytes
+}
+
+// Reads tables saved by `to_bytes`, or returns an error if they are malformed.
+pub fn from_bytes(bytes: &[u8]) -> Result<(Vec<Eval>, Vec<Eval>), &'static str> {
+    if bytes.len() < 5 || &bytes[..4] != MAGIC {
+        return Err("win tables invalid");
+    }
+    let payload = match migrate_bytes(Format::WinTables, bytes[4], &bytes[5..]) {
+        Ok(payload) => payload,
+        Err(VersionError::Unsupported) => return Err("win tables version unsupported"),
+        Err(VersionError::Malformed) => return Err("win tables invalid"),
+    };
+    if payload.len() != 4 * ARRANGEMENTS {
+        return Err("win tables invalid");
+    }
+    let mut evals = payload
+        .chunks_exact(2)
+        .map(|chunk| i16::from_le_bytes([chunk[0], chunk[1]]) as Eval);
+    let mut large = vec![0; TABLE_SIZE];
+    let mut small = vec![0; TABLE_SIZE];
+    for (is_large, table) in [(true, &mut large), (false, &mut small)] {
+        for (x_cells, o_cells) in arrangements() {
+            let saved = evals.next().unwrap();
+            let index = ((o_cells << 9) | x_cells) as usize;
+            match decided_eval(x_cells, o_cells) {
+                None => table[index] = saved,
+                Some(eval) if is_large => table[index] = eval,
+                Some(_) => {}
+            }
+        }
+    }
+    Ok((large, small))
+}
+
+/**
+ * Plays `games` games of self-play, each from a random opening of `opening_plies` moves,
+ * with the engines of `settings` searching with `tables`, and records their results.
+ * The same seed gives the same statistics.
+ */
+pub fn generate(
+    games: usize,
+    settings: &MatchSettings,
+    opening_plies: usize,
+    seed: u64,
+    tables: &(Vec<Eval>, Vec<Eval>),
+) -> WinStats {
+    let mut rng = Rng::new(seed);
+    let mut stats = WinStats::new();
+    for _ in 0..games {
+        let opening = random_opening(&mut rng, opening_plies);
+        let outcome = play_game(&opening, settings, tables);
+        stats.record(&outcome.game, outcome.result);
+    }
+    stats
+}
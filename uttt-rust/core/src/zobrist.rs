@@ -0,0 +1,117 @@
+use crate::{
+    engine::{Board, Move, ZONE_ANY},
+    symmetry::canonical_line,
+};
+
+/**
+ * Zobrist hashing of board positions.
+ * Each cell and player pair, each zone value and the side to move
+ * are assigned a pseudorandom 64-bit key, and a position is hashed
+ * by XOR-ing together the keys of all the features present in it.
+ */
+pub type Hash = u64;
+
+// Since hashes are used by the frontend as persistent keys, the keys must
+// never change between builds. They are hence generated at compile time
+// from a fixed seed, rather than from a runtime source of randomness.
+const SEED: u64 = 0x5554_5454_5a4f_4252;
+
+// Layout of the key array: 81 keys for cells occupied by X,
+// 81 keys for cells occupied by O, 10 keys for the zone values
+// (including `ZONE_ANY`), and 1 key for O being the side to move.
+const O_OFFSET: usize = 81;
+const ZONE_OFFSET: usize = 162;
+const SIDE_INDEX: usize = 172;
+const KEY_COUNT: usize = 173;
+
+// The SplitMix64 generator, which produces well-distributed values
+// even from a simple incrementing state.
+pub(crate) const fn splitmix64(state: u64) -> u64 {
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+const fn generate_keys() -> [u64; KEY_COUNT] {
+    let mut keys = [0u64; KEY_COUNT];
+    let mut i = 0;
+    while i < KEY_COUNT {
+        keys[i] = splitmix64(SEED.wrapping_add((i as u64 + 1).wrapping_mul(0x9e3779b97f4a7c15)));
+        i += 1;
+    }
+    keys
+}
+
+static KEYS: [u64; KEY_COUNT] = generate_keys();
+
+// XORs together the keys (starting from `offset`) of every set bit in `bits`,
+// where bit `i` corresponds to cell `first_cell + i`.
+#[inline]
+fn hash_bits(mut bits: u64, first_cell: usize, offset: usize) -> Hash {
+    let mut hash = 0;
+    while bits != 0 {
+        hash ^= KEYS[offset + first_cell + bits.trailing_zeros() as usize];
+        bits &= bits - 1;
+    }
+    hash
+}
+
+// Returns the hash of a board, independent of the side to move.
+// The large grid is not hashed, since it is fully determined by the small grids.
+pub fn hash_board(board: Board) -> Hash {
+    let (us, them, share) = board;
+    let zone = ((share >> 54) & 0b1111).min(ZONE_ANY) as usize;
+    hash_bits(us, 0, 0)
+        ^ hash_bits(them, 0, O_OFFSET)
+        ^ hash_bits(share & 0x3ffff, 63, 0)
+        ^ hash_bits((share >> 18) & 0x3ffff, 63, O_OFFSET)
+        ^ KEYS[ZONE_OFFSET + zone]
+}
+
+// Returns the hash of a board together with the side to move,
+// using the same `side` convention as the search functions.
+#[inline]
+pub fn hash_position(board: Board, side: bool) -> Hash {
+    hash_board(board) ^ side_key(side)
+}
+
+// The key that distinguishes positions by the side to move.
+#[inline]
+pub fn side_key(side: bool) -> Hash {
+    if side {
+        KEYS[SIDE_INDEX]
+    } else {
+        0
+    }
+}
+
+// Incrementally updates a position hash after `mv` is played by `side`,
+// given the zone values before and after the move.
+// This flips the side to move, exactly as `play_move` followed by
+// `hash_position` with the opposite side would.
+#[inline]
+pub fn update_hash(hash: Hash, mv: Move, side: bool, old_zone: u64, new_zone: u64) -> Hash {
+    let cell_key = if side {
+        KEYS[O_OFFSET + mv as usize]
+    } else {
+        KEYS[mv as usize]
+    };
+    hash ^ cell_key
+        ^ KEYS[ZONE_OFFSET + old_zone as usize]
+        ^ KEYS[ZONE_OFFSET + new_zone as usize]
+        ^ KEYS[SIDE_INDEX]
+}
+
+/**
+ * Returns a hash of the moves of a game from the empty board, taken in their canonical
+ * form (see `canonical_line`), so that a game and its symmetric variants hash alike.
+ * It is used to recognise games imported more than once, and like the position hashes,
+ * it never changes between builds. Unlike them, it is not a Zobrist hash, as the order
+ * of the moves matters.
+ */
+pub fn hash_game(moves: &[Move]) -> Hash {
+    canonical_line(moves)
+        .iter()
+        .fold(splitmix64(SEED), |hash, &mv| splitmix64(hash ^ (mv + 1)))
+}
@@ -0,0 +1,64 @@
+use serde::Serialize;
+
+use crate::{engine::{line_presence, zone_cells, Board}, ZONE_ARRAY_LOWER};
+
+/*
+ * The state of each zone, for frontends drawing a badge over it, so that they need not
+ * read the bitboards themselves. A zone whose cells are all filled without a line is
+ * drawn, and counts for neither side, however many marks each has in it.
+ */
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ZoneStatus {
+    Open,
+    WonX,
+    WonO,
+    // Full without a line for either side.
+    Drawn,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ZoneSummary {
+    // The zone's name, such as `nw`.
+    pub zone: &'static str,
+    pub status: ZoneStatus,
+    pub x_marks: u32,
+    pub o_marks: u32,
+    // Whether the side to move must play in this zone. No zone is forced when the side
+    // to move may play anywhere, nor once the game is over.
+    pub forced: bool,
+}
+
+// The status of a zone, by its index from 0 (NW) to 8 (SE).
+pub fn zone_status(board: Board, zone: u64) -> ZoneStatus {
+    let (x_cells, o_cells) = zone_cells(board, zone);
+    if (board.2 >> (36 + zone)) & 1 == 1 {
+        ZoneStatus::WonX
+    } else if (board.2 >> (45 + zone)) & 1 == 1 {
+        ZoneStatus::WonO
+    } else if x_cells | o_cells == 0b111111111 {
+        ZoneStatus::Drawn
+    } else {
+        ZoneStatus::Open
+    }
+}
+
+// Summarises the nine zones in index order, from NW to SE.
+pub fn zone_summary(board: Board) -> Vec<ZoneSummary> {
+    let game_over = line_presence(board.2 >> 36) || line_presence(board.2 >> 45);
+    let forced_zone = (board.2 >> 54) & 0b1111;
+    (0..9)
+        .map(|zone| {
+            let (x_cells, o_cells) = zone_cells(board, zone);
+            let status = zone_status(board, zone);
+            ZoneSummary {
+                zone: ZONE_ARRAY_LOWER[zone as usize],
+                status,
+                x_marks: x_cells.count_ones(),
+                o_marks: o_cells.count_ones(),
+                forced: !game_over && zone == forced_zone && status == ZoneStatus::Open,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "dev-tools")]
+
+use uttt_core::{
+    engine::{perft, EMPTY_BOARD},
+    movegen_check::{check_random, check_tree},
+};
+
+/*
+ * Checks that `generate_moves_into` gives exactly the moves of `generate_moves`, in the
+ * same order, before either is changed. Run with `cargo test --features dev-tools`.
+ * The tree is shallower, and the random games fewer, than `uttt-rust movegen-check`
+ * uses by default, so that the tests stay quick in debug builds.
+ */
+
+#[test]
+fn generators_agree_on_tree() {
+    let report = check_tree(4);
+    assert!(report.passed(), "mismatches: {:?}", report.mismatches);
+    // Every position to depth 4 is visited once per line reaching it, as in perft.
+    let expected: u64 = (0..=4).map(|depth| perft(EMPTY_BOARD, false, depth)).sum();
+    assert_eq!(report.positions, expected);
+}
+
+#[test]
+fn generators_agree_on_random_games() {
+    let report = check_random(200_000, 687);
+    assert!(report.passed(), "mismatches: {:?}", report.mismatches);
+    assert!(report.positions >= 200_000);
+}
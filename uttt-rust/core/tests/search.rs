@@ -0,0 +1,142 @@
+use std::cell::Cell;
+
+use uttt_core::{
+    engine::{board_from_choices, generate_moves, Board, Eval, Move, OUTCOME_LOSS},
+    load_tables,
+    rng::Rng,
+    search::{is_forced_win, iterative_deepening, longest_win, root_move_scores, SearchContext},
+    tt::{TranspositionTable, DEFAULT_HASH_MB},
+};
+
+/*
+ * Checks that decisive scores are ordered by distance: the search prefers the quickest
+ * of several forced wins, resists a forced loss for as long as it can, and in troll mode
+ * prefers the slowest forced win instead. Positions are taken from seeded random games,
+ * and the moves the search chooses are compared with scores of every root move.
+ */
+
+const DEPTH: usize = 5;
+const POSITIONS: usize = 300;
+
+type Tables = (Vec<Eval>, Vec<Eval>);
+
+// Positions from random games, with the side to move, which are not yet over.
+fn positions() -> Vec<(Board, bool)> {
+    let mut rng = Rng::new(672);
+    (0..POSITIONS)
+        .map(|_| {
+            let length = 20 + (rng.next_u64() % 40) as usize;
+            let choices: Vec<u8> = (0..length).map(|_| rng.next_u64() as u8).collect();
+            board_from_choices(&choices)
+        })
+        .filter(|&(board, _)| generate_moves(board).next().is_some())
+        .collect()
+}
+
+fn scores(board: Board, side: bool, tables: &Tables) -> Vec<(Move, Eval)> {
+    let mut ctx = SearchContext::new(tables, DEPTH);
+    root_move_scores(board, side, DEPTH, &mut ctx).unwrap()
+}
+
+// The score and best move of an ordinary search, as `go` would make it.
+fn search(board: Board, side: bool, tables: &Tables) -> (Eval, Move) {
+    let mut tt = TranspositionTable::new(DEFAULT_HASH_MB);
+    let mut ctx = SearchContext::new(tables, DEPTH);
+    ctx.tt = Some(&mut tt);
+    let result = iterative_deepening(board, side, DEPTH, &mut ctx).unwrap();
+    (result.eval, result.pv[0])
+}
+
+fn score_of(scores: &[(Move, Eval)], mv: Move) -> Eval {
+    scores.iter().find(|&&(m, _)| m == mv).unwrap().1
+}
+
+fn is_forced_loss(eval: Eval) -> bool {
+    eval <= OUTCOME_LOSS + DEPTH as Eval
+}
+
+#[test]
+fn prefers_shortest_win() {
+    let tables = load_tables();
+    let mut checked = 0;
+    for (board, side) in positions() {
+        let scores = scores(board, side, &tables);
+        let best = scores[0].1;
+        if !is_forced_win(best) {
+            continue;
+        }
+        let (eval, mv) = search(board, side, &tables);
+        assert_eq!(eval, best, "win distance in {board:?}");
+        assert_eq!(score_of(&scores, mv), best, "slower win chosen in {board:?}");
+        checked += 1;
+    }
+    assert!(checked > 0, "no forced wins found");
+}
+
+#[test]
+fn resists_loss_longest() {
+    let tables = load_tables();
+    let mut checked = 0;
+    for (board, side) in positions() {
+        let scores = scores(board, side, &tables);
+        let best = scores[0].1;
+        if !is_forced_loss(best) {
+            continue;
+        }
+        let (eval, mv) = search(board, side, &tables);
+        assert_eq!(eval, best, "loss distance in {board:?}");
+        assert_eq!(score_of(&scores, mv), best, "quicker loss chosen in {board:?}");
+        checked += 1;
+    }
+    assert!(checked > 0, "no forced losses found");
+}
+
+#[test]
+fn troll_mode_prefers_longest_win() {
+    let tables = load_tables();
+    let (mut checked, mut prolonged) = (0, 0);
+    for (board, side) in positions() {
+        let scores = scores(board, side, &tables);
+        if !is_forced_win(scores[0].1) {
+            continue;
+        }
+        let mut ctx = SearchContext::new(&tables, DEPTH);
+        let (mv, eval) = longest_win(board, side, DEPTH, &mut ctx).unwrap();
+        let slowest = scores.iter().map(|&(_, e)| e).filter(|&e| is_forced_win(e)).min();
+        assert_eq!(Some(eval), slowest, "faster win chosen in {board:?}");
+        assert_eq!(score_of(&scores, mv), eval);
+        checked += 1;
+        if eval < scores[0].1 {
+            prolonged += 1;
+        }
+    }
+    assert!(checked > 0, "no forced wins found");
+    assert!(prolonged > 0, "no win could be prolonged");
+}
+
+// Root results of a search restricted to some moves are kept out of the table, yet each
+// iteration still searches every allowed move exactly once, the best of the last first,
+// rather than searching the root again to find a first move (see `alpha_beta`).
+#[test]
+fn restricted_root_searches_each_move_once() {
+    let tables = load_tables();
+    for (board, side) in positions().into_iter().take(20) {
+        let allowed: Vec<Move> = generate_moves(board).skip(1).collect();
+        if allowed.is_empty() {
+            continue;
+        }
+        let searched = Cell::new(0);
+        let observer = |_: usize, mv: Move, _: usize| {
+            assert!(allowed.contains(&mv));
+            searched.set(searched.get() + 1);
+        };
+        let mut tt = TranspositionTable::new(DEFAULT_HASH_MB);
+        let mut ctx = SearchContext::new(&tables, DEPTH);
+        ctx.tt = Some(&mut tt);
+        ctx.allowed_root_moves = Some(allowed.clone());
+        ctx.observer = Some(&observer);
+        let result = iterative_deepening(board, side, DEPTH, &mut ctx).unwrap();
+        assert!(allowed.contains(&result.pv[0]));
+        assert_eq!(searched.get(), DEPTH * allowed.len(), "root searched again in {board:?}");
+    }
+}
@@ -0,0 +1,53 @@
+use uttt_core::{
+    engine::{board_from_choices, evaluate, generate_moves, Board, PackedTables},
+    load_tables,
+    rng::Rng,
+    search::{iterative_deepening, SearchContext},
+};
+
+/*
+ * Checks that the packed backend of the lookup tables holds the same values as the tables
+ * of `init`, so that evaluations and searches are unchanged by which of them is used.
+ * Positions are taken from seeded random games of every length.
+ */
+
+const POSITIONS: usize = 2000;
+const DEPTH: usize = 4;
+
+// Positions from random games, with the side to move, including finished ones.
+fn positions() -> Vec<(Board, bool)> {
+    let mut rng = Rng::new(702);
+    (0..POSITIONS)
+        .map(|_| {
+            let length = (rng.next_u64() % 80) as usize;
+            let choices: Vec<u8> = (0..length).map(|_| rng.next_u64() as u8).collect();
+            board_from_choices(&choices)
+        })
+        .collect()
+}
+
+#[test]
+fn packed_tables_evaluate_alike() {
+    let tables = load_tables();
+    let packed = PackedTables::new(&tables).unwrap();
+    for (board, side) in positions() {
+        assert_eq!(evaluate(board, side, &tables), evaluate(board, side, &packed));
+    }
+}
+
+#[test]
+fn packed_tables_search_alike() {
+    let tables = load_tables();
+    let packed = PackedTables::new(&tables).unwrap();
+    for (board, side) in positions().into_iter().step_by(20) {
+        if generate_moves(board).next().is_none() {
+            continue;
+        }
+        let mut ctx = SearchContext::new(&tables, DEPTH);
+        let expected = iterative_deepening(board, side, DEPTH, &mut ctx).unwrap();
+        let mut packed_ctx = SearchContext::new(&packed, DEPTH);
+        let actual = iterative_deepening(board, side, DEPTH, &mut packed_ctx).unwrap();
+        assert_eq!((expected.eval, expected.pv), (actual.eval, actual.pv));
+        assert_eq!(ctx.nodes, packed_ctx.nodes);
+    }
+}
@@ -0,0 +1,22 @@
+#![no_main]
+
+// Checks that `go` responds to arbitrary arguments with either a search result
+// or an error, and agrees with `parse_go_arguments` about which it is.
+use libfuzzer_sys::fuzz_target;
+use uttt_rust::{go, parse_go_arguments};
+use wasm_bindgen::JsValue;
+
+// Deeper searches are too slow to run on every input.
+const MAX_FUZZ_DEPTH: usize = 2;
+
+fuzz_target!(|input: (&str, &str, bool)| {
+    let (depth, board, side) = input;
+    let parsed = parse_go_arguments(depth, board, false);
+    if let Ok((d, _)) = parsed {
+        if d > MAX_FUZZ_DEPTH {
+            return;
+        }
+    }
+    let response = go(depth, board, side, None, JsValue::UNDEFINED);
+    assert_eq!(response[0] == "error", parsed.is_err());
+});
@@ -0,0 +1,24 @@
+#![no_main]
+
+// Feeds arbitrary strings to every parser that receives input from JavaScript,
+// checking that none of them panic, and that any board or score they accept
+// can be serialised again.
+use libfuzzer_sys::fuzz_target;
+use uttt_core::{
+    board_from_string, board_from_values, board_string,
+    engine::{check_invariants, MAX_PLY},
+    eval_from_string, eval_string, move_from_string, move_string,
+};
+
+fuzz_target!(|input: &str| {
+    if let Some(mv) = move_from_string(input) {
+        assert_eq!(move_string(mv), input);
+    }
+    for &board in [board_from_string(input), board_from_values(input)].iter().flatten() {
+        let _ = board_string(board);
+        let _ = check_invariants(board);
+    }
+    if let Some(eval) = eval_from_string(input) {
+        assert_eq!(eval_from_string(&eval_string(eval, MAX_PLY)), Some(eval));
+    }
+});
@@ -0,0 +1,26 @@
+#![no_main]
+
+// Plays arbitrary games, checking that every generated move is to an empty cell,
+// and that every position reached satisfies all board invariants.
+use libfuzzer_sys::fuzz_target;
+use uttt_core::engine::{
+    check_invariants, generate_moves, play_move, zone_cells, Move, EMPTY_BOARD,
+};
+
+fuzz_target!(|choices: &[u8]| {
+    let mut board = EMPTY_BOARD;
+    let mut side = false;
+    for &choice in choices {
+        let moves: Vec<Move> = generate_moves(board).collect();
+        if moves.is_empty() {
+            break;
+        }
+        for &mv in &moves {
+            let (x_cells, o_cells) = zone_cells(board, mv / 9);
+            assert_eq!((x_cells | o_cells) >> (mv % 9) & 1, 0, "move to an occupied cell");
+        }
+        board = play_move(board, moves[choice as usize % moves.len()], side);
+        side = !side;
+        assert_eq!(check_invariants(board), Ok(()));
+    }
+});
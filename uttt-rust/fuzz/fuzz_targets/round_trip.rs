@@ -0,0 +1,15 @@
+#![no_main]
+
+// Checks that every board accepted by `board_from_string`, and every board
+// reachable by legal play, is unchanged by serialising and parsing it again.
+use libfuzzer_sys::fuzz_target;
+use uttt_core::{board_from_string, board_string, engine::board_from_choices};
+
+fuzz_target!(|input: (&str, &[u8])| {
+    let (string, choices) = input;
+    if let Some(board) = board_from_string(string) {
+        assert_eq!(board_from_string(&board_string(board)), Some(board));
+    }
+    let (board, _) = board_from_choices(choices);
+    assert_eq!(board_from_string(&board_string(board)), Some(board));
+});
@@ -0,0 +1,45 @@
+export interface CallOptions {
+  signal?: AbortSignal;
+}
+
+export interface GoOptions extends CallOptions {
+  onProgress?: (info: string[]) => void;
+}
+
+export class Engine {
+  constructor();
+  go(depth: string, board: string, side: boolean, options?: GoOptions): Promise<string[]>;
+  goDual(depth: string, board: string, side: boolean, options?: CallOptions): Promise<string[]>;
+  goClock(
+    board: string,
+    side: boolean,
+    remainingMs: number,
+    incrementMs: number,
+    options?: CallOptions,
+  ): Promise<string[]>;
+  goMate(plies: string, board: string, side: boolean, options?: CallOptions): Promise<string[]>;
+  precomputeReplies(
+    board: string,
+    side: boolean,
+    budgetMs: number,
+    options?: CallOptions,
+  ): Promise<string[]>;
+  analyzeGame(moves: string, depth: string, options?: CallOptions): Promise<any>;
+  analyzeBatch(
+    boards: string[],
+    depth: string,
+    shareTt?: boolean,
+    options?: CallOptions,
+  ): Promise<any>;
+  evaluateChildren(
+    board: string,
+    side: boolean,
+    depth: string,
+    options?: CallOptions,
+  ): Promise<any>;
+  exportState(): Promise<Uint8Array>;
+  importState(bytes: Uint8Array): Promise<string[]>;
+  call(method: string, ...args: unknown[]): Promise<any>;
+  stop(): void;
+  terminate(): Promise<number>;
+}
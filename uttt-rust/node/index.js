@@ -0,0 +1,156 @@
+'use strict';
+
+/*
+ * The engine for Node.js. Each `Engine` runs on its own worker thread, so that
+ * searches (such as server-side analysis jobs) do not block the event loop.
+ * Calls to an engine are queued and run one at a time, each returning a promise
+ * of what the export of the same name in `pkg/uttt_rust.js` returns.
+ *
+ *     const { Engine } = require('uttt-engine');
+ *     const engine = new Engine();
+ *     const response = await engine.go('8', board, true, { signal, onProgress });
+ *     const state = await engine.exportState();
+ *     await otherEngine.importState(state);
+ *     await engine.terminate();
+ */
+
+const path = require('path');
+const { Worker } = require('worker_threads');
+
+class Engine {
+  constructor() {
+    this._stopBuffer = new SharedArrayBuffer(4);
+    this._stopFlag = new Int32Array(this._stopBuffer);
+    this._worker = new Worker(path.join(__dirname, 'worker.js'), {
+      workerData: { stopBuffer: this._stopBuffer },
+    });
+    this._nextId = 0;
+    this._pending = new Map();
+    this._queue = Promise.resolve();
+    this._worker.on('message', (message) => this._receive(message));
+    this._worker.on('error', (error) => this._failAll(error));
+    // Lets the process exit while the engine is idle.
+    this._worker.unref();
+  }
+
+  // Searches as `go` does. `onProgress` is called with each info keyword array.
+  go(depth, board, side, { signal, onProgress } = {}) {
+    return this._call('go', [depth, board, side], { signal, onProgress });
+  }
+
+  // Searches as `go_dual` does. The preview callback cannot reach another thread,
+  // so only the response of the full search is returned.
+  goDual(depth, board, side, { signal } = {}) {
+    return this._call('go_dual', [depth, board, side], { signal });
+  }
+
+  goClock(board, side, remainingMs, incrementMs, { signal } = {}) {
+    return this._call('go_clock', [board, side, remainingMs, incrementMs], { signal });
+  }
+
+  goMate(plies, board, side, { signal } = {}) {
+    return this._call('go_mate', [plies, board, side], { signal });
+  }
+
+  precomputeReplies(board, side, budgetMs, { signal } = {}) {
+    return this._call('precompute_replies', [board, side, budgetMs], { signal });
+  }
+
+  analyzeGame(moves, depth, { signal } = {}) {
+    return this._call('analyze_game', [moves, depth], { signal });
+  }
+
+  analyzeBatch(boards, depth, shareTt, { signal } = {}) {
+    return this._call('analyze_batch', [boards, depth, shareTt], { signal });
+  }
+
+  evaluateChildren(board, side, depth, { signal } = {}) {
+    return this._call('evaluate_children', [board, side, depth], { signal });
+  }
+
+  // Resolves to the engine's search results as a `Uint8Array`,
+  // which can be given to `importState` of this or another engine.
+  exportState() {
+    return this._call('export_state', []);
+  }
+
+  // Restores search results from `exportState`. The buffer of `bytes` is moved
+  // to the worker rather than copied, so `bytes` is unusable afterwards.
+  importState(bytes) {
+    return this._call('import_state', [bytes], { transfer: [bytes.buffer] });
+  }
+
+  // Calls any other export of the engine by name, such as `set_option` or `probe`.
+  call(method, ...args) {
+    return this._call(method, args);
+  }
+
+  // Stops the call in progress, which then responds with its deepest completed iteration.
+  stop() {
+    Atomics.store(this._stopFlag, 0, 1);
+  }
+
+  terminate() {
+    this.stop();
+    return this._worker.terminate();
+  }
+
+  _call(method, args, { signal, onProgress, transfer = [] } = {}) {
+    const run = () =>
+      new Promise((resolve, reject) => {
+        const id = this._nextId++;
+        const onAbort = () => this.stop();
+        // Only one call is in flight at a time, so the flag belongs to this call.
+        Atomics.store(this._stopFlag, 0, signal && signal.aborted ? 1 : 0);
+        if (signal) {
+          signal.addEventListener('abort', onAbort);
+        }
+        this._pending.set(id, {
+          onProgress,
+          settle: (error, result) => {
+            if (signal) {
+              signal.removeEventListener('abort', onAbort);
+            }
+            if (error) {
+              reject(error);
+            } else {
+              resolve(result);
+            }
+          },
+        });
+        this._worker.ref();
+        this._worker.postMessage({ id, method, args, progress: Boolean(onProgress) }, transfer);
+      });
+    const result = this._queue.then(run);
+    // A failed call does not hold up those queued after it.
+    this._queue = result.catch(() => {});
+    return result;
+  }
+
+  _receive({ id, progress, result, error }) {
+    const pending = this._pending.get(id);
+    if (!pending) {
+      return;
+    }
+    if (progress !== undefined) {
+      if (pending.onProgress) {
+        pending.onProgress(progress);
+      }
+      return;
+    }
+    this._pending.delete(id);
+    if (this._pending.size === 0) {
+      this._worker.unref();
+    }
+    pending.settle(error === undefined ? null : new Error(error), result);
+  }
+
+  _failAll(error) {
+    for (const pending of this._pending.values()) {
+      pending.settle(error);
+    }
+    this._pending.clear();
+  }
+}
+
+module.exports = { Engine };
@@ -0,0 +1,37 @@
+'use strict';
+
+/*
+ * Checks that calls made through a worker reach the engine's exports with their
+ * arguments in place. Run with `npm test` once the engine has been built into `pkg`.
+ * Each search is given a signal which is already aborted, so that it responds at once,
+ * which it only does if the worker has put the signal in the right argument.
+ */
+
+const assert = require('assert');
+const { Engine } = require('./index.js');
+
+const EMPTY_BOARD = '9/9/9/9/9/9/9/9/9 any';
+
+async function main() {
+  const engine = new Engine();
+  const controller = new AbortController();
+  controller.abort();
+  const { signal } = controller;
+  try {
+    assert.deepStrictEqual(await engine.goDual('8', EMPTY_BOARD, true, { signal }), [
+      'error',
+      'search',
+      'aborted',
+    ]);
+    assert.deepStrictEqual(await engine.evaluateChildren(EMPTY_BOARD, true, '8', { signal }), {
+      error: 'search aborted',
+    });
+  } finally {
+    await engine.terminate();
+  }
+}
+
+main().catch((e) => {
+  console.error(e);
+  process.exit(1);
+});
@@ -0,0 +1,52 @@
+'use strict';
+
+/*
+ * Runs calls to the engine on a worker thread, so that searches do not block
+ * the event loop of the main thread. Each worker has its own engine,
+ * with its own transposition table and options.
+ */
+
+const { parentPort, workerData } = require('worker_threads');
+const engine = require('./pkg/uttt_rust.js');
+
+// Set by the main thread to stop the call in progress. A real `AbortSignal`
+// cannot be used, as its events never reach a worker which is busy searching.
+const stopFlag = new Int32Array(workerData.stopBuffer);
+const signal = {
+  get aborted() {
+    return Atomics.load(stopFlag, 0) !== 0;
+  },
+};
+
+// The position of the `signal` argument of each export which takes one.
+const SIGNAL_ARGUMENT = {
+  go: 3,
+  go_dual: 3,
+  go_clock: 4,
+  go_mate: 3,
+  only_move: 3,
+  precompute_replies: 3,
+  analyze_game: 2,
+  analyze_batch: 3,
+  evaluate_children: 3,
+};
+
+parentPort.on('message', ({ id, method, args, progress }) => {
+  try {
+    if (typeof engine[method] !== 'function') {
+      throw new Error(`unknown method ${method}`);
+    }
+    if (method in SIGNAL_ARGUMENT) {
+      args[SIGNAL_ARGUMENT[method]] = signal;
+    }
+    if (method === 'go' && progress) {
+      args[4] = (info) => parentPort.postMessage({ id, progress: info });
+    }
+    const result = engine[method](...args);
+    // Exported state is moved to the main thread rather than copied.
+    const transfer = result instanceof Uint8Array ? [result.buffer] : [];
+    parentPort.postMessage({ id, result }, transfer);
+  } catch (e) {
+    parentPort.postMessage({ id, error: String(e && e.message ? e.message : e) });
+  }
+});
@@ -1,4 +1,166 @@
 /* tslint:disable */
 /* eslint-disable */
-export function go(depth: string, board: string, side: boolean): (string)[];
+
+export class Replay {
+    private constructor();
+    free(): void;
+    [Symbol.dispose](): void;
+    analyze(depth: string, signal?: any | null): any;
+    board(): string;
+    cached_analysis(): any;
+    current_move(): string;
+    static from_transcript(moves: string): Replay | undefined;
+    length(): number;
+    next(): string;
+    next_move(): string;
+    ply(): number;
+    prev(): string;
+    seek(ply: number): boolean;
+    side(): boolean;
+}
+
+export function adjudicate(board: string, side: boolean, evals: string): string[];
+
+export function analyze_batch(boards: string[], depth: string, share_tt?: boolean | null, signal?: any | null): any;
+
+export function analyze_game(moves: string, depth: string, signal?: any | null): any;
+
+export function apply_move(board: string, mv: string, side: boolean): any;
+
+export function board_from_bytes(bytes: Uint8Array): any;
+
+export function board_to_bytes(board: string, side: boolean): Uint8Array;
+
+export function book_export(): Uint8Array;
+
+export function book_import(bytes: Uint8Array): string[];
+
+export function book_move(board: string): string[];
+
+export function calibrate_speed(budget_ms: number): string[];
+
+export function check_invariants(board: string): string[];
+
+export function coach(board: string, mv: string): any;
+
+export function count_legal_moves(board: string): string[];
+
+export function decode_state_url(token: string): any;
+
+export function describe_board(board: string): string;
+
+export function encode_state_url(moves: string): string;
+
+export function engine_constants(): any;
+
+export function engine_info(): any;
+
+export function evaluate_children(board: string, side: boolean, depth: string, signal?: any | null): any;
+
+export function export_state(): Uint8Array;
+
+export function gamedb_add(text: string): string[];
+
+export function gamedb_export(): Uint8Array;
+
+export function gamedb_export_ndjson(): string;
+
+export function gamedb_games(board: string): any;
+
+export function gamedb_import(bytes: Uint8Array): string[];
+
+export function gamedb_import_ndjson(text: string): string[];
+
+export function gamedb_moves(board: string): any;
+
+export function gamedb_record(game: number): string;
+
+export function gamedb_search(pattern: string, symmetric: boolean): any;
+
+export function gamedb_stats(max_plies: string): any;
+
+export function go(depth: string, board: string, side: boolean, signal: any | null | undefined, progress: any): string[];
+
+export function go_clock(board: string, side: boolean, remaining_ms: number, increment_ms: number, signal?: any | null): string[];
+
+export function go_dual(depth: string, board: string, side: boolean, signal: any | null | undefined, on_preview: Function | null | undefined, progress: any): string[];
+
+export function go_human(depth: string, board: string, side: boolean, rating: number): string[];
+
+export function go_mate(plies: string, board: string, side: boolean, signal?: any | null): string[];
+
+export function hash_board(board: string): string;
+
+export function hash_game(moves: string): string;
+
+export function hash_info(): string[];
+
+export function import_moves(text: string, notation: string): string[];
+
+export function import_state(bytes: Uint8Array): string[];
+
+export function learn_game(record: string): string[];
+
+export function legal_move_mask(board: string): Uint32Array;
+
+export function load_analysis(text: string): any;
+
+export function load_book(bytes: Uint8Array): string[];
+
+export function load_tablebase(bytes: Uint8Array): string[];
+
+export function load_win_tables(bytes: Uint8Array): string[];
+
+export function merge_analysis(saved: string, fresh: string): string;
+
+export function new_game(): void;
+
+export function only_move(depth: string, board: string, side: boolean, signal?: any | null): string[];
+
+export function opening_tree(records: string, max_plies: string): any;
+
+export function parse_match_record(text: string): any;
+
+export function parse_ndjson_line(line: string): any;
+
+export function precompute_replies(board: string, side: boolean, budget_ms: number, signal?: any | null): string[];
+
+export function probe(board: string, side: boolean): string[];
+
+export function probe_tablebase(board: string, side: boolean): string[];
+
+export function render_svg(board: string, options_json: string): string;
+
+export function save_analysis(moves: string, analysis_json: string): string;
+
+export function selftest(): string[];
+
 export function serialise_board(board_value: string): string;
+
+export function set_option(name: string, value: string): string[];
+
+export function stop(): void;
+
+export function transform_board(board: string, transform: string): string;
+
+export function transform_move(mv: string, transform: string): string;
+
+export function traps(board: string): any;
+
+export function unload_book(): string[];
+
+export function unload_tablebase(): string[];
+
+export function unload_win_tables(): string[];
+
+export function warmup(on_progress?: Function | null): string[];
+
+export function warmup_step(rows: number): string[];
+
+export function write_match_record(record_json: string): string;
+
+export function write_ndjson_line(record_json: string, analysis_json: string): string;
+
+export function zone_evals(board: string, side: boolean): any;
+
+export function zone_summary(board: string): any;
@@ -1,5 +1,9 @@
+/* @ts-self-types="./uttt_rust.d.ts" */
 import * as wasm from "./uttt_rust_bg.wasm";
-export * from "./uttt_rust_bg.js";
 import { __wbg_set_wasm } from "./uttt_rust_bg.js";
+
 __wbg_set_wasm(wasm);
 wasm.__wbindgen_start();
+export {
+    Replay, adjudicate, analyze_batch, analyze_game, apply_move, board_from_bytes, board_to_bytes, book_export, book_import, book_move, calibrate_speed, check_invariants, coach, count_legal_moves, decode_state_url, describe_board, encode_state_url, engine_constants, engine_info, evaluate_children, export_state, gamedb_add, gamedb_export, gamedb_export_ndjson, gamedb_games, gamedb_import, gamedb_import_ndjson, gamedb_moves, gamedb_record, gamedb_search, gamedb_stats, go, go_clock, go_dual, go_human, go_mate, hash_board, hash_game, hash_info, import_moves, import_state, learn_game, legal_move_mask, load_analysis, load_book, load_tablebase, load_win_tables, merge_analysis, new_game, only_move, opening_tree, parse_match_record, parse_ndjson_line, precompute_replies, probe, probe_tablebase, render_svg, save_analysis, selftest, serialise_board, set_option, stop, transform_board, transform_move, traps, unload_book, unload_tablebase, unload_win_tables, warmup, warmup_step, write_match_record, write_ndjson_line, zone_evals, zone_summary
+} from "./uttt_rust_bg.js";
@@ -1,11 +1,97 @@
 /* tslint:disable */
 /* eslint-disable */
 export const memory: WebAssembly.Memory;
-export const go: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+export const __wbg_replay_free: (a: number, b: number) => void;
+export const adjudicate: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+export const analyze_batch: (a: number, b: number, c: number, d: number, e: number, f: number) => any;
+export const analyze_game: (a: number, b: number, c: number, d: number, e: number) => any;
+export const apply_move: (a: number, b: number, c: number, d: number, e: number) => any;
+export const board_from_bytes: (a: number, b: number) => any;
+export const board_to_bytes: (a: number, b: number, c: number) => [number, number];
+export const book_export: () => [number, number];
+export const book_import: (a: number, b: number) => [number, number];
+export const book_move: (a: number, b: number) => [number, number];
+export const calibrate_speed: (a: number) => [number, number];
+export const check_invariants: (a: number, b: number) => [number, number];
+export const coach: (a: number, b: number, c: number, d: number) => any;
+export const count_legal_moves: (a: number, b: number) => [number, number];
+export const decode_state_url: (a: number, b: number) => any;
+export const describe_board: (a: number, b: number) => [number, number];
+export const encode_state_url: (a: number, b: number) => [number, number];
+export const engine_constants: () => any;
+export const engine_info: () => any;
+export const evaluate_children: (a: number, b: number, c: number, d: number, e: number, f: number) => any;
+export const export_state: () => [number, number];
+export const gamedb_add: (a: number, b: number) => [number, number];
+export const gamedb_export: () => [number, number];
+export const gamedb_export_ndjson: () => [number, number];
+export const gamedb_games: (a: number, b: number) => any;
+export const gamedb_import: (a: number, b: number) => [number, number];
+export const gamedb_import_ndjson: (a: number, b: number) => [number, number];
+export const gamedb_moves: (a: number, b: number) => any;
+export const gamedb_record: (a: number) => [number, number];
+export const gamedb_search: (a: number, b: number, c: number) => any;
+export const gamedb_stats: (a: number, b: number) => any;
+export const go: (a: number, b: number, c: number, d: number, e: number, f: number, g: any) => [number, number];
+export const go_clock: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number];
+export const go_dual: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: any) => [number, number];
+export const go_human: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number];
+export const go_mate: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number];
+export const hash_board: (a: number, b: number) => [number, number];
+export const hash_game: (a: number, b: number) => [number, number];
+export const hash_info: () => [number, number];
+export const import_moves: (a: number, b: number, c: number, d: number) => [number, number];
+export const import_state: (a: number, b: number) => [number, number];
+export const learn_game: (a: number, b: number) => [number, number];
+export const legal_move_mask: (a: number, b: number) => [number, number];
+export const load_analysis: (a: number, b: number) => any;
+export const load_book: (a: number, b: number) => [number, number];
+export const load_tablebase: (a: number, b: number) => [number, number];
+export const load_win_tables: (a: number, b: number) => [number, number];
+export const merge_analysis: (a: number, b: number, c: number, d: number) => [number, number];
+export const new_game: () => void;
+export const only_move: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number];
+export const opening_tree: (a: number, b: number, c: number, d: number) => any;
+export const parse_match_record: (a: number, b: number) => any;
+export const parse_ndjson_line: (a: number, b: number) => any;
+export const precompute_replies: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+export const probe: (a: number, b: number, c: number) => [number, number];
+export const probe_tablebase: (a: number, b: number, c: number) => [number, number];
+export const render_svg: (a: number, b: number, c: number, d: number) => [number, number];
+export const replay_analyze: (a: number, b: number, c: number, d: number) => any;
+export const replay_board: (a: number) => [number, number];
+export const replay_cached_analysis: (a: number) => any;
+export const replay_current_move: (a: number) => [number, number];
+export const replay_from_transcript: (a: number, b: number) => number;
+export const replay_length: (a: number) => number;
+export const replay_next: (a: number) => [number, number];
+export const replay_next_move: (a: number) => [number, number];
+export const replay_ply: (a: number) => number;
+export const replay_prev: (a: number) => [number, number];
+export const replay_seek: (a: number, b: number) => number;
+export const replay_side: (a: number) => number;
+export const save_analysis: (a: number, b: number, c: number, d: number) => [number, number];
+export const selftest: () => [number, number];
 export const serialise_board: (a: number, b: number) => [number, number];
-export const __wbindgen_free: (a: number, b: number, c: number) => void;
+export const set_option: (a: number, b: number, c: number, d: number) => [number, number];
+export const stop: () => void;
+export const transform_board: (a: number, b: number, c: number, d: number) => [number, number];
+export const transform_move: (a: number, b: number, c: number, d: number) => [number, number];
+export const traps: (a: number, b: number) => any;
+export const unload_book: () => [number, number];
+export const unload_tablebase: () => [number, number];
+export const unload_win_tables: () => [number, number];
+export const warmup: (a: number) => [number, number];
+export const warmup_step: (a: number) => [number, number];
+export const write_match_record: (a: number, b: number) => [number, number];
+export const write_ndjson_line: (a: number, b: number, c: number, d: number) => [number, number];
+export const zone_evals: (a: number, b: number, c: number) => any;
+export const zone_summary: (a: number, b: number) => any;
 export const __wbindgen_malloc: (a: number, b: number) => number;
 export const __wbindgen_realloc: (a: number, b: number, c: number, d: number) => number;
-export const __wbindgen_export_3: WebAssembly.Table;
+export const __wbindgen_exn_store: (a: number) => void;
+export const __externref_table_alloc: () => number;
+export const __wbindgen_externrefs: WebAssembly.Table;
+export const __wbindgen_free: (a: number, b: number, c: number) => void;
 export const __externref_drop_slice: (a: number, b: number) => void;
 export const __wbindgen_start: () => void;
@@ -1,89 +1,2126 @@
-use std::sync::LazyLock;
-
-use crate::{
-    utils::{
-        set_panic_hook,
-        move_string,
-        // move_from_string,
-        eval_string,
-        board_string,
-        board_from_string,
-        // print_board,
-        engine::{
-            // Board, Move, Eval,
-            init, alpha_beta,
-            // play_move, generate_moves,
-            // ZONE_ANY,
-            NULL_MOVE, MAX_PLY,
-            OUTCOME_WIN, OUTCOME_LOSS,
-        },
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, LazyLock, Mutex,
+};
+
+use uttt_core::{
+    move_string,
+    move_from_string,
+    eval_string,
+    eval_from_string,
+    board_string,
+    board_from_string,
+    board_from_values,
+    load_tables,
+    // print_board,
+    engine::{
+        self,
+        Board, Eval, EvalParams, Move, TableBuilder,
+        // play_move, generate_moves,
+        // ZONE_ANY,
+        NULL_MOVE, MAX_PLY,
+        // OUTCOME_WIN, OUTCOME_LOSS,
+    },
+    search::{
+        is_forced_win, iterative_deepening, longest_win, root_move_lines, root_move_scores,
+        SearchContext, SearchObserver, SearchResult, SearchStats,
     },
+    timeman::{
+        blitz_move, measure_speed, SpeedProfile, TimeControl, TimeParams,
+        DEFAULT_SPEED_BUDGET_MS,
+    },
+    timer::{nodes_per_second, Timer},
+    tt::{Bound, TranspositionTable, DEFAULT_HASH_MB},
+    adjudication::{Adjudication, Adjudicator},
+    analysis,
+    annotated,
+    coach,
+    describe,
+    game::{self, Game},
+    humanlike,
+    info,
+    ndjson,
+    notation::{self, Notation},
+    options::{EngineOptions, ScorePerspective, TableSource},
+    ponder,
+    record,
+    replay,
+    rng::Rng,
+    selftest,
+    state_url,
+    symmetry,
+    tablebase::{Tablebase, Wdl},
+    win_tables,
+    zobrist,
+    zones,
 };
 
-mod utils;
+#[cfg(feature = "book")]
+use uttt_core::{
+    book::Book,
+    game::parse_game_record,
+    gamedb::GameDb,
+    pattern::Pattern,
+    opening_tree::opening_tree_from_records,
+};
+#[cfg(feature = "solver")]
+use uttt_core::solver::{MateSearch, DRAW_PROOF_PLIES};
+#[cfg(feature = "render")]
+use uttt_core::render::{self, SvgOptions};
 
 use wasm_bindgen::prelude::*;
 
+fn set_panic_hook() {
+    // When the `console_error_panic_hook` feature is enabled, we can call the
+    // `set_panic_hook` function at least once during initialization, and then
+    // we will get better error messages if our code ever panics.
+    //
+    // For more details see
+    // https://github.com/rustwasm/console_error_panic_hook#readme
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+}
+
 #[wasm_bindgen]
 extern "C" {
     fn alert(s: &str);
 }
 
-static TABLES: LazyLock<(Vec<i32>, Vec<i32>)> = LazyLock::new(|| init());
+// The large and small grid lookup tables, shared by searches.
+type Tables = Arc<(Vec<Eval>, Vec<Eval>)>;
+
+// Built on first use, unless `warmup` or `warmup_step` has begun building them,
+// in which case any rows still missing are built then.
+static TABLES: LazyLock<Tables> = LazyLock::new(|| {
+    let tables = match WARMUP.lock().unwrap().take() {
+        Some(builder) => builder.finish(),
+        None => load_tables(),
+    };
+    TABLES_READY.store(true, Ordering::Relaxed);
+    Arc::new(tables)
+});
+
+// Set once `TABLES` has been built, after which there is nothing left to warm up.
+static TABLES_READY: AtomicBool = AtomicBool::new(false);
+
+// The tables being built by `warmup` or `warmup_step`, until they are complete.
+static WARMUP: LazyLock<Mutex<Option<TableBuilder>>> = LazyLock::new(|| Mutex::new(None));
+
+// The number of rows (see `TABLE_ROWS`) `warmup` builds between calls of its callback.
+const WARMUP_ROWS_PER_STEP: usize = 32;
+
+// The tables learnt from self-play loaded by `load_win_tables`, if any.
+static LEARNED_TABLES: LazyLock<Mutex<Option<Tables>>> = LazyLock::new(|| Mutex::new(None));
+
+static TT: LazyLock<Mutex<TranspositionTable>> =
+    LazyLock::new(|| Mutex::new(TranspositionTable::new(DEFAULT_HASH_MB)));
+
+static OPTIONS: LazyLock<Mutex<EngineOptions>> =
+    LazyLock::new(|| Mutex::new(EngineOptions::default()));
+
+#[cfg(feature = "book")]
+static BOOK: LazyLock<Mutex<Book>> = LazyLock::new(|| Mutex::new(Book::new()));
+
+#[cfg(feature = "book")]
+static GAMEDB: LazyLock<Mutex<GameDb>> = LazyLock::new(|| Mutex::new(GameDb::new()));
+
+static TABLEBASE: LazyLock<Mutex<Tablebase>> =
+    LazyLock::new(|| Mutex::new(Tablebase::default()));
+
+// The speed of this device, once measured (see `calibrate_speed`).
+static SPEED: LazyLock<Mutex<Option<SpeedProfile>>> = LazyLock::new(|| Mutex::new(None));
+
+static RNG: LazyLock<Mutex<Rng>> = LazyLock::new(|| Mutex::new(Rng::from_clock()));
+
+// Set by `stop` to end the current search, and cleared when a search starts.
+static STOP: AtomicBool = AtomicBool::new(false);
+
+#[wasm_bindgen]
+extern "C" {
+    // Any object with a boolean `aborted` property, such as the `signal`
+    // of an `AbortController`. A small shim object can be used instead
+    // where a real `AbortSignal` is unavailable (e.g. one backed by a
+    // `SharedArrayBuffer` so that another thread can abort the search).
+    pub type AbortSignal;
+
+    #[wasm_bindgen(method, getter)]
+    fn aborted(this: &AbortSignal) -> bool;
+}
+
+// Splits a response into the keyword array returned to JS.
+fn keywords(response: &str) -> Vec<String> {
+    response
+        .split_whitespace()
+        .map(|keyword| keyword.to_string())
+        .collect()
+}
+
+// Names a score bound as in UCI output.
+fn bound_string(bound: Bound) -> &'static str {
+    match bound {
+        Bound::Exact => "exact",
+        Bound::Lower => "lowerbound",
+        Bound::Upper => "upperbound",
+    }
+}
+
+// Statistics of a whole search which are reported alongside its result.
+struct SearchSummary {
+    nodes: u64,
+    time: u64,
+    hashfull: usize,
+}
+
+// Converts a serialisable value into the equivalent JS object.
+fn json_value<T: serde::Serialize>(value: &T) -> JsValue {
+    let json = serde_json::to_string(value).unwrap();
+    js_sys::JSON::parse(&json).unwrap()
+}
+
+// Formats the result of a completed search iteration as an info line,
+// with its score already converted to the reported perspective and formatted.
+// The `uncertainty` field is the average change in score between the last
+// few iterations, in the same units as the score, for fuzzing an eval bar.
+fn info_string(result: &SearchResult, score: &str, summary: &SearchSummary) -> String {
+    format!(
+        "info depth {} seldepth {} time {} nodes {} nps {} hashfull {} pv {} eval {} uncertainty {}",
+        result.depth,
+        result.seldepth,
+        summary.time,
+        summary.nodes,
+        nodes_per_second(summary.nodes, summary.time),
+        summary.hashfull,
+        result
+            .pv
+            .iter()
+            .take_while(|&&m| m != NULL_MOVE)
+            .map(|m| move_string(*m))
+            .collect::<Vec<_>>()
+            .join(" "),
+        score,
+        result.uncertainty,
+    )
+}
+
+// Formats the score of a search of `board` with `side` to move, as `eval_string` does,
+// except that a score of 0 which the solver proves to be a draw is written as `draw_string`
+// does, so that the page can tell a dead draw from a balanced position. Only positions
+// with at most `DRAW_PROOF_PLIES` empty cells are tried, for which the proof is quick.
+#[cfg(feature = "solver")]
+fn score_string(board: Board, side: bool, eval: Eval, depth: usize) -> String {
+    let proven = match eval {
+        engine::OUTCOME_DRAW => MateSearch::new().draw_distance(board, side, DRAW_PROOF_PLIES),
+        _ => None,
+    };
+    proven.map_or_else(|| eval_string(eval, depth), uttt_core::draw_string)
+}
+
+// Without the solver, no draw is proven.
+#[cfg(not(feature = "solver"))]
+fn score_string(_board: Board, _side: bool, eval: Eval, depth: usize) -> String {
+    eval_string(eval, depth)
+}
+
+// Formats the move to play, and the expected reply to ponder on, from a PV
+// as `bestmove <move> [ponder <move>]`, or `bestmove none` if it is empty.
+fn bestmove_string(pv: &[Move]) -> String {
+    let mut moves = pv.iter().take_while(|&&m| m != NULL_MOVE);
+    match (moves.next(), moves.next()) {
+        (Some(&best), Some(&ponder)) => {
+            format!("bestmove {} ponder {}", move_string(best), move_string(ponder))
+        }
+        (Some(&best), None) => format!("bestmove {}", move_string(best)),
+        _ => "bestmove none".to_string(),
+    }
+}
+
+// Formats the statistics of a search as a `stats` field.
+// Cutoff counts are listed by move index, up to the last non-zero count.
+// Root moves are listed as `<move>:<nodes>:<mean cutoff index>`, most nodes first,
+// with `-` for the mean of a subtree without cutoffs.
+fn stats_string(stats: &SearchStats) -> String {
+    let used = stats
+        .cutoffs
+        .iter()
+        .rposition(|&count| count != 0)
+        .map_or(1, |last| last + 1);
+    let mut fields = format!(
+        "stats cutoffs {}",
+        stats.cutoffs[..used]
+            .iter()
+            .map(|count| count.to_string())
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+    if let Some(ebf) = stats.branching_factor() {
+        fields.push_str(&format!(" ebf {ebf:.2}"));
+    }
+    if let Some(rate) = stats.tt_hit_rate() {
+        fields.push_str(&format!(" tthits {rate:.3}"));
+    }
+    if stats.tablebase_hits > 0 {
+        fields.push_str(&format!(" tbhits {}", stats.tablebase_hits));
+    }
+    let mut root_moves = stats.root_moves.clone();
+    if !root_moves.is_empty() {
+        root_moves.sort_by_key(|root| std::cmp::Reverse(root.nodes));
+        let entries: Vec<String> = root_moves
+            .iter()
+            .map(|root| {
+                let mean = root.mean_cutoff_index().map_or("-".to_string(), |m| format!("{m:.2}"));
+                format!("{}:{}:{mean}", move_string(root.mv), root.nodes)
+            })
+            .collect();
+        fields.push_str(&format!(" rootmoves {}", entries.join(",")));
+    }
+    fields
+}
+
+// Parses a board string received from JS. A zone field pointing to a won or full
+// zone is corrected to allow moves in any zone, unless `strict` is set,
+// in which case it is rejected with its own error.
+fn parse_board(board: &str, strict: bool) -> Result<Board, String> {
+    let b = board_from_string(board).ok_or_else(|| "board invalid".to_string())?;
+    if strict && !engine::zone_available(b) {
+        return Err("zone unavailable".to_string());
+    }
+    let b = engine::correct_zone(b);
+    engine::check_structure(b).map_err(|_| "board invalid".to_string())?;
+    Ok(b)
+}
+
+// Validates the depth and board arguments of `go`, returning them parsed
+// (with `infinite` as the maximum depth, and `auto` as that of `auto_depth`),
+// or the description of the error to respond with.
+pub fn parse_go_arguments(
+    depth: &str,
+    board: &str,
+    strict: bool,
+) -> Result<(usize, Board), String> {
+    // An infinite search deepens until it is stopped, or the depth limit is reached.
+    let d = match depth {
+        "infinite" => MAX_PLY,
+        "auto" => auto_depth(),
+        _ => parse_depth(depth)?,
+    };
+    Ok((d, parse_board(board, strict)?))
+}
+
+// The depth expected to answer within the `latency` option, measuring the speed
+// of the device first if `calibrate_speed` has not.
+fn auto_depth() -> usize {
+    let options = OPTIONS.lock().unwrap().clone();
+    let mut speed = SPEED.lock().unwrap();
+    let profile = speed.get_or_insert_with(|| {
+        measure_speed(&TABLES, options.eval_params, DEFAULT_SPEED_BUDGET_MS)
+    });
+    profile.depth_for_latency(options.target_latency_ms)
+}
+
+// Measures the speed of this device by searching for `budget_ms` milliseconds (or
+// a quarter of a second if it is 0), for choosing the depth of searches to depth `auto`.
+// This is otherwise done by the first such search, which it can be called beforehand
+// to spare, such as while the page loads. Responds with `speed nps <n> depth <d>`,
+// where the depth is that `auto` now stands for, given the `latency` option.
+#[wasm_bindgen]
+pub fn calibrate_speed(budget_ms: u32) -> Vec<String> {
+    set_panic_hook();
+    let options = OPTIONS.lock().unwrap().clone();
+    let budget_ms = match budget_ms {
+        0 => DEFAULT_SPEED_BUDGET_MS,
+        ms => ms as u64,
+    };
+    let profile = measure_speed(&TABLES, options.eval_params, budget_ms);
+    let response = format!(
+        "speed nps {} depth {}",
+        profile.nps,
+        profile.depth_for_latency(options.target_latency_ms),
+    );
+    *SPEED.lock().unwrap() = Some(profile);
+    keywords(&response)
+}
+
+// Parses a search depth in plies, which must be from 1 to `MAX_PLY`.
+fn parse_depth(depth: &str) -> Result<usize, String> {
+    match depth.parse::<usize>() {
+        Ok(0) | Err(_) => Err("depth invalid".to_string()),
+        Ok(d) if d > MAX_PLY => Err(format!("depth overflow {MAX_PLY}")),
+        Ok(d) => Ok(d),
+    }
+}
+
+// Passes the progress of a search to JS: to a function, before each root move as `go`
+// has always done, or to the methods of an object, each called if present with an info
+// keyword array. `onRootMove` is given `info depth <d> currmove <move> currmovenumber <n>`,
+// `onDepthComplete` is given `info depth <d> seldepth <d> time <ms> nodes <n> pv <moves>
+// eval <score>` as each iteration completes, and `onFinish` is given `finish bestmove
+// <move> nodes <n>`, or `finish none nodes <n>` if no iteration completed.
+// Errors thrown by the callbacks are deliberately ignored.
+struct JsObserver {
+    target: JsValue,
+    timer: Timer,
+    perspective: ScorePerspective,
+    x_to_move: bool,
+}
+
+impl JsObserver {
+    fn call(&self, method: &str, info: &str) {
+        let array: js_sys::Array = keywords(info).iter().map(JsValue::from).collect();
+        let function = match self.target.dyn_ref::<js_sys::Function>() {
+            Some(function) if method == "onRootMove" => function.clone(),
+            Some(_) => return,
+            None => match js_sys::Reflect::get(&self.target, &JsValue::from_str(method)) {
+                Ok(value) if value.is_function() => value.unchecked_into(),
+                _ => return,
+            },
+        };
+        let _ = function.call1(&self.target, &array);
+    }
+}
+
+impl SearchObserver for JsObserver {
+    fn on_root_move(&self, depth: usize, mv: Move, number: usize) {
+        let info = format!(
+            "info depth {depth} currmove {} currmovenumber {number}",
+            move_string(mv),
+        );
+        self.call("onRootMove", &info);
+    }
+
+    fn on_depth_complete(&self, result: &SearchResult, nodes: u64) {
+        let pv: Vec<String> =
+            result.pv.iter().take_while(|&&m| m != NULL_MOVE).map(|&m| move_string(m)).collect();
+        let eval = self.perspective.normalise(result.eval, self.x_to_move);
+        let info = format!(
+            "info depth {} seldepth {} time {} nodes {nodes} pv {} eval {}",
+            result.depth,
+            result.seldepth,
+            self.timer.elapsed_ms(),
+            pv.join(" "),
+            eval_string(eval, result.depth),
+        );
+        self.call("onDepthComplete", &info);
+    }
+
+    fn on_finish(&self, result: Option<&SearchResult>, nodes: u64) {
+        let best_move = result.map_or("none".to_string(), |result| move_string(result.pv[0]));
+        self.call("onFinish", &format!("finish bestmove {best_move} nodes {nodes}"));
+    }
+}
+
+// The penalties of the `contempt` option for the root moves of a position,
+// from the results of the book (see `Book::draw_penalties`).
+#[cfg(feature = "book")]
+fn contempt_penalties(board: Board, contempt: Eval) -> Vec<(Move, Eval)> {
+    BOOK.lock().unwrap().draw_penalties(board, contempt)
+}
+
+// Without a book, no line is known to be drawn.
+#[cfg(not(feature = "book"))]
+fn contempt_penalties(_board: Board, _contempt: Eval) -> Vec<(Move, Eval)> {
+    Vec::new()
+}
+
+// The tables searches evaluate with under the `tables` option, which are the learnt ones
+// only once they have been loaded.
+fn search_tables(options: &EngineOptions) -> Tables {
+    let learned = match options.tables {
+        TableSource::Learned => LEARNED_TABLES.lock().unwrap().clone(),
+        TableSource::Heuristic => None,
+    };
+    learned.unwrap_or_else(|| Arc::clone(&TABLES))
+}
+
+// Responds with the info line of the deepest completed iteration, followed by
+// `bestmove <move>` (or `bestmove none` if the game is over) and, when the PV
+// has a reply, `ponder <move>`.
+// `side` is `true` if X is to move. The reported score is relative to the
+// side to move unless the `perspective` option is set to `x`.
+// `depth` is a number of plies, or `infinite` to keep deepening until the
+// search is stopped by `signal` or `stop` (or reaches the maximum depth),
+// or `auto` for the deepest search expected to answer within the `latency` option
+// on this device (see `calibrate_speed`), which also stops once that time has passed.
+// With the `troll` option set, a forced win is played out by the winning move that
+// takes longest, as found by searching every root move, and the PV is that move alone.
+// If `signal` is aborted or `stop` is called during the search, the result of
+// the deepest completed iteration is returned instead of the full-depth result.
+// If `progress` is a function, it is called with an info keyword array
+// (`info depth <d> currmove <move> currmovenumber <n>`) before each root move.
+// It may instead be an object with any of the methods `onRootMove`, `onDepthComplete`
+// and `onFinish`, to follow the whole search (see `JsObserver`).
+// The search holds the engine's tables while it calls back, so the callbacks may only
+// call the exports which look at them (`hash_info`, `probe`, `probe_tablebase` and
+// `export_state`), and `set_option`, which all respond `error busy` where they would
+// need a table in use; calling any other export that searches or changes them panics.
+// A zone field pointing to a won or full zone is treated as `any`,
+// or rejected with `error zone unavailable` if the `strict` option is set.
+#[wasm_bindgen]
+pub fn go(
+    depth: &str,
+    board: &str,
+    side: bool,
+    signal: Option<AbortSignal>,
+    progress: JsValue,
+) -> Vec<String> {
+    set_panic_hook();
+    STOP.store(false, Ordering::Relaxed);
+    let options = OPTIONS.lock().unwrap().clone();
+    let tables = search_tables(&options);
+    // Parsed before the search is timed, as a depth of `auto` may first measure the device.
+    let arguments = parse_go_arguments(depth, board, options.strict);
+    let started = Timer::start();
+    let deadline = Some(options.target_latency_ms).filter(|_| depth == "auto");
+    let is_aborted = || {
+        STOP.load(Ordering::Relaxed)
+            || signal.as_ref().is_some_and(|s| s.aborted())
+            || deadline.is_some_and(|ms| started.elapsed_ms() >= ms)
+    };
+    let observer = JsObserver {
+        target: progress,
+        timer: Timer::start(),
+        perspective: options.perspective,
+        x_to_move: side,
+    };
+    let response = match arguments {
+        Err(e) => format!("error {e}"),
+        Ok(_) if is_aborted() => "error search aborted".to_string(),
+        Ok((d, b)) => {
+            let timer = Timer::start();
+            let mut tt = TT.lock().unwrap();
+            tt.new_search();
+            let tablebase = TABLEBASE.lock().unwrap();
+            let mut ctx = SearchContext::new(&*tables, d);
+            ctx.eval_params = options.eval_params;
+            ctx.eval_noise = options.eval_noise();
+            ctx.allowed_root_moves = options.safe_root_moves(b, !side);
+            ctx.root_penalties = contempt_penalties(b, options.contempt);
+            // Weakened play is meant to make mistakes, so it is not given exact results.
+            if ctx.eval_noise.is_none() {
+                ctx.tablebase = Some(&tablebase);
+            }
+            ctx.stop_condition = Some(&is_aborted);
+            if observer.target.is_object() || observer.target.is_function() {
+                ctx.observer = Some(&observer);
+            }
+            ctx.tt = Some(&mut tt);
+            if options.collect_stats {
+                ctx.stats = Some(SearchStats::new());
+            }
+            match iterative_deepening(b, !side, d, &mut ctx) {
+                Some(mut result) => {
+                    if options.troll && is_forced_win(result.eval) {
+                        if let Some((mv, eval)) = longest_win(b, !side, result.depth, &mut ctx) {
+                            result.eval = eval;
+                            result.pv = [NULL_MOVE; MAX_PLY];
+                            result.pv[0] = mv;
+                        }
+                    }
+                    let eval = options.perspective.normalise(result.eval, side);
+                    let stats = ctx.stats.take();
+                    let summary = SearchSummary {
+                        nodes: ctx.nodes,
+                        time: timer.elapsed_ms(),
+                        hashfull: tt.hashfull(),
+                    };
+                    let score = score_string(b, !side, eval, result.depth);
+                    let info = format!(
+                        "{} {}",
+                        info_string(&result, &score, &summary),
+                        bestmove_string(&result.pv),
+                    );
+                    match stats {
+                        Some(stats) => format!("{} {}", info, stats_string(&stats)),
+                        None => info,
+                    }
+                }
+                None => "error search aborted".to_string(),
+            }
+        }
+    };
+    keywords(&response)
+}
+
+// The depth of the quick search with which `go_dual` answers first.
+const PREVIEW_DEPTH: usize = 3;
+
+// Searches as `go` does, but first makes a quick search to `PREVIEW_DEPTH` plies and calls
+// `on_preview` with its result, so that the score and best move can be shown at once while
+// the full search runs. The preview is given as the keyword array `preview info ...
+// bestmove <move>`, in the form of the response of `go`. Both searches share the
+// transposition table, so the preview also orders the moves of the full search, which
+// costs little extra. There is no preview if `depth` is at most `PREVIEW_DEPTH`.
+// If `signal` is aborted or `stop` is called by `on_preview`, the full search is not made,
+// and the response is `error search aborted`. Otherwise the arguments and response
+// are those of `go`.
+#[wasm_bindgen]
+pub fn go_dual(
+    depth: &str,
+    board: &str,
+    side: bool,
+    signal: Option<AbortSignal>,
+    on_preview: Option<js_sys::Function>,
+    progress: JsValue,
+) -> Vec<String> {
+    set_panic_hook();
+    STOP.store(false, Ordering::Relaxed);
+    let is_aborted =
+        || STOP.load(Ordering::Relaxed) || signal.as_ref().is_some_and(|s| s.aborted());
+    let options = OPTIONS.lock().unwrap().clone();
+    let tables = search_tables(&options);
+    if let Ok((d, b)) = parse_go_arguments(depth, board, options.strict) {
+        if d > PREVIEW_DEPTH {
+            let timer = Timer::start();
+            let mut tt = TT.lock().unwrap();
+            tt.new_search();
+            let tablebase = TABLEBASE.lock().unwrap();
+            let mut ctx = SearchContext::new(&*tables, PREVIEW_DEPTH);
+            ctx.eval_params = options.eval_params;
+            ctx.eval_noise = options.eval_noise();
+            ctx.allowed_root_moves = options.safe_root_moves(b, !side);
+            ctx.root_penalties = contempt_penalties(b, options.contempt);
+            if ctx.eval_noise.is_none() {
+                ctx.tablebase = Some(&tablebase);
+            }
+            ctx.stop_condition = Some(&is_aborted);
+            ctx.tt = Some(&mut tt);
+            let preview = iterative_deepening(b, !side, PREVIEW_DEPTH, &mut ctx);
+            let summary = SearchSummary {
+                nodes: ctx.nodes,
+                time: timer.elapsed_ms(),
+                hashfull: tt.hashfull(),
+            };
+            // The tables are released before calling JS, and before `go` takes them again.
+            drop(tt);
+            drop(tablebase);
+            if let (Some(result), Some(callback)) = (preview, &on_preview) {
+                let eval = options.perspective.normalise(result.eval, side);
+                let info = format!(
+                    "preview {} {}",
+                    info_string(&result, &eval_string(eval, result.depth), &summary),
+                    bestmove_string(&result.pv),
+                );
+                let array: js_sys::Array = keywords(&info).iter().map(JsValue::from).collect();
+                let _ = callback.call1(&JsValue::NULL, &array);
+            }
+            if is_aborted() {
+                return keywords("error search aborted");
+            }
+        }
+    }
+    go(depth, board, side, signal, progress)
+}
+
+// Chooses a move in a game on a clock, where `remaining_ms` is the time left on the clock
+// of the side to move and `increment_ms` is added to it after each move.
+// The search deepens until its share of the remaining time is used up, and responds
+// as `go` does, followed by `mode normal`. When little time remains, a move is chosen
+// cheaply instead (from a win at once, the book, the transposition table, or a shallow
+// search), with the response `[info ...] bestmove <move> mode blitz source <source>`,
+// where the info line is only given for a search and the source is `win`, `book`, `tt`
+// or `search`. `board`, `side` and `signal` are as for `go`.
+#[wasm_bindgen]
+pub fn go_clock(
+    board: &str,
+    side: bool,
+    remaining_ms: u32,
+    increment_ms: u32,
+    signal: Option<AbortSignal>,
+) -> Vec<String> {
+    set_panic_hook();
+    STOP.store(false, Ordering::Relaxed);
+    let timer = Timer::start();
+    let options = OPTIONS.lock().unwrap().clone();
+    let tables = search_tables(&options);
+    let clock = TimeControl {
+        remaining_ms: remaining_ms as u64,
+        increment_ms: increment_ms as u64,
+        move_overhead_ms: options.move_overhead_ms,
+        params: TimeParams::DEFAULT,
+    };
+    let think_time = clock.move_time_ms();
+    let is_aborted = || {
+        STOP.load(Ordering::Relaxed)
+            || signal.as_ref().is_some_and(|s| s.aborted())
+            || timer.elapsed_ms() >= think_time
+    };
+    let response = match parse_board(board, options.strict) {
+        Err(e) => format!("error {e}"),
+        Ok(b) => {
+            let mut tt = TT.lock().unwrap();
+            tt.new_search();
+            let tablebase = TABLEBASE.lock().unwrap();
+            let mut ctx = SearchContext::new(&*tables, MAX_PLY);
+            ctx.eval_params = options.eval_params;
+            ctx.eval_noise = options.eval_noise();
+            ctx.allowed_root_moves = options.safe_root_moves(b, !side);
+            ctx.root_penalties = contempt_penalties(b, options.contempt);
+            if ctx.eval_noise.is_none() {
+                ctx.tablebase = Some(&tablebase);
+            }
+            ctx.stop_condition = Some(&is_aborted);
+            ctx.tt = Some(&mut tt);
+            let info = |result: &SearchResult, nodes: u64, hashfull: usize| {
+                let summary = SearchSummary {
+                    nodes,
+                    time: timer.elapsed_ms(),
+                    hashfull,
+                };
+                let eval = options.perspective.normalise(result.eval, side);
+                info_string(result, &score_string(b, !side, eval, result.depth), &summary)
+            };
+            if clock.is_low() {
+                #[cfg(feature = "book")]
+                let book_move = BOOK.lock().unwrap().choose(b, &mut RNG.lock().unwrap());
+                #[cfg(not(feature = "book"))]
+                let book_move = None;
+                match blitz_move(b, !side, book_move, &mut ctx) {
+                    Some(choice) => {
+                        let nodes = ctx.nodes;
+                        drop(ctx);
+                        let line = format!(
+                            "bestmove {} mode blitz source {}",
+                            move_string(choice.mv),
+                            choice.source.name(),
+                        );
+                        match choice.result {
+                            Some(result) => {
+                                format!("{} {line}", info(&result, nodes, tt.hashfull()))
+                            }
+                            None => line,
+                        }
+                    }
+                    None => "bestmove none mode blitz".to_string(),
+                }
+            } else {
+                match iterative_deepening(b, !side, MAX_PLY, &mut ctx) {
+                    Some(result) => {
+                        let nodes = ctx.nodes;
+                        drop(ctx);
+                        format!(
+                            "{} {} mode normal",
+                            info(&result, nodes, tt.hashfull()),
+                            bestmove_string(&result.pv),
+                        )
+                    }
+                    None => "error search aborted".to_string(),
+                }
+            }
+        }
+    };
+    keywords(&response)
+}
+
+// Precomputes the engine's responses to the likely replies of the side to move
+// while it is thinking, for `budget_ms` milliseconds at most, so that the engine's
+// next `go` or `go_clock` after the reply finishes almost at once. The positions after
+// each reply are searched one depth at a time, likeliest first, into the
+// transposition table. Returns when done, when the budget is used up, or when `signal`
+// is aborted or `stop` is called, responding with
+// `precomputed replies <n> depth <d> partial <n> nodes <n> time <ms>`, where `depth`
+// is the depth to which every reply was searched and `partial` the number of replies
+// searched a ply deeper. `board` and `side` are as for `go`.
+#[wasm_bindgen]
+pub fn precompute_replies(
+    board: &str,
+    side: bool,
+    budget_ms: u32,
+    signal: Option<AbortSignal>,
+) -> Vec<String> {
+    set_panic_hook();
+    STOP.store(false, Ordering::Relaxed);
+    let timer = Timer::start();
+    let is_aborted = || {
+        STOP.load(Ordering::Relaxed)
+            || signal.as_ref().is_some_and(|s| s.aborted())
+            || timer.elapsed_ms() >= budget_ms as u64
+    };
+    let options = OPTIONS.lock().unwrap().clone();
+    let tables = search_tables(&options);
+    let response = match parse_board(board, options.strict) {
+        Ok(b) => {
+            let mut tt = TT.lock().unwrap();
+            let params = options.eval_params;
+            let summary =
+                ponder::precompute_replies(b, !side, &tables, params, &mut tt, Some(&is_aborted));
+            format!(
+                "precomputed replies {} depth {} partial {} nodes {} time {}",
+                summary.replies,
+                summary.depth,
+                summary.partial,
+                summary.nodes,
+                timer.elapsed_ms(),
+            )
+        }
+        Err(e) => format!("error {e}"),
+    };
+    keywords(&response)
+}
+
+// Searches only for a forced win for the side to move within `plies` plies,
+// which is much faster than a full search to the same depth.
+// Responds with `mate <n> nodes <n> pv <moves>`, where the first number is the
+// length of the winning line in plies, or `mate none nodes <n>` if there is none.
+// `plies`, `board`, `side` and `signal` are as for `go`.
+#[cfg(feature = "solver")]
+#[wasm_bindgen]
+pub fn go_mate(plies: &str, board: &str, side: bool, signal: Option<AbortSignal>) -> Vec<String> {
+    set_panic_hook();
+    STOP.store(false, Ordering::Relaxed);
+    let is_aborted =
+        || STOP.load(Ordering::Relaxed) || signal.as_ref().is_some_and(|s| s.aborted());
+    let strict = OPTIONS.lock().unwrap().strict;
+    let response = match parse_go_arguments(plies, board, strict) {
+        Err(e) => format!("error {e}"),
+        Ok((n, b)) => {
+            let mut mate_search = MateSearch::new();
+            mate_search.stop_condition = Some(&is_aborted);
+            match mate_search.solve(b, !side, n) {
+                _ if mate_search.stopped => "error search aborted".to_string(),
+                Some(line) => format!(
+                    "mate {} nodes {} pv {}",
+                    line.len(),
+                    mate_search.nodes,
+                    line.iter().map(|&mv| move_string(mv)).collect::<Vec<_>>().join(" "),
+                ),
+                None => format!("mate none nodes {}", mate_search.nodes),
+            }
+        }
+    };
+    keywords(&response)
+}
 
+// Searches a position to `depth` plies and decides whether it has an only move: one which
+// every alternative loses at least the `onlymovemargin` option against, as for puzzles.
+// Responds with `onlymove <move> depth <d> eval <e>`, or `onlymove none ...` if there is
+// no such move. `depth`, `board`, `side` and `signal` are as for `go`.
 #[wasm_bindgen]
-pub fn go(depth: &str, board: &str, side: bool) -> Vec<String> {
+pub fn only_move(depth: &str, board: &str, side: bool, signal: Option<AbortSignal>) -> Vec<String> {
     set_panic_hook();
-    let response = if let Ok(d) = depth.parse::<usize>() {
-        if d <= 0 {
-            "error depth invalid"
-        } else if d > MAX_PLY {
-            &format!("error depth overflow {MAX_PLY}") as &str
+    STOP.store(false, Ordering::Relaxed);
+    let is_aborted =
+        || STOP.load(Ordering::Relaxed) || signal.as_ref().is_some_and(|s| s.aborted());
+    let options = OPTIONS.lock().unwrap().clone();
+    let tables = search_tables(&options);
+    let response = match parse_go_arguments(depth, board, options.strict) {
+        Err(e) => format!("error {e}"),
+        Ok((d, b)) => {
+            let mut tt = TT.lock().unwrap();
+            tt.new_search();
+            let tablebase = TABLEBASE.lock().unwrap();
+            let mut ctx = SearchContext::new(&*tables, d);
+            ctx.eval_params = options.eval_params;
+            ctx.tt = Some(&mut tt);
+            ctx.tablebase = Some(&tablebase);
+            ctx.stop_condition = Some(&is_aborted);
+            match analysis::find_only_move(b, !side, d, options.only_move_margin, &mut ctx) {
+                Some((result, mv)) => format!(
+                    "onlymove {} depth {} eval {}",
+                    mv.map_or_else(|| "none".to_string(), move_string),
+                    result.depth,
+                    eval_string(options.perspective.normalise(result.eval, side), result.depth),
+                ),
+                None => "error search aborted".to_string(),
+            }
+        }
+    };
+    keywords(&response)
+}
+
+// Analyzes every move of a game given as a transcript of moves from the empty
+// board (such as `c/c c/nw`), searching each position to `depth` plies.
+// Returns an array with an object for each move, holding the move played,
+// the best move, the depth, both their scores (formatted, and as numbers relative
+// to the side to move), the score lost, the principal variation of the best move,
+// an annotation symbol (`!!`, `!`, `?!`, `?`, `??` or null), whether it was
+// the only good move, and the highlights of the position before it.
+// The result can be saved with the game by `save_analysis`.
+// The highlights hold an `arrow` from the cell of the best move to the centre of the zone
+// it sends the opponent to (each as `[row, column]` of the 9x9 grid, or null if the opponent
+// may play anywhere), that zone as `sends_to`, and the `threats` of both sides:
+// the empty cells which would win their zone, each with its `cell`, `at` and `side`.
+// Returns an object with an `error` description if the arguments are invalid
+// or `signal` is aborted.
+#[wasm_bindgen]
+pub fn analyze_game(moves: &str, depth: &str, signal: Option<AbortSignal>) -> JsValue {
+    set_panic_hook();
+    let is_aborted = || signal.as_ref().is_some_and(|s| s.aborted());
+    let options = OPTIONS.lock().unwrap().clone();
+    let tables = search_tables(&options);
+    let result = parse_depth(depth).and_then(|d| {
+        let game = Game::from_transcript(moves).map_err(|e| e.to_string())?;
+        analysis::analyze_game(
+            &game,
+            d,
+            &tables,
+            options.eval_params,
+            options.hash_mb,
+            options.perspective,
+            Some(&is_aborted),
+        )
+        .ok_or_else(|| "search aborted".to_string())
+    });
+    match result {
+        Ok(analysis) => json_value(&analysis),
+        Err(e) => json_value(&serde_json::json!({ "error": e })),
+    }
+}
+
+// Analyzes many positions in one call, each searched to `depth` plies,
+// with the side to move inferred from the number of marks.
+// Returns an array with an object for each board, in order, holding its board string,
+// the side to move (`x` or `o`), the depth reached, the best move, the score,
+// the principal variation, the number of nodes searched and the highlights
+// of the position, as described for `analyze_game`.
+// If `share_tt` is set, the engine's transposition table is used (and kept) throughout,
+// which helps where the positions are related, such as those of a single game.
+// Otherwise each position is searched with a separate, cleared table, so that
+// its result does not depend on the others.
+// Returns an object with an `error` description if any argument is invalid
+// or `signal` is aborted.
+#[wasm_bindgen]
+pub fn analyze_batch(
+    boards: Vec<String>,
+    depth: &str,
+    share_tt: Option<bool>,
+    signal: Option<AbortSignal>,
+) -> JsValue {
+    set_panic_hook();
+    let is_aborted = || signal.as_ref().is_some_and(|s| s.aborted());
+    let options = OPTIONS.lock().unwrap().clone();
+    let tables = search_tables(&options);
+    let result = parse_depth(depth).and_then(|d| {
+        let boards = boards
+            .iter()
+            .enumerate()
+            .map(|(index, board)| {
+                parse_board(board, options.strict).map_err(|e| format!("{e} index {index}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let share_tt = share_tt.unwrap_or(false);
+        let (mut shared, mut separate);
+        let tt: &mut TranspositionTable = if share_tt {
+            shared = TT.lock().unwrap();
+            &mut shared
         } else {
-            if let Some(b) = board_from_string(board) {
-                let (eval, line) = alpha_beta(
+            separate = TranspositionTable::new(options.hash_mb);
+            &mut separate
+        };
+        boards
+            .into_iter()
+            .map(|b| {
+                if !share_tt {
+                    tt.clear();
+                }
+                analysis::analyze_position(
                     b,
-                    !side,
                     d,
-                    OUTCOME_LOSS,
-                    OUTCOME_WIN,
-                    &(*TABLES),
-                    d,
-                );
-                &format!(
-                    "info depth {} pv {} eval {}",
-                    d,
-                    line
-                        .iter()
-                        .take_while(|&&m| m != NULL_MOVE)
-                        .map(|m| move_string(*m))
-                        .collect::<Vec<_>>()
-                        .join(" "),
-                    eval_string(eval, d),
-                ) as &str
-            } else {
-                "error board invalid"
+                    &tables,
+                    options.eval_params,
+                    tt,
+                    options.perspective,
+                    Some(&is_aborted),
+                )
+                .filter(|_| !is_aborted())
+                .ok_or_else(|| "search aborted".to_string())
+            })
+            .collect::<Result<Vec<_>, _>>()
+    });
+    match result {
+        Ok(analysis) => json_value(&analysis),
+        Err(e) => json_value(&serde_json::json!({ "error": e })),
+    }
+}
+
+// Aggregates game records, one per line as `<result> <moves>` (for example
+// `1-0 c/c c/nw nw/c`, with results `1-0`, `0-1` or `1/2-1/2`), into an opening tree
+// covering the first `max_plies` moves of each game. Symmetric positions are merged.
+// Records may begin with a version tag such as `v1`; untagged records are version 1.
+// Returns an array with an object for each position, holding its canonical board
+// string, the side to move, the number of games, and the moves played from it
+// with their game counts, results and average score for the side that played them.
+// Returns an object with an `error` description if any argument is invalid.
+#[cfg(feature = "book")]
+#[wasm_bindgen]
+pub fn opening_tree(records: &str, max_plies: &str) -> JsValue {
+    set_panic_hook();
+    let result = match max_plies.parse::<usize>() {
+        Ok(plies) => opening_tree_from_records(records, plies),
+        Err(_) => Err("plies invalid".to_string()),
+    };
+    match result {
+        Ok(tree) => json_value(&tree.export()),
+        Err(e) => json_value(&serde_json::json!({ "error": e })),
+    }
+}
+
+// Feeds a finished game back into the book, given as a record `<result> <moves>`
+// (as for `opening_tree`). Moves of the winning side become more likely to be
+// chosen by `book_move`, and those of the losing side less likely, with older
+// games counting for progressively less. Returns `ok` or an error description.
+#[cfg(feature = "book")]
+#[wasm_bindgen]
+pub fn learn_game(record: &str) -> Vec<String> {
+    let response = match parse_game_record(record) {
+        Ok((game, result)) => {
+            BOOK.lock().unwrap().learn(&game, result);
+            "ok".to_string()
+        }
+        Err(e) => format!("error {e}"),
+    };
+    keywords(&response)
+}
+
+// Returns the book as bytes, in the format documented in `book`, for keeping
+// in browser storage.
+#[cfg(feature = "book")]
+#[wasm_bindgen]
+pub fn book_export() -> Vec<u8> {
+    BOOK.lock().unwrap().to_bytes()
+}
+
+// Replaces the book with one from bytes produced by `book_export`, such as those kept
+// in browser storage, returning `ok positions <n>` or an error description,
+// in which case the book is unchanged.
+#[cfg(feature = "book")]
+#[wasm_bindgen]
+pub fn book_import(bytes: &[u8]) -> Vec<String> {
+    keywords(&replace_book(bytes))
+}
+
+// Loads a book built offline by `uttt-rust book`, which the frontend fetches as a static
+// asset only when a page needs it, so that the book is not carried in the wasm binary.
+// Any book already loaded or learnt is replaced. Returns as `book_import` does.
+#[cfg(feature = "book")]
+#[wasm_bindgen]
+pub fn load_book(bytes: &[u8]) -> Vec<String> {
+    keywords(&replace_book(bytes))
+}
+
+// Discards the book, freeing its memory, after which `book_move` finds no moves
+// until a book is loaded or games are learnt again. Returns `ok`.
+#[cfg(feature = "book")]
+#[wasm_bindgen]
+pub fn unload_book() -> Vec<String> {
+    *BOOK.lock().unwrap() = Book::new();
+    keywords("ok")
+}
+
+#[cfg(feature = "book")]
+fn replace_book(bytes: &[u8]) -> String {
+    match Book::from_bytes(bytes) {
+        Ok(book) => {
+            let count = book.len();
+            *BOOK.lock().unwrap() = book;
+            format!("ok positions {count}")
+        }
+        Err(e) => format!("error {e}"),
+    }
+}
+
+// Chooses a move for the position from the book, at random with
+// probability weighted by the results of earlier games, returning
+// `bookmove <move>`, or `bookmove none` if the position is not in the book.
+#[cfg(feature = "book")]
+#[wasm_bindgen]
+pub fn book_move(board: &str) -> Vec<String> {
+    let strict = OPTIONS.lock().unwrap().strict;
+    let response = match parse_board(board, strict) {
+        Ok(b) => match BOOK.lock().unwrap().choose(b, &mut RNG.lock().unwrap()) {
+            Some(mv) => format!("bookmove {}", move_string(mv)),
+            None => "bookmove none".to_string(),
+        },
+        Err(e) => format!("error {e}"),
+    };
+    keywords(&response)
+}
+
+// Loads a tablebase built offline by `uttt-rust tablebase`, which the frontend fetches
+// as a static asset when it wants the engine to play late positions perfectly. Searches
+// then score the positions it holds exactly, unless weakened by the `evalnoise` option.
+// Any tablebase already loaded is replaced. Returns `ok positions <n>` or an error
+// description, in which case the tablebase is unchanged.
+#[wasm_bindgen]
+pub fn load_tablebase(bytes: &[u8]) -> Vec<String> {
+    let response = match Tablebase::from_bytes(bytes) {
+        Ok(tablebase) => {
+            let count = tablebase.len();
+            *TABLEBASE.lock().unwrap() = tablebase;
+            format!("ok positions {count}")
+        }
+        Err(e) => format!("error {e}"),
+    };
+    keywords(&response)
+}
+
+// Discards the tablebase, freeing its memory. Returns `ok`.
+#[wasm_bindgen]
+pub fn unload_tablebase() -> Vec<String> {
+    *TABLEBASE.lock().unwrap() = Tablebase::default();
+    keywords("ok")
+}
+
+// Loads lookup tables learnt from self-play (see `uttt-rust win-tables`), typically
+// a static asset fetched by the page, which searches then evaluate with under the option
+// `tables learned`. Any tables already loaded are replaced, and under that option the
+// transposition table is cleared. Returns `ok` or an error description, in which case
+// the tables are unchanged.
+#[wasm_bindgen]
+pub fn load_win_tables(bytes: &[u8]) -> Vec<String> {
+    let response = match win_tables::from_bytes(bytes) {
+        Ok(tables) => {
+            *LEARNED_TABLES.lock().unwrap() = Some(Arc::new(tables));
+            clear_learned_scores();
+            "ok".to_string()
+        }
+        Err(e) => format!("error {e}"),
+    };
+    keywords(&response)
+}
+
+// Discards the learnt tables, freeing their memory, after which searches evaluate with
+// the heuristic tables whatever the option `tables`. Under `tables learned`, this clears
+// the transposition table. Returns `ok`.
+#[wasm_bindgen]
+pub fn unload_win_tables() -> Vec<String> {
+    *LEARNED_TABLES.lock().unwrap() = None;
+    clear_learned_scores();
+    keywords("ok")
+}
+
+// Empties the transposition table if searches evaluate with the learnt tables,
+// whose scores in it no longer hold once those tables are replaced or discarded.
+fn clear_learned_scores() {
+    if OPTIONS.lock().unwrap().tables == TableSource::Learned {
+        TT.lock().unwrap().clear();
+    }
+}
+
+// Looks a position up in the tablebase, responding with `tablebase <win|draw|loss> distance
+// <plies> bestmove <move>`, the exact result for the side to move, the plies until the game
+// is decided with best play, and a move achieving it (or `none` if the game is over, or
+// the tablebase lacks the positions after the moves). Responds with `tablebase none`
+// if the tablebase does not hold the position, or `error busy` while a search holds it.
+// `board` and `side` are as for `go`.
+#[wasm_bindgen]
+pub fn probe_tablebase(board: &str, side: bool) -> Vec<String> {
+    let strict = OPTIONS.lock().unwrap().strict;
+    let response = match parse_board(board, strict) {
+        Ok(b) => {
+            let tablebase = match TABLEBASE.try_lock() {
+                Ok(tablebase) => tablebase,
+                Err(_) => return keywords("error busy"),
+            };
+            match tablebase.probe(b, !side) {
+                Some(entry) => {
+                    let wdl = match entry.wdl {
+                        Wdl::Win => "win",
+                        Wdl::Draw => "draw",
+                        Wdl::Loss => "loss",
+                    };
+                    let best = tablebase.best_move(b, !side);
+                    format!(
+                        "tablebase {wdl} distance {} bestmove {}",
+                        entry.distance,
+                        best.map_or_else(|| "none".to_string(), move_string),
+                    )
+                }
+                None => "tablebase none".to_string(),
             }
         }
-    } else {
-        "error depth invalid"
+        Err(e) => format!("error {e}"),
     };
-    response
-        .split_whitespace()
-        .map(|keyword| keyword.to_string())
-        .collect()
+    keywords(&response)
+}
+
+// Searches every legal move to `depth` plies in total, sharing the transposition table
+// between them, for hints, heatmaps and choosing moves as a weaker player would.
+// Returns an array with an object for each move, best first, holding the `move`,
+// its `score` as a number and as an `eval` string, relative to the side to move
+// (or to X if the `perspective` option is set to `x`), and its `pv`, starting with it.
+// The array is empty once the game is over. Returns an object with an `error`
+// description if the arguments are invalid or `signal` is aborted.
+// `board`, `side` and `signal` are as for `go`.
+#[wasm_bindgen]
+pub fn evaluate_children(
+    board: &str,
+    side: bool,
+    depth: &str,
+    signal: Option<AbortSignal>,
+) -> JsValue {
+    set_panic_hook();
+    STOP.store(false, Ordering::Relaxed);
+    let is_aborted =
+        || STOP.load(Ordering::Relaxed) || signal.as_ref().is_some_and(|s| s.aborted());
+    let options = OPTIONS.lock().unwrap().clone();
+    let tables = search_tables(&options);
+    let result = parse_depth(depth).and_then(|d| {
+        let b = parse_board(board, options.strict)?;
+        let mut tt = TT.lock().unwrap();
+        tt.new_search();
+        let tablebase = TABLEBASE.lock().unwrap();
+        let mut ctx = SearchContext::new(&*tables, d);
+        ctx.eval_params = options.eval_params;
+        ctx.stop_condition = Some(&is_aborted);
+        ctx.tt = Some(&mut tt);
+        ctx.tablebase = Some(&tablebase);
+        let lines = root_move_lines(b, !side, d, &mut ctx).ok_or("search aborted")?;
+        let children: Vec<serde_json::Value> = lines
+            .iter()
+            .map(|(mv, eval, line)| {
+                let score = options.perspective.normalise(*eval, side);
+                let pv: Vec<String> =
+                    line.iter().take_while(|&&m| m != NULL_MOVE).map(|&m| move_string(m)).collect();
+                serde_json::json!({
+                    "move": move_string(*mv),
+                    "score": score,
+                    "eval": eval_string(score, d),
+                    "pv": pv,
+                })
+            })
+            .collect();
+        Ok(children)
+    });
+    match result {
+        Ok(children) => json_value(&children),
+        Err(e) => json_value(&serde_json::json!({ "error": e })),
+    }
+}
+
+// Chooses a move as a human player of the given rating (from 400 to 2400) might,
+// by searching every move to `depth` plies and choosing at random, favouring
+// better moves more strongly at higher ratings. Moves which let the opponent win
+// a zone or the game with its reply are never chosen if another move does not,
+// unless the `blunders` option is set. Responds with
+// `bestmove <move> eval <score>`, where the score is that of the chosen move,
+// or an error as for `go`. `board` and `side` are as for `go`.
+#[wasm_bindgen]
+pub fn go_human(depth: &str, board: &str, side: bool, rating: u32) -> Vec<String> {
+    set_panic_hook();
+    let options = OPTIONS.lock().unwrap().clone();
+    let tables = search_tables(&options);
+    let arguments = parse_depth(depth).and_then(|d| Ok((d, parse_board(board, options.strict)?)));
+    let response = match arguments {
+        Err(e) => format!("error {e}"),
+        Ok((d, b)) => {
+            let mut tt = TT.lock().unwrap();
+            tt.new_search();
+            let mut ctx = SearchContext::new(&*tables, d);
+            ctx.eval_params = options.eval_params;
+            ctx.eval_noise = options.eval_noise();
+            ctx.tt = Some(&mut tt);
+            let temperature = humanlike::temperature_for_rating(rating);
+            let choice = root_move_scores(b, !side, d, &mut ctx).and_then(|mut scores| {
+                if !options.allow_blunders {
+                    let moves: Vec<Move> = scores.iter().map(|&(mv, _)| mv).collect();
+                    let safe = humanlike::safest_moves(b, !side, &moves);
+                    scores.retain(|(mv, _)| safe.contains(mv));
+                }
+                let mv = humanlike::choose_move(&scores, temperature, &mut RNG.lock().unwrap())?;
+                scores.into_iter().find(|&(m, _)| m == mv)
+            });
+            match choice {
+                Some((mv, eval)) => format!(
+                    "bestmove {} eval {}",
+                    move_string(mv),
+                    eval_string(options.perspective.normalise(eval, side), d),
+                ),
+                None => "bestmove none".to_string(),
+            }
+        }
+    };
+    keywords(&response)
+}
+
+// Decides whether the bot playing `side` (`true` for X) should resign or offer
+// a draw, given the board after its latest move and the scores reported by `go`
+// for each of its moves so far, oldest first, separated by spaces.
+// Scores are read according to the `perspective` option, as `go` reported them.
+// Responds with `adjudication resign`, `adjudication draw` or `adjudication none`,
+// following the `resignscore`, `resignmoves`, `drawscore` and `drawmoves` options.
+#[wasm_bindgen]
+pub fn adjudicate(board: &str, side: bool, evals: &str) -> Vec<String> {
+    let options = OPTIONS.lock().unwrap().clone();
+    let evals: Option<Vec<Eval>> = evals.split_whitespace().map(eval_from_string).collect();
+    let response = match (parse_board(board, options.strict), evals) {
+        (Err(e), _) => format!("error {e}"),
+        (_, None) => "error eval invalid".to_string(),
+        (Ok(b), Some(evals)) => {
+            let (x_count, o_count) = engine::mark_counts(b);
+            let ply = (x_count + o_count) as usize;
+            let mut adjudicator = Adjudicator::new(options.adjudication);
+            let adjudication = evals
+                .into_iter()
+                .map(|eval| options.perspective.normalise(eval, side))
+                .map(|eval| adjudicator.record(!side, eval, b, ply))
+                .last()
+                .flatten();
+            match adjudication {
+                Some(Adjudication::Resign(_)) => "adjudication resign".to_string(),
+                Some(Adjudication::Draw) => "adjudication draw".to_string(),
+                None => "adjudication none".to_string(),
+            }
+        }
+    };
+    keywords(&response)
+}
+
+// Classifies a proposed move for the beginner coach mode, with the side to move
+// inferred from the number of marks. Returns an object with a `classification`
+// (`best`, `good`, `inaccuracy`, `mistake` or `blunder`), an array of `reasons`
+// (`wins_game`, `misses_game_win`, `allows_game_win`, `wins_zone`, `misses_zone_win`,
+// `sends_to_zone_win`, `grants_free_move` and `creates_large_fork`), the `best` move
+// and the score `loss` against it. Returns an object with an `error` description
+// if the board or move is invalid, or the move is illegal.
+#[wasm_bindgen]
+pub fn coach(board: &str, mv: &str) -> JsValue {
+    set_panic_hook();
+    let options = OPTIONS.lock().unwrap().clone();
+    let tables = search_tables(&options);
+    let result = parse_board(board, options.strict).and_then(|b| {
+        let m = move_from_string(mv).ok_or_else(|| "move invalid".to_string())?;
+        if !engine::generate_moves(b).any(|legal| legal == m) {
+            return Err("move illegal".to_string());
+        }
+        let (x_count, o_count) = engine::mark_counts(b);
+        let mut tt = TT.lock().unwrap();
+        let side = x_count > o_count;
+        coach::coach_move(b, m, side, &tables, options.eval_params, &mut tt, None)
+            .ok_or_else(|| "search aborted".to_string())
+    });
+    match result {
+        Ok(report) => json_value(&report),
+        Err(e) => json_value(&serde_json::json!({ "error": e })),
+    }
+}
+
+// Finds the moves to warn a beginner about, with the side to move inferred from
+// the number of marks. Returns an object with the `best` move, the `worst` move,
+// and the `traps`: moves which look natural by a shallow search, but which a deeper
+// search finds to be mistakes or blunders, worst first. Each of these is an object
+// with the `move`, its `classification` and `reasons` (as for `coach`), and its score
+// `loss` against the best move by the deeper search, and `natural_loss` by the shallow one.
+// Returns an object with an `error` description if the board is invalid or has no moves.
+#[wasm_bindgen]
+pub fn traps(board: &str) -> JsValue {
+    set_panic_hook();
+    let options = OPTIONS.lock().unwrap().clone();
+    let tables = search_tables(&options);
+    let result = parse_board(board, options.strict).and_then(|b| {
+        let (x_count, o_count) = engine::mark_counts(b);
+        let mut tt = TT.lock().unwrap();
+        coach::find_traps(b, x_count > o_count, &tables, options.eval_params, &mut tt, None)
+            .ok_or_else(|| "no legal moves".to_string())
+    });
+    match result {
+        Ok(report) => json_value(&report),
+        Err(e) => json_value(&serde_json::json!({ "error": e })),
+    }
+}
+
+// Builds up to `rows` more rows of the tables, returning the percentage built,
+// which is 100 once they are ready for use. Embedded tables need only be decoded.
+fn build_tables(rows: usize) -> u32 {
+    if !TABLES_READY.load(Ordering::Relaxed) && !cfg!(feature = "embedded-tables") {
+        let mut warmup = WARMUP.lock().unwrap();
+        let builder = warmup.get_or_insert_with(|| TableBuilder::new(&EvalParams::DEFAULT));
+        if !builder.step(rows) {
+            return (builder.progress() * 100.0) as u32;
+        }
+    }
+    LazyLock::force(&TABLES);
+    100
+}
+
+// Builds the evaluation tables now, rather than in the first call that needs them,
+// which on slow devices takes long enough to be noticed, so that the page can choose
+// when to pay for it and show that it is loading. If `on_progress` is given, it is called
+// with the keyword array `warmup progress <percent>` after each step of the build.
+// Responds with `warmup done`, at once if the tables are already built.
+#[wasm_bindgen]
+pub fn warmup(on_progress: Option<js_sys::Function>) -> Vec<String> {
+    set_panic_hook();
+    loop {
+        let percent = build_tables(WARMUP_ROWS_PER_STEP);
+        if let Some(callback) = &on_progress {
+            let info = format!("warmup progress {percent}");
+            let array: js_sys::Array = keywords(&info).iter().map(JsValue::from).collect();
+            let _ = callback.call1(&JsValue::NULL, &array);
+        }
+        if percent == 100 {
+            return keywords("warmup done");
+        }
+    }
+}
+
+// Builds the tables a step at a time, `rows` rows of 512 per call, for a page running the
+// engine on its own thread, which can make each call from a timer and stay responsive
+// in between. A search started before the build is complete finishes it first.
+// Responds with `warmup progress <percent>`, or `warmup done` once complete.
+#[wasm_bindgen]
+pub fn warmup_step(rows: u32) -> Vec<String> {
+    set_panic_hook();
+    match build_tables(rows.max(1) as usize) {
+        100 => keywords("warmup done"),
+        percent => keywords(&format!("warmup progress {percent}")),
+    }
+}
+
+// Stops the search in progress, which then returns the result of its deepest
+// completed iteration. This is for callers which run `go` on another thread
+// with shared memory; otherwise the `signal` argument of `go` is used.
+#[wasm_bindgen]
+pub fn stop() {
+    STOP.store(true, Ordering::Relaxed);
+}
+
+// Reports the size of the transposition table in megabytes and entries,
+// and how full it is in permille, or `error busy` while a search holds it.
+#[wasm_bindgen]
+pub fn hash_info() -> Vec<String> {
+    let tt = match TT.try_lock() {
+        Ok(tt) => tt,
+        Err(_) => return keywords("error busy"),
+    };
+    keywords(&format!(
+        "hash size {} entries {} hashfull {}",
+        tt.megabytes(),
+        tt.capacity(),
+        tt.hashfull(),
+    ))
+}
+
+// Returns any transposition table entry stored for the position, as
+// `tt depth <d> eval <score> bound <exact|lowerbound|upperbound> bestmove <move>`,
+// or `tt none` if the position has not been searched (or has been overwritten),
+// or `error busy` while a search holds the table.
+// `side` is `true` if X is to move, and the score follows the `perspective` option.
+#[wasm_bindgen]
+pub fn probe(board: &str, side: bool) -> Vec<String> {
+    let options = OPTIONS.lock().unwrap().clone();
+    let response = match parse_board(board, options.strict) {
+        Ok(b) => match TT.try_lock().map(|tt| tt.probe(zobrist::hash_position(b, !side))) {
+            Err(_) => "error busy".to_string(),
+            Ok(Some(entry)) => format!(
+                "tt depth {} eval {} bound {} bestmove {}",
+                entry.depth,
+                eval_string(options.perspective.normalise(entry.score, side), MAX_PLY),
+                bound_string(entry.bound),
+                move_string(entry.best_move()),
+            ),
+            Ok(None) => "tt none".to_string(),
+        },
+        Err(e) => format!("error {e}"),
+    };
+    keywords(&response)
+}
+
+// Returns an object with the engine's name, version, supported variants,
+// search features, options and build features, and `strength`, the approximate
+// rating of each search depth as `{depth, rating, label}` objects, for labelling bot levels.
+#[wasm_bindgen]
+pub fn engine_info() -> JsValue {
+    let mut info = info::engine_info();
+    if cfg!(feature = "console_error_panic_hook") {
+        info.features.push("console_error_panic_hook");
+    }
+    json_value(&info)
+}
+
+// Returns an object with the engine constants that frontends depend on:
+// `max_ply`, the `outcome_*` scores, `null_move`, `zone_any`,
+// and the zone names in index order (`zones` and `zones_upper`).
+#[wasm_bindgen]
+pub fn engine_constants() -> JsValue {
+    json_value(&info::engine_constants())
+}
+
+// Runs a fixed set of perfts and searches, returning `selftest digest <hex>`.
+// The digest must match that of the native reference build.
+#[wasm_bindgen]
+pub fn selftest() -> Vec<String> {
+    keywords(&format!("selftest digest {:016x}", selftest::selftest(&TABLES)))
+}
+
+// Resets all search state carried between calls, so that a new game
+// (or a reproducible analysis) is unaffected by earlier searches.
+// Options set with `set_option` are kept.
+#[wasm_bindgen]
+pub fn new_game() {
+    TT.lock().unwrap().clear();
+}
+
+// Serialises the engine's accumulated search results to bytes,
+// so that the frontend can persist them between sessions.
+// Returns no bytes while a search holds the table.
+#[wasm_bindgen]
+pub fn export_state() -> Vec<u8> {
+    TT.try_lock().map_or_else(|_| Vec::new(), |tt| tt.export())
+}
+
+// Restores search results from bytes produced by `export_state`,
+// returning `ok entries <n>` or an error description.
+#[wasm_bindgen]
+pub fn import_state(bytes: &[u8]) -> Vec<String> {
+    let response = match TT.lock().unwrap().import(bytes) {
+        Ok(count) => format!("ok entries {count}"),
+        Err(e) => format!("error {e}"),
+    };
+    keywords(&response)
+}
+
+// Sets a persistent engine option, returning `ok` or an error description,
+// which is `error busy` if the option needs the transposition table reset while
+// a search holds it.
+// Setting `hash` (in megabytes) reallocates the transposition table,
+// discarding its contents. `moveoverhead` is the number of milliseconds
+// `go_clock` takes from the time for each move, to allow for latency.
+// `onlymovemargin` is how much every alternative must lose for `only_move` to report a move.
+// `troll` makes `go` draw out a won game rather than win it as quickly as possible.
+// `tempo` is the bonus given to the side to move when positions are evaluated.
+// Changing it clears the transposition table, whose scores were found with the old bonus.
+// `evalnoise` weakens `go`, `go_clock` and `go_human` for lower difficulty levels by adding
+// random noise of that standard deviation to evaluations, fading out towards the end
+// of the game, and `noiseseed` seeds it, so that games can be reproduced or varied.
+// However noisy, these searches only play a move which lets the opponent win a zone
+// or the game with its reply when every other move does too, unless `blunders` is set.
+// `latency` is the time in milliseconds that searches to depth `auto` aim to answer in.
+// `contempt` makes `go` and `go_clock` play for a win by scoring moves whose games
+// in the book were mostly drawn up to that much lower, the more so the more were drawn.
+// `tables` is `heuristic` or `learned`: with `learned`, searches evaluate with the tables
+// learnt from self-play, once loaded by `load_win_tables`, instead of the heuristic ones.
+// Changing it clears the transposition table, as its scores were found with the other tables.
+#[wasm_bindgen]
+pub fn set_option(name: &str, value: &str) -> Vec<String> {
+    let mut options = OPTIONS.lock().unwrap();
+    let mut updated = options.clone();
+    let response = match updated.set(name, value) {
+        Ok(()) => {
+            let stale =
+                updated.tables != options.tables || updated.eval_params != options.eval_params;
+            // The option is only set once the table is reset, which a search calling back
+            // from its progress callbacks prevents by holding the table.
+            if name == "hash" || stale {
+                match TT.try_lock() {
+                    Ok(mut tt) if name == "hash" => *tt = TranspositionTable::new(updated.hash_mb),
+                    Ok(mut tt) => tt.clear(),
+                    Err(_) => return keywords("error busy"),
+                }
+            }
+            *options = updated;
+            "ok".to_string()
+        }
+        Err(e) => format!("error {e}"),
+    };
+    keywords(&response)
 }
 
 #[wasm_bindgen]
 pub fn serialise_board(board_value: &str) -> String {
-    let segments: Vec<&str> = board_value.split_whitespace().collect();
-    if segments.len() != 3 {
-        "invalid".to_string()
-    } else if let (Ok(us), Ok(them), Ok(share)) =
-        (segments[0].parse::<u64>(), segments[1].parse::<u64>(), segments[2].parse::<u64>())
+    if let Some(b) = board_from_values(board_value)
+        .filter(|&b| engine::check_structure(b).is_ok())
     {
-        board_string((us, them, share))
+        board_string(b)
+    } else {
+        "invalid".to_string()
+    }
+}
+
+// A game being reviewed, which can be stepped through without replaying it from the
+// start on every navigation. Moves are returned as move strings, or `none` where there is
+// no such move, and boards as board strings.
+#[wasm_bindgen]
+pub struct Replay {
+    inner: replay::Replay,
+}
+
+#[wasm_bindgen]
+impl Replay {
+    // Creates a replay at the initial position of a game given as a transcript of moves,
+    // or returns `undefined` if the transcript is invalid.
+    pub fn from_transcript(moves: &str) -> Option<Replay> {
+        replay::Replay::from_transcript(moves)
+            .ok()
+            .map(|inner| Replay { inner })
+    }
+
+    // The number of moves played to reach the current position.
+    pub fn ply(&self) -> usize {
+        self.inner.ply()
+    }
+
+    // The number of moves in the game.
+    pub fn length(&self) -> usize {
+        self.inner.len()
+    }
+
+    // Moves to the position after `ply` moves, returning whether it exists.
+    pub fn seek(&mut self, ply: usize) -> bool {
+        self.inner.seek(ply)
+    }
+
+    // Steps forward a move, returning the move played.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> String {
+        move_string(self.inner.next().unwrap_or(NULL_MOVE))
+    }
+
+    // Steps back a move, returning the move undone.
+    pub fn prev(&mut self) -> String {
+        move_string(self.inner.prev().unwrap_or(NULL_MOVE))
+    }
+
+    pub fn board(&self) -> String {
+        board_string(self.inner.board())
+    }
+
+    // Whether X is to move, as for the `side` argument of other functions.
+    pub fn side(&self) -> bool {
+        !self.inner.side_to_move()
+    }
+
+    // The move which led to the current position.
+    pub fn current_move(&self) -> String {
+        move_string(self.inner.current_move().unwrap_or(NULL_MOVE))
+    }
+
+    // The move played from the current position.
+    pub fn next_move(&self) -> String {
+        move_string(self.inner.next_move().unwrap_or(NULL_MOVE))
+    }
+
+    // The cached analysis of the current position, as from `analyze`, or null.
+    pub fn cached_analysis(&self) -> JsValue {
+        json_value(&self.inner.cached_analysis())
+    }
+
+    // Analyzes the current position to `depth` plies, returning an object as for
+    // `analyze_batch`. The analysis is cached, so the position is only searched again
+    // for a greater depth or a different score perspective. Returns an object with
+    // an `error` description if the depth is invalid or `signal` is aborted.
+    pub fn analyze(&mut self, depth: &str, signal: Option<AbortSignal>) -> JsValue {
+        set_panic_hook();
+        let is_aborted = || signal.as_ref().is_some_and(|s| s.aborted());
+        let options = OPTIONS.lock().unwrap().clone();
+        let tables = search_tables(&options);
+        let result = parse_depth(depth).and_then(|d| {
+            let mut tt = TT.lock().unwrap();
+            let (params, perspective) = (options.eval_params, options.perspective);
+            self.inner
+                .analyze(d, &tables, params, &mut tt, perspective, Some(&is_aborted))
+                .map(json_value)
+                .ok_or_else(|| "search aborted".to_string())
+        });
+        result.unwrap_or_else(|e| json_value(&serde_json::json!({ "error": e })))
+    }
+}
+
+// Encodes a game, given as a transcript of moves from the empty board,
+// as a short URL-safe token for shareable links.
+// Returns `error <description>` if the transcript is invalid.
+#[wasm_bindgen]
+pub fn encode_state_url(moves: &str) -> String {
+    match Game::from_transcript(moves) {
+        Ok(game) => state_url::encode_state_url(&game),
+        Err(e) => format!("error {e}"),
+    }
+}
+
+// Decodes a token made by `encode_state_url`, including by earlier versions,
+// returning an object with the transcript of its `moves`, the resulting `board`
+// and the `side` to move (`x` or `o`), or an `error` description.
+#[wasm_bindgen]
+pub fn decode_state_url(token: &str) -> JsValue {
+    match state_url::decode_state_url(token) {
+        Ok(game) => json_value(&serde_json::json!({
+            "moves": game.transcript(),
+            "board": board_string(game.board()),
+            "side": if game.side_to_move() { "o" } else { "x" },
+        })),
+        Err(e) => json_value(&serde_json::json!({ "error": e })),
+    }
+}
+
+// Encodes a board and the side to move (`true` for X) as 24 bytes, for bulk storage.
+// Returns an empty array if the board is invalid.
+#[wasm_bindgen]
+pub fn board_to_bytes(board: &str, side: bool) -> Vec<u8> {
+    let strict = OPTIONS.lock().unwrap().strict;
+    match parse_board(board, strict) {
+        Ok(b) => uttt_core::board_to_bytes(b, !side).to_vec(),
+        Err(_) => Vec::new(),
+    }
+}
+
+// Decodes a board encoded by `board_to_bytes`, returning an object with its `board` string
+// and the `side` to move (`x` or `o`), or an `error` description if the bytes are invalid.
+#[wasm_bindgen]
+pub fn board_from_bytes(bytes: &[u8]) -> JsValue {
+    match uttt_core::board_from_bytes(bytes).filter(|&(b, _)| engine::check_structure(b).is_ok()) {
+        Some((b, side)) => json_value(&serde_json::json!({
+            "board": board_string(b),
+            "side": if side { "o" } else { "x" },
+        })),
+        None => json_value(&serde_json::json!({ "error": "bytes invalid" })),
+    }
+}
+
+// Checks a board for consistency, returning `ok` or `error <description>`.
+// The board may be given either as a board string, or as the raw values
+// of its three `u64` components (as accepted by `serialise_board`).
+#[wasm_bindgen]
+pub fn check_invariants(board: &str) -> Vec<String> {
+    let response = match board_from_values(board).or_else(|| board_from_string(board)) {
+        Some(b) => match engine::check_invariants(b) {
+            Ok(()) => "ok".to_string(),
+            Err(e) => format!("error {e}"),
+        },
+        None => "error board invalid".to_string(),
+    };
+    keywords(&response)
+}
+
+// Plays a move on a board, for frontends to learn its consequences without
+// comparing board strings. `side` is `true` if X is the one moving.
+// Returns an object with the new `board` string and an array of `events`, each
+// an object with a `type` of `zone_won` (with `side` and `zone`), `zone_drawn`
+// (with `zone`), `free_move_granted` or `game_over` (with `result`).
+// Returns an object with an `error` description if the board or move is invalid,
+// or the move is illegal.
+#[wasm_bindgen]
+pub fn apply_move(board: &str, mv: &str, side: bool) -> JsValue {
+    let strict = OPTIONS.lock().unwrap().strict;
+    let result = parse_board(board, strict).and_then(|b| {
+        let m = move_from_string(mv).ok_or_else(|| "move invalid".to_string())?;
+        if !engine::generate_moves(b).any(|legal| legal == m) {
+            return Err("move illegal".to_string());
+        }
+        Ok(game::apply_move(b, m, !side))
+    });
+    match result {
+        Ok((b, events)) => json_value(&serde_json::json!({
+            "board": board_string(b),
+            "events": events,
+        })),
+        Err(e) => json_value(&serde_json::json!({ "error": e })),
+    }
+}
+
+// Breaks the static evaluation of a board down by zone, for shading each small
+// grid by who is ahead in it. Returns an object with `large`, the score of the
+// large grid, and `zones`, the nine scores of the small grids in index order,
+// with null for zones that are won or full. The scores are relative to `side`
+// (`true` for X), or to X if the `perspective` option is set to `x`. Unless the game
+// is over, they add up to the evaluation without the terms for the side to move,
+// which belong to no grid: the tempo bonus, the score of the zone it is sent to,
+// and the mobility bonus for its number of legal moves. Returns an object with
+// an `error` description if the board is invalid.
+#[wasm_bindgen]
+pub fn zone_evals(board: &str, side: bool) -> JsValue {
+    let options = OPTIONS.lock().unwrap().clone();
+    match parse_board(board, options.strict) {
+        Ok(b) => {
+            let (large, zones) = engine::zone_evals(b, search_tables(&options).as_ref());
+            // The components are from X's point of view, so are first made relative to `side`.
+            let relative = |eval: Eval| {
+                options.perspective.normalise(if side { eval } else { -eval }, side)
+            };
+            json_value(&serde_json::json!({
+                "large": relative(large),
+                "zones": zones.map(|eval| eval.map(relative)),
+            }))
+        }
+        Err(e) => json_value(&serde_json::json!({ "error": e })),
+    }
+}
+
+// Summarises each of the nine zones, for drawing badges over them. Returns an array
+// in index order (`nw` to `se`) of objects holding the `zone`'s name, its `status`,
+// which is `open`, `won_x`, `won_o` or `drawn` (full without a line), its number of
+// `x_marks` and `o_marks`, and whether it is `forced`, being the zone the side to move
+// must play in. Returns an object with an `error` description if the board is invalid.
+#[wasm_bindgen]
+pub fn zone_summary(board: &str) -> JsValue {
+    let strict = OPTIONS.lock().unwrap().strict;
+    match parse_board(board, strict) {
+        Ok(b) => json_value(&zones::zone_summary(b)),
+        Err(e) => json_value(&serde_json::json!({ "error": e })),
+    }
+}
+
+// The legal moves of the side to move as a mask of 81 bits, for highlighting playable
+// cells as often as every frame without building strings. It is returned as three
+// 32-bit words, least significant first, in which bit `9 * zone + cell` is set if that
+// cell (numbered from 0, row by row from the top left, as are the zones) is playable.
+// All bits are clear once the game is over. Returns an empty array if the board is invalid.
+#[wasm_bindgen]
+pub fn legal_move_mask(board: &str) -> Vec<u32> {
+    let strict = OPTIONS.lock().unwrap().strict;
+    match parse_board(board, strict) {
+        Ok(b) => {
+            let mask = engine::legal_move_mask(b);
+            vec![mask as u32, (mask >> 32) as u32, (mask >> 64) as u32]
+        }
+        Err(_) => Vec::new(),
+    }
+}
+
+// Counts the legal moves of the side to move without listing them, responding with
+// `moves <n>`, which is 0 once the game is over, or an error if the board is invalid.
+#[wasm_bindgen]
+pub fn count_legal_moves(board: &str) -> Vec<String> {
+    let strict = OPTIONS.lock().unwrap().strict;
+    let response = match parse_board(board, strict) {
+        Ok(b) => format!("moves {}", engine::count_legal_moves(b)),
+        Err(e) => format!("error {e}"),
+    };
+    keywords(&response)
+}
+
+// Describes a board in words for screen readers, such as
+// `X controls NW and C. O to move in the SE zone; cells SE/n and SE/e are open.`
+// The side to move is inferred from the number of marks.
+// Returns `error <description>` if the board is invalid.
+#[wasm_bindgen]
+pub fn describe_board(board: &str) -> String {
+    set_panic_hook();
+    let strict = OPTIONS.lock().unwrap().strict;
+    match parse_board(board, strict) {
+        Ok(b) => describe::describe_board(b),
+        Err(e) => format!("error {e}"),
+    }
+}
+
+// Renders a board as a standalone SVG image, for share images and previews.
+// `options_json` is an object with any of `size` (the side length in pixels),
+// `last_move` (a move string, whose cell is shaded) and `highlight_zone`
+// (whether the zones that may be played in are shaded, which is the default),
+// or an empty string for the defaults.
+// Returns `error <description>` if either argument is invalid.
+#[cfg(feature = "render")]
+#[wasm_bindgen]
+pub fn render_svg(board: &str, options_json: &str) -> String {
+    set_panic_hook();
+    let strict = OPTIONS.lock().unwrap().strict;
+    let result = parse_board(board, strict).and_then(|b| {
+        let options = SvgOptions::from_json(options_json).map_err(|e| e.to_string())?;
+        Ok(render::render_svg(b, &options))
+    });
+    result.unwrap_or_else(|e| format!("error {e}"))
+}
+
+// Returns the Zobrist hash of a board as a 16-digit hexadecimal string,
+// suitable as a stable key for caching analysis results.
+// Boards with a corrected zone field hash the same as their corrected form.
+#[wasm_bindgen]
+pub fn hash_board(board: &str) -> String {
+    if let Some(b) = board_from_string(board).map(engine::correct_zone) {
+        format!("{:016x}", zobrist::hash_board(b))
     } else {
         "invalid".to_string()
     }
 }
+
+// Applies one of the eight symmetries of the board, given by name (`identity`,
+// `rotate-90`, `rotate-180` or `rotate-270`, clockwise, `reflect-vertical` or
+// `reflect-horizontal` in that axis, `reflect-diagonal` from the top left, or
+// `reflect-antidiagonal`) or by its index from 0 to 7 in that order. Marks, won zones
+// and the zone to play in are all moved, so the result is the same position seen
+// from another side. Returns the board string, or `error <description>`.
+#[wasm_bindgen]
+pub fn transform_board(board: &str, transform: &str) -> String {
+    let strict = OPTIONS.lock().unwrap().strict;
+    let result = parse_board(board, strict).and_then(|b| {
+        let t = symmetry::transform_from_name(transform).ok_or("transform invalid")?;
+        Ok(board_string(symmetry::transform_board(b, t)))
+    });
+    result.unwrap_or_else(|e| format!("error {e}"))
+}
+
+// Applies a symmetry, given as for `transform_board`, to a move, so that moves
+// can be shown on a transformed board. Returns the move, or `error <description>`.
+#[wasm_bindgen]
+pub fn transform_move(mv: &str, transform: &str) -> String {
+    let result = move_from_string(mv).ok_or("move invalid").and_then(|m| {
+        let t = symmetry::transform_from_name(transform).ok_or("transform invalid")?;
+        Ok(move_string(symmetry::transform_move(m, t)))
+    });
+    result.unwrap_or_else(|e| format!("error {e}"))
+}
+
+// Returns a hash of a game's moves, given as a transcript, as a 16-digit hexadecimal
+// string. Games which are symmetric variants of each other hash the same, so it serves
+// to recognise a game imported twice. Returns `invalid` if the transcript is not a game.
+#[wasm_bindgen]
+pub fn hash_game(moves: &str) -> String {
+    match Game::from_transcript(moves) {
+        Ok(game) => format!("{:016x}", zobrist::hash_game(game.moves())),
+        Err(_) => "invalid".to_string(),
+    }
+}
+
+// Parses a match record: a game with headers and annotations in a format modelled on PGN.
+// Returns an object with the record's `headers` as `[name, value]` pairs (other than
+// `Result`), its `moves` as a transcript, the `annotations` of each move as `{nags, comment}`,
+// the `comment` on the whole game, and the `result` (`1-0`, `0-1`, `1/2-1/2`, or null
+// if the game is unfinished). Returns an object with an `error` description if it is invalid.
+#[wasm_bindgen]
+pub fn parse_match_record(text: &str) -> JsValue {
+    match record::parse_record(text) {
+        Ok(r) => json_value(&record::record_json(&r)),
+        Err(e) => json_value(&serde_json::json!({ "error": e })),
+    }
+}
+
+// Writes a match record from a JSON object of the form returned by `parse_match_record`,
+// where only `moves` is required. Returns `error <description>` if it is invalid.
+#[wasm_bindgen]
+pub fn write_match_record(record_json: &str) -> String {
+    match record::record_from_json(record_json) {
+        Ok(r) => record::record_string(&r),
+        Err(e) => format!("error {e}"),
+    }
+}
+
+// Writes a line of NDJSON holding a game, given as a JSON object of the form returned by
+// `parse_match_record`, and its analysis (such as the result of `analyze_game`) as JSON,
+// or an empty string for none. Lines carry a schema `version` field, so that they can be
+// stored and read one at a time. Returns `error <description>` if an argument is invalid.
+#[wasm_bindgen]
+pub fn write_ndjson_line(record_json: &str, analysis_json: &str) -> String {
+    let analysis = match analysis_json.trim() {
+        "" => Ok(None),
+        json => serde_json::from_str(json).map(Some).map_err(|_| "analysis invalid"),
+    };
+    let entry = analysis.and_then(|analysis| {
+        Ok(ndjson::GameEntry {
+            record: record::record_from_json(record_json)?,
+            analysis,
+        })
+    });
+    match entry {
+        Ok(entry) => ndjson::entry_line(&entry),
+        Err(e) => format!("error {e}"),
+    }
+}
+
+// Reads a line of NDJSON written by `write_ndjson_line`, returning an object with
+// the game as `record` (in the form returned by `parse_match_record`) and its `analysis`,
+// or null if it has none. Returns an object with an `error` description if it is invalid.
+#[wasm_bindgen]
+pub fn parse_ndjson_line(line: &str) -> JsValue {
+    match ndjson::parse_entry_line(line) {
+        Ok(entry) => json_value(&serde_json::json!({
+            "record": record::record_json(&entry.record),
+            "analysis": entry.analysis,
+        })),
+        Err(e) => json_value(&serde_json::json!({ "error": e })),
+    }
+}
+
+// Saves a game, given as a transcript, with its analysis as a JSON array of entries in
+// the form returned by `analyze_game`, of some or all of its moves. Returns the annotated
+// game as a line of JSON carrying a `version` field, which `load_analysis` reads back
+// without searching again, or `error <description>` if an argument is invalid.
+#[wasm_bindgen]
+pub fn save_analysis(moves: &str, analysis_json: &str) -> String {
+    let annotated = Game::from_transcript(moves).and_then(|game| {
+        let analysis = serde_json::from_str(analysis_json).map_err(|_| "analysis invalid")?;
+        annotated::AnnotatedGame::new(game, analysis)
+    });
+    match annotated {
+        Ok(annotated) => annotated::annotated_string(&annotated),
+        Err(e) => format!("error {e}"),
+    }
+}
+
+// Reads a game saved by `save_analysis`, returning an object with its `moves`
+// as a transcript and its `analysis`, an array with an entry for each analysed move
+// holding its `ply`, the `move` played, the `best` move, the search `depth`, the `score`
+// of the best move and the `played_score` of the move played (both relative to the side
+// to move), the `pv` of the best move and its `annotation` (as for `analyze_game`).
+// Returns an object with an `error` description if it is invalid.
+#[wasm_bindgen]
+pub fn load_analysis(text: &str) -> JsValue {
+    match annotated::parse_annotated(text) {
+        Ok(annotated) => json_value(&serde_json::json!({
+            "moves": annotated.game.transcript(),
+            "analysis": annotated.analysis,
+        })),
+        Err(e) => json_value(&serde_json::json!({ "error": e })),
+    }
+}
+
+// Merges two saves of the same game by `save_analysis`, such as an older save and
+// a deeper analysis made since, keeping the deeper analysis of each move (or that of
+// `fresh` if equally deep). Returns the merged save, or `error <description>`.
+#[wasm_bindgen]
+pub fn merge_analysis(saved: &str, fresh: &str) -> String {
+    let merged = annotated::parse_annotated(saved).and_then(|saved| {
+        annotated::merge(&saved, &annotated::parse_annotated(fresh)?)
+    });
+    match merged {
+        Ok(merged) => annotated::annotated_string(&merged),
+        Err(e) => format!("error {e}"),
+    }
+}
+
+// Converts a move list written by another implementation to a transcript in the engine's
+// notation, checking that it is a legal game. `notation` is `tuple` for
+// `(bigRow,bigCol,smallRow,smallCol)` tuples, `rowcol` for `row col` pairs of the 9x9 grid
+// (both counting from 0), `internal` for the engine's own, or `auto` to detect it.
+// Returns `notation <name> moves <move>...`, or an error description.
+#[wasm_bindgen]
+pub fn import_moves(text: &str, notation: &str) -> Vec<String> {
+    let notation = match notation {
+        "auto" => Ok(None),
+        name => Notation::from_name(name).map(Some).ok_or("notation unknown"),
+    };
+    let response = match notation.and_then(|n| notation::import_game(text, n)) {
+        Ok((n, g)) => format!("notation {} moves {}", n.name(), g.transcript()),
+        Err(e) => format!("error {e}"),
+    };
+    keywords(&response)
+}
+
+// Adds a match record (as for `parse_match_record`) to the game database,
+// returning `ok game <n>` with the number by which the game is known,
+// or an error description. A game already in the database, in the same or
+// a symmetric form, is not added again, and `duplicate game <n>` names the game
+// it repeats.
+#[cfg(feature = "book")]
+#[wasm_bindgen]
+pub fn gamedb_add(text: &str) -> Vec<String> {
+    let response = match record::parse_record(text).map(|r| GAMEDB.lock().unwrap().add_unique(r)) {
+        Ok(Ok(game)) => format!("ok game {game}"),
+        Ok(Err(game)) => format!("duplicate game {game}"),
+        Err(e) => format!("error {e}"),
+    };
+    keywords(&response)
+}
+
+// Lists the games in the database reaching a position, in any of its symmetric variants.
+// Returns an array with an object for each game, holding its number (`game`), the `ply`
+// at which it reaches the position, the `move` played from it (transformed to match
+// the board given, or null if the game ended there), its `result` (null if unfinished)
+// and its `headers`. Returns an object with an `error` description if the board is invalid.
+#[cfg(feature = "book")]
+#[wasm_bindgen]
+pub fn gamedb_games(board: &str) -> JsValue {
+    let strict = OPTIONS.lock().unwrap().strict;
+    match parse_board(board, strict) {
+        Ok(b) => json_value(&GAMEDB.lock().unwrap().games_reaching(b)),
+        Err(e) => json_value(&serde_json::json!({ "error": e })),
+    }
+}
+
+// Finds the games in the database reaching a position which matches a pattern: the 9 rows
+// of the 9x9 grid from the top, separated by `/`, with a character for each cell, which is
+// `x` or `o` for a mark of that side, `.` for an empty cell, `*` for a mark of either side,
+// and `?` for any cell. With `symmetric`, positions matching any rotation or reflection
+// of the pattern are found too. Returns an array with an object for each game, in the form
+// returned by `gamedb_games`, at the first matching position, with the `move` played from it.
+// Returns an object with an `error` description if the pattern is invalid.
+#[cfg(feature = "book")]
+#[wasm_bindgen]
+pub fn gamedb_search(pattern: &str, symmetric: bool) -> JsValue {
+    match Pattern::from_string(pattern) {
+        Ok(p) => json_value(&GAMEDB.lock().unwrap().search(&p, symmetric)),
+        Err(e) => json_value(&serde_json::json!({ "error": e })),
+    }
+}
+
+// Sums up the finished games in the database by the move played from a position,
+// returning an array with the game counts, results and average score for the side
+// that played each move, as for `opening_tree`, most played first.
+// Returns an object with an `error` description if the board is invalid.
+#[cfg(feature = "book")]
+#[wasm_bindgen]
+pub fn gamedb_moves(board: &str) -> JsValue {
+    let strict = OPTIONS.lock().unwrap().strict;
+    match parse_board(board, strict) {
+        Ok(b) => json_value(&GAMEDB.lock().unwrap().move_stats(b)),
+        Err(e) => json_value(&serde_json::json!({ "error": e })),
+    }
+}
+
+// Sums up the results of the finished games in the database by their opening.
+// Returns an object with the number of finished `games`, and arrays of statistics for
+// the `first_moves`, the `replies` (lines of two moves) and every opening line of up to
+// `max_plies` moves (`lines`). Each holds the `line` as a transcript, with symmetric lines
+// counted together, its length in `plies`, the game counts and results, and the `win_rate`
+// and average `score` for the side that played the last move of the line.
+// Returns an object with an `error` description if `max_plies` is invalid.
+#[cfg(feature = "book")]
+#[wasm_bindgen]
+pub fn gamedb_stats(max_plies: &str) -> JsValue {
+    match max_plies.parse::<usize>() {
+        Ok(plies) => json_value(&GAMEDB.lock().unwrap().opening_stats(plies)),
+        Err(_) => json_value(&serde_json::json!({ "error": "plies invalid" })),
+    }
+}
+
+// Returns a game in the database as a match record, or `error <description>`.
+#[cfg(feature = "book")]
+#[wasm_bindgen]
+pub fn gamedb_record(game: usize) -> String {
+    match GAMEDB.lock().unwrap().record(game) {
+        Some(r) => record::record_string(r),
+        None => "error game unknown".to_string(),
+    }
+}
+
+// Returns the game database as bytes, for keeping in browser storage.
+#[cfg(feature = "book")]
+#[wasm_bindgen]
+pub fn gamedb_export() -> Vec<u8> {
+    GAMEDB.lock().unwrap().to_bytes()
+}
+
+// Replaces the game database with one from bytes produced by `gamedb_export`,
+// returning `ok games <n>` or an error description.
+#[cfg(feature = "book")]
+#[wasm_bindgen]
+pub fn gamedb_import(bytes: &[u8]) -> Vec<String> {
+    let response = match GameDb::from_bytes(bytes) {
+        Ok(db) => {
+            let count = db.len();
+            *GAMEDB.lock().unwrap() = db;
+            format!("ok games {count}")
+        }
+        Err(e) => format!("error {e}"),
+    };
+    keywords(&response)
+}
+
+// Returns every game in the database as NDJSON, a line per game (see `write_ndjson_line`).
+#[cfg(feature = "book")]
+#[wasm_bindgen]
+pub fn gamedb_export_ndjson() -> String {
+    let db = GAMEDB.lock().unwrap();
+    let entries = db.records().iter().map(|r| ndjson::GameEntry {
+        record: r.clone(),
+        analysis: None,
+    });
+    entries.map(|entry| ndjson::entry_line(&entry) + "\n").collect()
+}
+
+// Adds the games of NDJSON text to the database, ignoring their analysis, and returns
+// `ok games <n> duplicates <m>` with the number added and the number skipped as
+// already in the database (as for `gamedb_add`), or an error naming the first invalid
+// line, in which case no games are added.
+#[cfg(feature = "book")]
+#[wasm_bindgen]
+pub fn gamedb_import_ndjson(text: &str) -> Vec<String> {
+    let entries: Result<Vec<_>, _> = ndjson::read_entries(text.as_bytes()).collect();
+    let response = match entries {
+        Ok(entries) => {
+            let mut db = GAMEDB.lock().unwrap();
+            let count = entries.len();
+            let added = entries.into_iter().filter(|e| db.add_unique(e.record.clone()).is_ok());
+            let added = added.count();
+            format!("ok games {added} duplicates {}", count - added)
+        }
+        Err(e) => format!("error {e}"),
+    };
+    keywords(&response)
+}